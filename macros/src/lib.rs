@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Expr, Ident, LitStr, Token};
+
+mod stylesheet;
+
+use stylesheet::Part;
+
+struct Argument {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Argument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+
+        Ok(Self { name, value })
+    }
+}
+
+struct Input {
+    template: LitStr,
+    arguments: Vec<Argument>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let template = input.parse()?;
+
+        let arguments = if input.is_empty() {
+            Vec::new()
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::<Argument, Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect()
+        };
+
+        Ok(Self {
+            template,
+            arguments,
+        })
+    }
+}
+
+// Parses a stylesheet at compile time and scopes it to the element, into a `crate::css::Stylesheet`.
+//
+// Rules can be nested, with `&` for the parent, and put in @media or @supports. Values are
+// interpolated as `${name}` with `name = value` arguments that implement `crate::css::CssValue`.
+//
+// ```ignore
+// css!(
+//     r#"
+//     display: flex;
+//     img { object-fit: ${fit}; }
+//     &:hover { opacity: 0.8; }
+//     "#,
+//     fit = Keyword::parse(self.fit)?,
+// )
+// ```
+#[proc_macro]
+pub fn css(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    match expand(input) {
+        Ok(m) => m.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: Input) -> syn::Result<TokenStream2> {
+    let parts = stylesheet::compile(&input.template.value())
+        .map_err(|e| syn::Error::new(input.template.span(), e))?;
+
+    let mut names = HashSet::new();
+    for m in input.arguments.iter() {
+        if !names.insert(m.name.to_string()) {
+            return Err(syn::Error::new(
+                m.name.span(),
+                format!("duplicate argument `{}`", m.name),
+            ));
+        }
+    }
+
+    let used = parts
+        .iter()
+        .filter_map(|m| match m {
+            Part::Argument(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    if let Some(m) = used.iter().find(|m| !names.contains(**m)) {
+        return Err(syn::Error::new(
+            input.template.span(),
+            format!("there is no argument named `{}`", m),
+        ));
+    }
+    if let Some(m) = input
+        .arguments
+        .iter()
+        .find(|m| !used.contains(m.name.to_string().as_str()))
+    {
+        return Err(syn::Error::new(m.name.span(), "argument never used"));
+    }
+
+    // Each argument is evaluated once, even if it is used more than once.
+    let variable = |name: &str| format_ident!("css_{}", name, span = Span::mixed_site());
+
+    let bindings = input.arguments.iter().map(|m| {
+        let name = variable(&m.name.to_string());
+        let value = &m.value;
+        quote! { let #name = crate::css::CssValue::to_css_value(&(#value)); }
+    });
+
+    let parts = parts.iter().map(|m| match m {
+        Part::Text(m) => quote! { crate::css::StylesheetPart::Text(#m) },
+        Part::Scope => quote! { crate::css::StylesheetPart::Scope },
+        Part::Argument(m) => {
+            let name = variable(m);
+            quote! { crate::css::StylesheetPart::Value(#name.clone()) }
+        }
+    });
+
+    Ok(quote! {
+        {
+            #(#bindings)*
+            crate::css::Stylesheet::from_parts(::std::vec![#(#parts),*])
+        }
+    })
+}
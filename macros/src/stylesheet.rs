@@ -0,0 +1,611 @@
+use std::fmt;
+
+// Markers in compiled text, templates cannot contain them as control characters are rejected.
+const SCOPE: char = '\u{0}';
+const ARGUMENT_START: char = '\u{1}';
+const ARGUMENT_END: char = '\u{2}';
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Error {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid CSS on line {}: {}", self.line, self.message)
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+// A part of a compiled stylesheet.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Part {
+    Text(String),
+    // The selector of the element, `.{class_name}`.
+    Scope,
+    // An interpolated value, by the name of the argument.
+    Argument(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Declaration { property: String, value: String },
+    Rule { selector: String, items: Vec<Item> },
+    // @media and @supports, which apply to the rules inside them.
+    Condition { prelude: String, items: Vec<Item> },
+    // @keyframes, which is copied as is.
+    Raw(String),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(template: &str) -> Self {
+        Self {
+            chars: template.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn error<S: Into<String>>(&self, message: S) -> Error {
+        let line = self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|m| **m == '\n')
+            .count();
+
+        Error {
+            // Templates usually start with a line break after r#".
+            line: line + 1,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn skip_comment(&mut self) -> Result<()> {
+        let start = self.pos;
+        self.pos += 2;
+
+        while !self.starts_with("*/") {
+            if self.peek().is_none() {
+                self.pos = start;
+                return Err(self.error("unterminated comment"));
+            }
+            self.pos += 1;
+        }
+        self.pos += 2;
+
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += 1,
+                Some('/') if self.starts_with("/*") => self.skip_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // Reads a quoted string, including the quotes.
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        let quote = self.chars[self.pos];
+        let mut s = quote.to_string();
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                Some('\\') => {
+                    s.push('\\');
+                    self.pos += 1;
+                    if let Some(c) = self.peek() {
+                        s.push(c);
+                        self.pos += 1;
+                    }
+                }
+                Some('\n') | None => {
+                    self.pos = start;
+                    return Err(self.error("unterminated string"));
+                }
+                Some(c) => {
+                    s.push(c);
+                    self.pos += 1;
+                    if c == quote {
+                        return Ok(s);
+                    }
+                }
+            }
+        }
+    }
+
+    // The first of `;`, `{` and `}` outside of strings, comments and brackets, which tells a
+    // declaration from a rule as selectors may contain colons.
+    fn next_delimiter(&self) -> Option<char> {
+        let mut depth = 0usize;
+        let mut quote = None;
+        let mut i = self.pos;
+
+        while let Some(&c) = self.chars.get(i) {
+            match (quote, c) {
+                (Some(q), '\\') if q != c => i += 1,
+                (Some(q), c) if q == c => quote = None,
+                (Some(_), _) => {}
+                (None, '"') | (None, '\'') => quote = Some(c),
+                // An argument, such as `${width}`.
+                (None, '$') if self.chars.get(i + 1) == Some(&'{') => {
+                    while i < self.chars.len() && self.chars[i] != '}' {
+                        i += 1;
+                    }
+                }
+                (None, '(') | (None, '[') => depth += 1,
+                (None, ')') | (None, ']') => depth = depth.saturating_sub(1),
+                (None, ';') | (None, '{') | (None, '}') if depth == 0 => return Some(c),
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    // Reads text up to one of the terminators, which is not consumed. Only values can have
+    // arguments.
+    fn text(&mut self, terminators: &[char], allow_arguments: bool) -> Result<String> {
+        let mut s = String::new();
+        let mut brackets = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if brackets.is_empty() && terminators.contains(&c) {
+                break;
+            }
+
+            match c {
+                '"' | '\'' => {
+                    let m = self.string()?;
+                    s.push_str(&m);
+                    continue;
+                }
+                '/' if self.starts_with("/*") => {
+                    self.skip_comment()?;
+                    s.push(' ');
+                    continue;
+                }
+                '$' if self.starts_with("${") => {
+                    if !allow_arguments {
+                        return Err(self.error("values can only be interpolated into declarations"));
+                    }
+
+                    self.pos += 2;
+                    let start = self.pos;
+                    while let Some(c) = self.peek() {
+                        if c == '}' {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                    if self.peek().is_none() {
+                        self.pos = start;
+                        return Err(self.error("unterminated `${`"));
+                    }
+
+                    let name = self.chars[start..self.pos].iter().collect::<String>();
+                    let name = name.trim();
+                    if !is_identifier(name) {
+                        return Err(self.error(format!("`{}` is not an argument name", name)));
+                    }
+                    s.push(ARGUMENT_START);
+                    s.push_str(name);
+                    s.push(ARGUMENT_END);
+
+                    self.pos += 1;
+                    continue;
+                }
+                '(' => brackets.push(')'),
+                '[' => brackets.push(']'),
+                ')' | ']' if brackets.pop() != Some(c) => {
+                    return Err(self.error(format!("unexpected `{}`", c)));
+                }
+                '{' | '}' | ';' => {
+                    return Err(self.error(format!("unexpected `{}`", c)));
+                }
+                c if c.is_control() && !c.is_whitespace() => {
+                    return Err(self.error("unexpected control character"));
+                }
+                _ => {}
+            }
+
+            s.push(c);
+            self.pos += 1;
+        }
+
+        if let Some(m) = brackets.last() {
+            return Err(self.error(format!("missing `{}`", m)));
+        }
+
+        Ok(s.trim().to_string())
+    }
+
+    fn block(&mut self, top_level: bool) -> Result<Vec<Item>> {
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_whitespace()?;
+
+            match self.peek() {
+                None if top_level => return Ok(items),
+                None => return Err(self.error("missing `}`")),
+                Some('}') if top_level => return Err(self.error("unexpected `}`")),
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                Some(';') => self.pos += 1,
+                Some('@') => items.push(self.at_rule()?),
+                Some(_) if self.next_delimiter() == Some('{') => items.push(self.rule()?),
+                Some(_) => items.push(self.declaration()?),
+            }
+        }
+    }
+
+    fn declaration(&mut self) -> Result<Item> {
+        let property = self.text(&[':'], false)?;
+        if !is_property(&property) {
+            return Err(self.error(format!("`{}` is not a property", property)));
+        }
+        if self.peek() != Some(':') {
+            return Err(self.error(format!("missing `:` after `{}`", property)));
+        }
+        self.pos += 1;
+
+        let value = self.text(&[';', '}'], true)?;
+        if value.is_empty() {
+            return Err(self.error(format!("missing value of `{}`", property)));
+        }
+        if self.peek() == Some(';') {
+            self.pos += 1;
+        }
+
+        Ok(Item::Declaration { property, value })
+    }
+
+    fn rule(&mut self) -> Result<Item> {
+        let selector = self.text(&['{'], false)?;
+        if selector.is_empty() || split_selector(&selector).iter().any(|m| m.is_empty()) {
+            return Err(self.error(format!("`{}` is not a selector", selector)));
+        }
+        self.pos += 1;
+
+        let items = self.block(false)?;
+        Ok(Item::Rule { selector, items })
+    }
+
+    fn at_rule(&mut self) -> Result<Item> {
+        let start = self.pos;
+        let prelude = self.text(&['{', ';'], false)?;
+        let name = prelude[1..]
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .next()
+            .unwrap_or_default();
+
+        if self.peek() != Some('{') {
+            return Err(self.error(format!("`@{}` is not supported", name)));
+        }
+
+        match name {
+            "media" | "supports" => {
+                self.pos += 1;
+                let items = self.block(false)?;
+                Ok(Item::Condition { prelude, items })
+            }
+            "keyframes" | "-webkit-keyframes" => {
+                self.pos += 1;
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.peek() {
+                        Some('{') => depth += 1,
+                        Some('}') => depth -= 1,
+                        Some('$') if self.starts_with("${") => {
+                            return Err(
+                                self.error("values can only be interpolated into declarations")
+                            );
+                        }
+                        Some(c) if c.is_control() && !c.is_whitespace() => {
+                            return Err(self.error("unexpected control character"));
+                        }
+                        Some(_) => {}
+                        None => {
+                            self.pos = start;
+                            return Err(self.error(format!("missing `}}` of `@{}`", name)));
+                        }
+                    }
+                    self.pos += 1;
+                }
+
+                Ok(Item::Raw(self.chars[start..self.pos].iter().collect()))
+            }
+            _ => Err(self.error(format!("`@{}` is not supported", name))),
+        }
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_property(s: &str) -> bool {
+    let name = s
+        .strip_prefix("--")
+        .or_else(|| s.strip_prefix('-'))
+        .unwrap_or(s);
+
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// Splits a selector list on commas that are not inside brackets, such as in `:not(a, b)`.
+fn split_selector(selector: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(selector[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(selector[start..].trim());
+
+    parts
+}
+
+// Nested selectors are relative to their parent, or replace `&` with it.
+fn nest_selector(parents: &[String], selector: &str) -> Vec<String> {
+    let mut selectors = Vec::new();
+
+    for parent in parents {
+        for m in split_selector(selector) {
+            if m.contains('&') {
+                selectors.push(m.replace('&', parent));
+            } else {
+                selectors.push(format!("{} {}", parent, m));
+            }
+        }
+    }
+
+    selectors
+}
+
+fn flatten(items: &[Item], selectors: &[String], conditions: &[String], out: &mut String) {
+    let declarations = items
+        .iter()
+        .filter_map(|m| match m {
+            Item::Declaration { property, value } => Some(format!("{}: {};", property, value)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !declarations.is_empty() {
+        for m in conditions {
+            out.push_str(m);
+            out.push_str(" {\n");
+        }
+
+        out.push_str(&selectors.join(", "));
+        out.push_str(" { ");
+        out.push_str(&declarations.join(" "));
+        out.push_str(" }\n");
+
+        for _ in conditions {
+            out.push_str("}\n");
+        }
+    }
+
+    for item in items {
+        match item {
+            Item::Declaration { .. } => {}
+            Item::Rule { selector, items } => {
+                flatten(items, &nest_selector(selectors, selector), conditions, out);
+            }
+            Item::Condition { prelude, items } => {
+                let mut conditions = conditions.to_vec();
+                conditions.push(prelude.clone());
+                flatten(items, selectors, &conditions, out);
+            }
+            Item::Raw(m) => {
+                out.push_str(m);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+// Parses a template and scopes its rules to the element, with `${name}` for arguments.
+pub(crate) fn compile(template: &str) -> Result<Vec<Part>> {
+    let items = Parser::new(template).block(true)?;
+
+    let mut text = String::new();
+    flatten(&items, &[SCOPE.to_string()], &[], &mut text);
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut argument = None;
+
+    for c in text.chars() {
+        match (c, argument.as_mut()) {
+            (ARGUMENT_END, Some(_)) => {
+                parts.push(Part::Argument(argument.take().unwrap_or_default()));
+            }
+            (c, Some(m)) => m.push(c),
+            (SCOPE, None) | (ARGUMENT_START, None) => {
+                if !current.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut current)));
+                }
+                if c == SCOPE {
+                    parts.push(Part::Scope);
+                } else {
+                    argument = Some(String::new());
+                }
+            }
+            (c, None) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(Part::Text(current));
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Compiled CSS with `.x` as the scope and `{name}` for arguments.
+    fn compiled(template: &str) -> String {
+        compile(template)
+            .unwrap()
+            .into_iter()
+            .map(|m| match m {
+                Part::Text(m) => m,
+                Part::Scope => ".x".to_string(),
+                Part::Argument(m) => format!("{{{}}}", m),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_declarations() {
+        assert_eq!(
+            compiled("display: flex;\n  color: rgb(0, 0, 0) ;"),
+            ".x { display: flex; color: rgb(0, 0, 0); }\n"
+        );
+        assert_eq!(compiled("width: 100%"), ".x { width: 100%; }\n");
+        assert_eq!(compiled(""), "");
+    }
+
+    #[test]
+    fn test_nesting() {
+        assert_eq!(
+            compiled(
+                r#"
+                color: red;
+                &:hover { color: blue; }
+                .viewport img, a { display: block; }
+                tr { td:first-child { padding: 0; } }
+                "#
+            ),
+            ".x { color: red; }\n\
+             .x:hover { color: blue; }\n\
+             .x .viewport img, .x a { display: block; }\n\
+             .x tr td:first-child { padding: 0; }\n"
+        );
+    }
+
+    #[test]
+    fn test_conditions() {
+        assert_eq!(
+            compiled(
+                r#"
+                @media only screen and (max-width: 800px) {
+                    width: 100%;
+                    .title { display: none; }
+                }
+                @supports (backdrop-filter: blur(100px)) or (-webkit-backdrop-filter: blur(100px)) {
+                    -webkit-backdrop-filter: blur(100px)
+                }
+                "#
+            ),
+            "@media only screen and (max-width: 800px) {\n.x { width: 100%; }\n}\n\
+             @media only screen and (max-width: 800px) {\n.x .title { display: none; }\n}\n\
+             @supports (backdrop-filter: blur(100px)) or (-webkit-backdrop-filter: blur(100px)) {\n\
+             .x { -webkit-backdrop-filter: blur(100px); }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_keyframes() {
+        assert_eq!(
+            compiled("@keyframes spin { 0%, 100% { opacity: 0 } 50% { opacity: 1 } }"),
+            "@keyframes spin { 0%, 100% { opacity: 0 } 50% { opacity: 1 } }\n"
+        );
+    }
+
+    #[test]
+    fn test_strings_and_comments() {
+        assert_eq!(
+            compiled(r#"/* a; { */ content: "a; }"; input[type="range"] { margin: 0; }"#),
+            r#".x { content: "a; }"; }
+.x input[type="range"] { margin: 0; }
+"#
+        );
+    }
+
+    #[test]
+    fn test_arguments() {
+        assert_eq!(
+            compile("img { object-fit: ${fit}; }").unwrap(),
+            vec![
+                Part::Scope,
+                Part::Text(" img { object-fit: ".to_string()),
+                Part::Argument("fit".to_string()),
+                Part::Text("; }\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        for m in &[
+            "color red;",
+            "color: ;",
+            "color: red; }",
+            "a { color: red;",
+            "color: rgb(0, 0, 0;",
+            "color: red);",
+            "${selector} { color: red; }",
+            "${property}: red;",
+            "color: ${};",
+            "color: ${a b};",
+            "color: ${red;",
+            "@import \"a.css\";",
+            "@font-face { font-family: a; }",
+            "@keyframes a { from { opacity: 0 }",
+            "/* color: red;",
+            "content: \"a;",
+            ", a { color: red; }",
+            "color: \u{1}red;",
+        ] {
+            assert!(compile(m).is_err(), "{:?} should not compile", m);
+        }
+    }
+
+    #[test]
+    fn test_error_line() {
+        let e = compile("\n  color: red;\n  color red;\n").unwrap_err();
+        assert_eq!(e.line, 3);
+    }
+}
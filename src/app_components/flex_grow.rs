@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use crate::css::{css, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Debug, Clone)]
@@ -35,14 +36,15 @@ impl YieldStyle for FlexGrow {
         "fl-flex-grow".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        r#"
-        margin-top: 0 !important;
-        margin-bottom: 0 !important;
-        height: 1px;
-        flex-grow: 1;
-        width: 100%;
-        "#
-        .into()
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            margin-top: 0 !important;
+            margin-bottom: 0 !important;
+            height: 1px;
+            flex-grow: 1;
+            width: 100%;
+            "#
+        )
     }
 }
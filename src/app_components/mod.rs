@@ -1,13 +1,21 @@
 // mod section;
 mod flex_grow;
+mod office_nav;
+mod reauth_overlay;
 mod signin_bottom_links;
 mod signin_logo;
 mod signin_profile;
 mod signin_sub_title;
+mod signin_title;
 
 // pub(crate) use section::Section;
 pub(crate) use flex_grow::FlexGrow;
-pub(crate) use signin_bottom_links::BottomLinks;
-pub(crate) use signin_logo::SigninLogo;
+pub(crate) use office_nav::{OfficeNav, OfficeNavItem};
+pub(crate) use reauth_overlay::{ReauthOverlay, ReauthPurpose};
+pub(crate) use signin_bottom_links::{
+    BottomLinkItemPosition, BottomLinkItemProps, BottomLinks, BottomLinksProps,
+};
+pub(crate) use signin_logo::{SigninLogo, SigninLogoProps};
 pub(crate) use signin_profile::SigninProfile;
-pub(crate) use signin_sub_title::SigninSubTitle;
+pub(crate) use signin_sub_title::{SigninSubTitle, SigninSubTitleProps};
+pub(crate) use signin_title::{SigninTitle, SigninTitleProps};
@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::traits::YieldStyle;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OfficeNavItem {
+    pub label: &'static str,
+    pub href: String,
+    pub active: bool,
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct OfficeNavProps {
+    pub title: String,
+    pub items: Vec<OfficeNavItem>,
+}
+
+impl YieldStyle for OfficeNavProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-office-nav".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+            width: 220px;
+            flex-shrink: 0;
+            padding: 20px;
+            box-sizing: border-box;
+
+            .title {
+                font-size: 1.2rem;
+                margin-bottom: 20px;
+                padding-left: 15px;
+            }
+
+            a {
+                display: block;
+                height: 40px;
+                line-height: 40px;
+                padding-left: 15px;
+                border-radius: 500px;
+                color: rgb(100, 100, 100);
+                text-decoration: none;
+                outline: 0;
+                transition: background-color 0.20s;
+            }
+
+            a:hover {
+                background-color: rgba(150, 150, 150, 0.2);
+            }
+
+            a.active {
+                color: white;
+                background-color: rgb(92, 184, 230);
+            }
+
+            @media only screen and (max-width: 800px) {
+                width: 100%;
+                flex-direction: row;
+                overflow-x: auto;
+                align-items: center;
+
+                .title {
+                    display: none;
+                }
+
+                a {
+                    flex-shrink: 0;
+                    padding-right: 15px;
+                }
+            }
+            "#
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OfficeNav(OfficeNavProps);
+
+impl Component for OfficeNav {
+    type Message = ();
+    type Properties = OfficeNavProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self(props)
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.0 {
+            self.0 = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let items = self.0.items.iter().map(|m| {
+            html! {
+                <a href=m.href.clone() class=if m.active { "active" } else { "" }>{ m.label }</a>
+            }
+        });
+
+        html! {
+            <nav class=self.0.yield_style_class()>
+                <div class="title">{ self.0.title.clone() }</div>
+                { for items }
+            </nav>
+        }
+    }
+}
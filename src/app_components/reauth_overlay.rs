@@ -0,0 +1,309 @@
+use std::borrow::Cow;
+
+use web_sys::HtmlElement;
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::{BottomLinks, FlexGrow, SigninProfile, SigninSubTitle, SigninTitle};
+use crate::backend::{Client, SigninResident, SigninThrottle};
+use crate::components::{Button, Card, Input, InputMsg, InputType, InputValue, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::WeakComponentLink;
+use crate::services::{Viewport, ViewportState};
+use crate::traits::YieldStyle;
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ReauthPurpose {
+    // The office has been locked after a period of inactivity.
+    Lock,
+    // A sensitive action requires the password to be entered again.
+    Sudo(Cow<'static, str>),
+    // The resident has signed out in another tab and has to sign in again.
+    SignedOut,
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct ReauthOverlayProps {
+    pub client: Client,
+    pub resident: SigninResident,
+    pub purpose: ReauthPurpose,
+
+    #[prop_or_else(Callback::noop)]
+    pub onauthenticated: Callback<()>,
+
+    // Sign out for Lock, give up the action for Sudo.
+    #[prop_or_else(Callback::noop)]
+    pub oncancel: Callback<()>,
+}
+
+impl YieldStyle for ReauthOverlayProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-reauth-overlay".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            position: fixed;
+            top: 0;
+            left: 0;
+            width: 100vw;
+            height: 100vh;
+            overflow-y: auto;
+            z-index: 1000;
+
+            display: flex;
+            flex-direction: column;
+            justify-content: center;
+            align-items: center;
+
+            &.keyboard {
+                justify-content: flex-start;
+            }
+
+            & > div > * {
+                margin-top: 12px;
+                margin-bottom: 12px;
+            }
+            "#
+        );
+
+        match self.purpose {
+            ReauthPurpose::Lock | ReauthPurpose::SignedOut => {
+                style += css!("background-color: rgb(244, 245, 249);");
+            }
+            ReauthPurpose::Sudo(_) => {
+                style += css!("background-color: rgba(0, 0, 0, 0.4);");
+            }
+        }
+
+        style
+    }
+}
+
+pub(crate) enum ReauthOverlayMsg {
+    PasswordInput(InputValue),
+    Submit,
+    Done(Result<()>),
+    Cancel,
+    ViewportChanged(ViewportState),
+    Ignore,
+}
+
+#[derive(Debug)]
+pub(crate) struct ReauthOverlay {
+    props: ReauthOverlayProps,
+    link: ComponentLink<Self>,
+    busy: bool,
+    throttled: bool,
+
+    password_input_link: WeakComponentLink<Input>,
+    password_input_val: Option<InputValue>,
+
+    root_ref: NodeRef,
+    // Keeps the password input above the virtual keyboard.
+    _viewport: Viewport,
+    viewport_state: ViewportState,
+}
+
+impl Component for ReauthOverlay {
+    type Message = ReauthOverlayMsg;
+    type Properties = ReauthOverlayProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let viewport = {
+            let link = link.clone();
+            Viewport::subscribe(move |m| link.send_message(ReauthOverlayMsg::ViewportChanged(m)))
+        };
+
+        Self {
+            props,
+            link,
+            busy: false,
+            throttled: false,
+
+            password_input_link: WeakComponentLink::default(),
+            password_input_val: None,
+
+            root_ref: NodeRef::default(),
+            viewport_state: viewport.state(),
+            _viewport: viewport,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ReauthOverlayMsg::PasswordInput(m) => {
+                self.password_input_val = Some(m);
+                false
+            }
+            ReauthOverlayMsg::Submit => self.on_submit(),
+            ReauthOverlayMsg::Done(m) => self.on_done(m),
+            ReauthOverlayMsg::Cancel => {
+                self.props.oncancel.emit(());
+                false
+            }
+            ReauthOverlayMsg::ViewportChanged(m) => {
+                let keyboard_changed = m.keyboard_visible != self.viewport_state.keyboard_visible;
+                self.viewport_state = m;
+                self.set_visible_height();
+                keyboard_changed
+            }
+            ReauthOverlayMsg::Ignore => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let (title, sub_title, action, cancel) = match self.props.purpose {
+            ReauthPurpose::SignedOut => return self.render_signed_out(),
+            ReauthPurpose::Lock => (
+                "管理局已鎖定".into(),
+                "由於長時間沒有操作，管理局已被鎖定。如要繼續，請輸入密碼並點擊「解鎖」。",
+                "解鎖",
+                "使用其它帳戶",
+            ),
+            ReauthPurpose::Sudo(ref m) => (
+                m.clone(),
+                "這是一項敏感操作，請再次輸入密碼來確認你的身份。",
+                "確認",
+                "取消",
+            ),
+        };
+
+        html! {
+            <div class=self.root_class() ref=self.root_ref.clone()>
+                <Card with_margin={ false } max_width={ "400px" } width={ "calc(100vw - 40px)" } height={ "500px" }>
+                    <SigninTitle>{ title }</SigninTitle>
+                    <SigninProfile resident={ self.props.resident.clone() } />
+                    <FlexGrow />
+                    <SigninSubTitle>{ if self.throttled { "嘗試次數過多，請稍後再試。" } else { sub_title } }</SigninSubTitle>
+                    <Input type_=InputType::Password onkeyup=self.password_callback() weak_link=self.password_input_link.clone()
+                        name="password" placeholder="密碼" width="100%" oninput=self.link.callback(ReauthOverlayMsg::PasswordInput)
+                        required=true minlength=8 autofocus=true disabled=self.busy />
+                    {
+                        if self.busy {
+                            html! { <Spinner /> }
+                        } else {
+                            html! { <Button width="100%" onclick=self.link.callback(|_| ReauthOverlayMsg::Submit)>{ action }</Button> }
+                        }
+                    }
+                    <BottomLinks>
+                        <span onclick=self.link.callback(|_| ReauthOverlayMsg::Cancel)>{ cancel }</span>
+                    </BottomLinks>
+                </Card>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            self.set_visible_height();
+        }
+    }
+}
+
+impl ReauthOverlay {
+    fn root_class(&self) -> String {
+        if self.viewport_state.keyboard_visible {
+            format!("{} keyboard", self.props.yield_style_class())
+        } else {
+            self.props.yield_style_class()
+        }
+    }
+
+    // 100vh includes the part covered by the virtual keyboard.
+    fn set_visible_height(&self) {
+        if let Some(m) = self.root_ref.cast::<HtmlElement>() {
+            m.style()
+                .set_property(
+                    "height",
+                    &format!("{}px", self.viewport_state.visible_height),
+                )
+                .unwrap();
+        }
+    }
+
+    fn render_signed_out(&self) -> Html {
+        html! {
+            <div class=self.root_class() ref=self.root_ref.clone()>
+                <Card with_margin={ false } max_width={ "400px" } width={ "calc(100vw - 40px)" } height={ "500px" }>
+                    <SigninTitle>{ "你已登出" }</SigninTitle>
+                    <SigninProfile resident={ self.props.resident.clone() } />
+                    <FlexGrow />
+                    <SigninSubTitle>{ "你已在其它分頁登出。如要繼續，請重新登入。" }</SigninSubTitle>
+                    <Button width="100%" onclick=self.link.callback(|_| ReauthOverlayMsg::Cancel)>{ "重新登入" }</Button>
+                </Card>
+            </div>
+        }
+    }
+
+    fn on_submit(&mut self) -> ShouldRender {
+        if self.busy {
+            return false;
+        }
+
+        if let Some(m) = self.password_input_link.borrow().as_ref() {
+            m.send_message(InputMsg::Validate);
+        }
+
+        let password = match self.password_input_val {
+            Some(ref m) if m.is_valid() => m.to_string(),
+            _ => return false,
+        };
+
+        self.busy = true;
+        self.throttled = false;
+
+        let client = self.props.client.clone();
+        self.link.send_future(async move {
+            ReauthOverlayMsg::Done(client.reauthenticate(&password).await)
+        });
+        true
+    }
+
+    fn on_done(&mut self, result: Result<()>) -> ShouldRender {
+        self.busy = false;
+
+        match result {
+            Ok(_) => {
+                self.props.onauthenticated.emit(());
+                return false;
+            }
+            Err(Error::Throttled(SigninThrottle::Locked)) => {
+                // The account is locked, only the sign in page can unlock it.
+                self.props.oncancel.emit(());
+                return false;
+            }
+            Err(Error::Throttled(_)) => {
+                self.throttled = true;
+            }
+            Err(e) => {
+                log::error!("Failed to reauthenticate: {}", e);
+                if let Some(m) = self.password_input_link.borrow().as_ref() {
+                    m.send_message(InputMsg::BecomeInvalid("密碼不正確".to_string()));
+                }
+            }
+        }
+
+        true
+    }
+
+    fn password_callback(&self) -> Callback<KeyboardEvent> {
+        self.link.callback(|e: KeyboardEvent| {
+            if e.key_code() == 13 {
+                ReauthOverlayMsg::Submit
+            } else {
+                ReauthOverlayMsg::Ignore
+            }
+        })
+    }
+}
@@ -3,6 +3,7 @@ use std::borrow::Cow;
 use yew::prelude::*;
 
 use super::SigninSubTitle;
+use crate::css::{css, Stylesheet};
 use crate::traits::YieldStyle;
 
 // Start Item
@@ -14,10 +15,10 @@ pub(crate) enum BottomLinkItemPosition {
 }
 
 impl BottomLinkItemPosition {
-    fn extra_style_str(&self) -> Cow<'static, str> {
+    fn extra_style(&self) -> Stylesheet {
         match self {
-            Self::First => "text-align: right;\n".into(),
-            Self::Last => "text-align: left;\n".into(),
+            Self::First => css!("text-align: right;"),
+            Self::Last => css!("text-align: left;"),
         }
     }
 }
@@ -25,8 +26,8 @@ impl BottomLinkItemPosition {
 #[derive(Properties, Clone, Debug, PartialEq)]
 pub(crate) struct BottomLinkItemProps {
     #[prop_or_default]
-    children: Children,
-    position: BottomLinkItemPosition,
+    pub children: Children,
+    pub position: BottomLinkItemPosition,
 }
 
 #[derive(Debug, Clone)]
@@ -66,26 +67,27 @@ impl YieldStyle for BottomLinkItemProps {
         "fl-bottom-link-item".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = r#"
-        min-width: 120px;
-        padding-left: 5px;
-        padding-right: 5px;
-
-        font-size: 0.9rem;
-        text-align: center;
-        color: rgb(100, 100, 100);
-        "#
-        .to_string();
-        style_string.push_str(&self.position.extra_style_str());
-        style_string.into()
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            min-width: 120px;
+            padding-left: 5px;
+            padding-right: 5px;
+
+            font-size: 0.9rem;
+            text-align: center;
+            color: rgb(100, 100, 100);
+            "#
+        );
+        style += self.position.extra_style();
+        style
     }
 }
 // End Item
 
 #[derive(Properties, Clone, Debug, PartialEq)]
 pub(crate) struct BottomLinksProps {
-    children: Children,
+    pub children: Children,
 }
 
 #[derive(Debug, Clone)]
@@ -133,28 +135,29 @@ impl Component for BottomLinks {
         }
 
         html! {
-            <div class=self.yield_style_class()>
+            <div class=self.0.yield_style_class()>
                 { children }
             </div>
         }
     }
 }
 
-impl YieldStyle for BottomLinks {
+impl YieldStyle for BottomLinksProps {
     fn element_name(&self) -> Cow<'static, str> {
         "fl-bottom-links".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        r#"
-        width: 100%;
-        cursor: default;
-
-        display: flex;
-        flex-direction: row;
-        justify-content: center;
-        align-items: center;
-        "#
-        .into()
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            width: 100%;
+            cursor: default;
+
+            display: flex;
+            flex-direction: row;
+            justify-content: center;
+            align-items: center;
+            "#
+        )
     }
 }
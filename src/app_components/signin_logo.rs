@@ -2,11 +2,16 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use crate::components::ProgressiveImage;
+use crate::css::{css, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Clone, Debug, PartialEq)]
 pub(crate) struct SigninLogoProps {
     pub src: String,
+    // Versions of the logo for high density screens, see ProgressiveImage.
+    #[prop_or_default]
+    pub densities: Vec<(f64, String)>,
     #[prop_or(80)]
     pub height: u64,
 }
@@ -16,20 +21,17 @@ impl YieldStyle for SigninLogoProps {
         "fl-signin-logo".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        format!(
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
             r#"
-            background-image: url({url});
-            height: {height}px;
-            width: 100%;
-            background-repeat: no-repeat;
-            background-size: contain;
-            background-position: center;
-            "#,
-            url = &self.src,
-            height = self.height
+                height: var(--fl-signin-logo-height);
+                width: 100%;
+                "#
         )
-        .into()
+    }
+
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![("height", Some(format!("{}px", self.height)))]
     }
 }
 
@@ -37,13 +39,18 @@ impl YieldStyle for SigninLogoProps {
 pub(crate) struct SigninLogo {
     props: SigninLogoProps,
     link: ComponentLink<Self>,
+    root_ref: NodeRef,
 }
 
 impl Component for SigninLogo {
     type Message = ();
     type Properties = SigninLogoProps;
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        Self { props, link }
+        Self {
+            props,
+            link,
+            root_ref: NodeRef::default(),
+        }
     }
 
     fn update(&mut self, _msg: Self::Message) -> ShouldRender {
@@ -62,8 +69,13 @@ impl Component for SigninLogo {
 
     fn view(&self) -> Html {
         html! {
-            <div class=self.props.yield_style_class()>
+            <div ref=self.root_ref.clone() class=self.props.yield_style_class()>
+                <ProgressiveImage src=self.props.src.clone() densities=self.props.densities.clone() fit="contain" />
             </div>
         }
     }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.props.apply_style_vars(&self.root_ref);
+    }
 }
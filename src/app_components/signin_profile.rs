@@ -4,6 +4,7 @@ use yew::prelude::*;
 
 use crate::backend::SigninResident;
 use crate::components::Avatar;
+use crate::css::{css, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Clone, Debug, PartialEq)]
@@ -17,22 +18,23 @@ impl YieldStyle for SigninProfileProps {
         "fl-signin-profile".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        r#"
-            display: block;
-            text-align: center;
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+                display: block;
+                text-align: center;
 
-            .name {
-                font-size: 0.9rem;
-                color: rgb(100, 100, 100);
-            }
+                .name {
+                    font-size: 0.9rem;
+                    color: rgb(100, 100, 100);
+                }
 
-            .display-name {
-                font-size: 1rem;
-                color: black;
-            }
-            "#
-        .into()
+                .display-name {
+                    font-size: 1rem;
+                    color: black;
+                }
+                "#
+        )
     }
 }
 
@@ -65,7 +67,7 @@ impl Component for SigninProfile {
     fn view(&self) -> Html {
         html! {
             <div class=self.props.yield_style_class()>
-                <Avatar src={ self.props.resident.avatar_url() } />
+                <Avatar src={ self.props.resident.avatar_url(100) } blurhash={ self.props.resident.avatar_blurhash.clone() } name={ self.props.resident.display_name().to_string() } />
                 <div class="name">{ self.props.resident.name.clone() }</div>
                 {
                     if let Some(ref m) = self.props.resident.display_name {
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use crate::css::{self, css, Keyword, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Clone, Debug, PartialEq)]
@@ -50,14 +51,18 @@ impl YieldStyle for SigninSubTitleProps {
         "fl-signin-sub-title".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = r#"
-        font-size: 0.9rem;
-        color: rgb(100, 100, 100);
-        "#
-        .to_string();
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            font-size: 0.9rem;
+            color: rgb(100, 100, 100);
+            "#
+        );
 
-        style_string.push_str(&format!("text-align: {};\n", self.text_align));
-        style_string.into()
+        if let Some(m) = css::checked("text-align", Keyword::parse(&self.text_align)) {
+            style += css!("text-align: ${text_align};", text_align = m);
+        }
+
+        style
     }
 }
@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::traits::YieldStyle;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct SigninTitleProps {
+    #[prop_or_default]
+    pub children: Children,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SigninTitle(SigninTitleProps);
+
+impl Component for SigninTitle {
+    type Message = ();
+    type Properties = SigninTitleProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self(props)
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.0 {
+            self.0 = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.0.yield_style_class()>
+                { self.0.children.clone() }
+            </div>
+        }
+    }
+}
+
+impl YieldStyle for SigninTitleProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-signin-title".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!("font-size: 1.5rem;")
+    }
+}
@@ -2,6 +2,7 @@ use yew::prelude::*;
 
 use crate::manifest::Manifest;
 
+mod office;
 mod signin;
 
 #[derive(Clone, Properties, Debug, PartialEq)]
@@ -15,4 +16,5 @@ impl From<Manifest> for AppProps {
     }
 }
 
-pub(crate) use signin::{SigninApp, SigninAppProps, SigninSection};
+pub(crate) use office::OfficeApp;
+pub(crate) use signin::{SigninApp, SigninAppProps, SigninSection, SIGNIN_FOOTER};
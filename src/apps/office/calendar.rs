@@ -0,0 +1,495 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::OfficePage;
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Post, PostStatus, PostSummary};
+use crate::components::{Card, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{now_timestamp, window};
+use crate::timezone::{LocalDateTime, TimeZone};
+use crate::traits::YieldStyle;
+use crate::Result;
+
+const WEEKDAYS: [&str; 7] = ["日", "一", "二", "三", "四", "五", "六"];
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct CalendarPageProps {
+    pub client: Client,
+    // Days are divided in this timezone.
+    pub timezone: String,
+    // Whether scheduled posts can be moved to another day.
+    pub can_schedule: bool,
+}
+
+impl YieldStyle for CalendarPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-calendar-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .header {
+                display: flex;
+                flex-direction: row;
+                align-items: center;
+                width: 100%;
+                margin-bottom: 10px;
+            }
+
+            .header h3 {
+                flex-grow: 1;
+                margin: 0;
+                font-weight: normal;
+            }
+
+            .header .nav {
+                padding: 5px 10px;
+                margin-left: 5px;
+                border-radius: 500px;
+                color: rgb(100, 100, 100);
+                cursor: pointer;
+            }
+
+            .header .nav:hover {
+                background-color: rgba(150, 150, 150, 0.2);
+            }
+
+            .grid {
+                display: grid;
+                grid-template-columns: repeat(7, minmax(0, 1fr));
+                width: 100%;
+                border-top: 1px solid rgba(150, 150, 150, 0.2);
+                border-left: 1px solid rgba(150, 150, 150, 0.2);
+            }
+
+            .grid > div {
+                border-right: 1px solid rgba(150, 150, 150, 0.2);
+                border-bottom: 1px solid rgba(150, 150, 150, 0.2);
+            }
+
+            .weekday {
+                padding: 5px;
+                text-align: center;
+                font-size: 0.8rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .day {
+                min-height: 100px;
+                padding: 5px;
+                box-sizing: border-box;
+            }
+
+            .day.today .date {
+                color: white;
+                background-color: rgb(92, 184, 230);
+            }
+
+            .day.drop-target {
+                background-color: rgba(92, 184, 230, 0.1);
+            }
+
+            .date {
+                display: inline-block;
+                min-width: 24px;
+                line-height: 24px;
+                text-align: center;
+                border-radius: 500px;
+                font-size: 0.8rem;
+            }
+
+            .entry {
+                display: block;
+                margin-top: 3px;
+                padding: 2px 5px;
+                border-radius: 3px;
+                font-size: 0.8rem;
+                color: white;
+                text-decoration: none;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }
+
+            .entry.published {
+                background-color: rgb(50, 191, 50);
+            }
+
+            .entry.scheduled {
+                background-color: rgb(92, 184, 230);
+                cursor: grab;
+            }
+
+            .timezone {
+                margin-top: 10px;
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum CalendarMsg {
+    Load,
+    Loaded(Result<Vec<PostSummary>>),
+
+    PrevMonth,
+    NextMonth,
+    ThisMonth,
+
+    DragStart(u64),
+    DragEnter(u32),
+    DragEnd,
+    Drop(u32),
+    Rescheduled(Result<Post>),
+
+    Ignore,
+}
+
+#[derive(Debug)]
+pub(crate) struct CalendarPage {
+    props: CalendarPageProps,
+    link: ComponentLink<Self>,
+
+    timezone: TimeZone,
+    year: i32,
+    month: u32,
+
+    posts: Option<Vec<PostSummary>>,
+    failed: bool,
+
+    dragging: Option<u64>,
+    drop_target: Option<u32>,
+    rescheduling: bool,
+}
+
+impl Component for CalendarPage {
+    type Message = CalendarMsg;
+    type Properties = CalendarPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(CalendarMsg::Load);
+
+        let timezone = Self::timezone(&props.timezone);
+        let today = timezone.to_local(now_timestamp());
+
+        Self {
+            props,
+            link,
+
+            timezone,
+            year: today.year,
+            month: today.month,
+
+            posts: None,
+            failed: false,
+
+            dragging: None,
+            drop_target: None,
+            rescheduling: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            CalendarMsg::Load => {
+                let client = self.props.client.clone();
+                let (from, to) = self.month_range();
+                self.link.send_future(async move {
+                    CalendarMsg::Loaded(client.calendar_posts(from, to).await)
+                });
+                false
+            }
+            CalendarMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        self.posts = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load calendar: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            CalendarMsg::PrevMonth => {
+                if self.month == 1 {
+                    self.year -= 1;
+                    self.month = 12;
+                } else {
+                    self.month -= 1;
+                }
+                self.reload()
+            }
+            CalendarMsg::NextMonth => {
+                if self.month == 12 {
+                    self.year += 1;
+                    self.month = 1;
+                } else {
+                    self.month += 1;
+                }
+                self.reload()
+            }
+            CalendarMsg::ThisMonth => {
+                let today = self.timezone.to_local(now_timestamp());
+                self.year = today.year;
+                self.month = today.month;
+                self.reload()
+            }
+
+            CalendarMsg::DragStart(m) => {
+                self.dragging = Some(m);
+                false
+            }
+            CalendarMsg::DragEnter(m) => {
+                if self.dragging.is_none() || self.drop_target == Some(m) {
+                    return false;
+                }
+                self.drop_target = Some(m);
+                true
+            }
+            CalendarMsg::DragEnd => {
+                self.dragging = None;
+                self.drop_target = None;
+                true
+            }
+            CalendarMsg::Drop(day) => {
+                self.drop_target = None;
+                let id = match self.dragging.take() {
+                    Some(m) => m,
+                    None => return true,
+                };
+
+                let published_at = match self
+                    .posts
+                    .as_ref()
+                    .and_then(|m| m.iter().find(|m| m.id == id))
+                    .and_then(|m| m.published_at)
+                {
+                    Some(m) => m,
+                    None => return true,
+                };
+
+                // Keeps the time of day, only the date is changed.
+                let mut local = self.timezone.to_local(published_at);
+                if local.day == day {
+                    return true;
+                }
+                local.year = self.year;
+                local.month = self.month;
+                local.day = day;
+
+                let publish_at = self.timezone.to_timestamp(local);
+                if publish_at <= now_timestamp() {
+                    window()
+                        .alert_with_message("無法將文章排程至過去的時間。")
+                        .unwrap();
+                    return true;
+                }
+
+                let client = self.props.client.clone();
+                self.rescheduling = true;
+                self.link.send_future(async move {
+                    CalendarMsg::Rescheduled(client.schedule_post(id, publish_at).await)
+                });
+                true
+            }
+            CalendarMsg::Rescheduled(m) => {
+                self.rescheduling = false;
+                if let Err(e) = m {
+                    log::error!("Failed to reschedule post: {}", e);
+                    window()
+                        .alert_with_message("無法變更排程，請稍後再試。")
+                        .unwrap();
+                }
+                self.link.send_message(CalendarMsg::Load);
+                true
+            }
+
+            CalendarMsg::Ignore => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+
+        if props.timezone != self.props.timezone {
+            self.timezone = Self::timezone(&props.timezone);
+            self.link.send_message(CalendarMsg::Load);
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="header">
+                        <h3>{ format!("{} 年 {} 月", self.year, self.month) }</h3>
+                        { if self.rescheduling { html! { <Spinner /> } } else { html! {} } }
+                        <div class="nav" onclick=self.link.callback(|_| CalendarMsg::PrevMonth)>
+                            <i class="fas fa-chevron-left"></i>
+                        </div>
+                        <div class="nav" onclick=self.link.callback(|_| CalendarMsg::ThisMonth)>{ "本月" }</div>
+                        <div class="nav" onclick=self.link.callback(|_| CalendarMsg::NextMonth)>
+                            <i class="fas fa-chevron-right"></i>
+                        </div>
+                    </div>
+                    { self.render_grid() }
+                    <div class="timezone">{ format!("時區：{}", self.timezone.name()) }</div>
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl CalendarPage {
+    fn timezone(name: &str) -> TimeZone {
+        TimeZone::new(name).unwrap_or_else(|_| {
+            log::error!(
+                "Unknown timezone: {}, using the timezone of the browser.",
+                name
+            );
+            TimeZone::browser()
+        })
+    }
+
+    fn reload(&mut self) -> ShouldRender {
+        self.posts = None;
+        self.link.send_message(CalendarMsg::Load);
+        true
+    }
+
+    fn month_range(&self) -> (i64, i64) {
+        let start = LocalDateTime {
+            year: self.year,
+            month: self.month,
+            day: 1,
+            hour: 0,
+            minute: 0,
+        };
+
+        let end = if self.month == 12 {
+            LocalDateTime {
+                year: self.year + 1,
+                month: 1,
+                ..start
+            }
+        } else {
+            LocalDateTime {
+                month: self.month + 1,
+                ..start
+            }
+        };
+
+        (
+            self.timezone.to_timestamp(start),
+            self.timezone.to_timestamp(end),
+        )
+    }
+
+    fn render_grid(&self) -> Html {
+        let posts = match self.posts {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入行事曆，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        let leading = LocalDateTime::weekday(self.year, self.month, 1);
+        let days = LocalDateTime::days_in_month(self.year, self.month);
+
+        html! {
+            <div class="grid">
+                { for WEEKDAYS.iter().map(|m| html! { <div class="weekday">{ m }</div> }) }
+                { for (0..leading).map(|_| html! { <div></div> }) }
+                { for (1..=days).map(|m| self.render_day(m, posts)) }
+            </div>
+        }
+    }
+
+    fn render_day(&self, day: u32, posts: &[PostSummary]) -> Html {
+        let today = self.timezone.to_local(now_timestamp());
+        let is_today = today.year == self.year && today.month == self.month && today.day == day;
+
+        let mut entries = posts
+            .iter()
+            .filter_map(|m| {
+                let local = self.timezone.to_local(m.published_at?);
+                if local.year == self.year && local.month == self.month && local.day == day {
+                    Some((local, m))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(local, _)| *local);
+
+        let mut class = vec!["day"];
+        if is_today {
+            class.push("today");
+        }
+        if self.drop_target == Some(day) {
+            class.push("drop-target");
+        }
+
+        html! {
+            <div class=class
+                ondragenter=self.link.callback(move |_| CalendarMsg::DragEnter(day))
+                ondragover=self.link.callback(|e: DragEvent| {
+                    // Allows dropping.
+                    e.prevent_default();
+                    CalendarMsg::Ignore
+                })
+                ondrop=self.link.callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    CalendarMsg::Drop(day)
+                })>
+                <div class="date">{ day }</div>
+                { for entries.into_iter().map(|(local, m)| self.render_entry(local, m)) }
+            </div>
+        }
+    }
+
+    fn render_entry(&self, local: LocalDateTime, post: &PostSummary) -> Html {
+        let text = format!("{} {}", local.time_str(), post.title);
+        let href = OfficePage::EditPost(Some(post.id)).to_hash();
+
+        match post.status {
+            // Only posts that have not been published can be moved.
+            PostStatus::Scheduled if self.props.can_schedule => {
+                let id = post.id;
+                html! {
+                    <a class="entry scheduled" href=href title=text.clone() draggable="true"
+                        ondragstart=self.link.callback(move |e: DragEvent| {
+                            // Firefox does not start dragging without data.
+                            if let Some(m) = e.data_transfer() {
+                                let _ = m.set_data("text/plain", &id.to_string());
+                            }
+                            CalendarMsg::DragStart(id)
+                        })
+                        ondragend=self.link.callback(|_| CalendarMsg::DragEnd)>{ text }</a>
+                }
+            }
+            PostStatus::Scheduled => html! {
+                <a class="entry scheduled" href=href title=text.clone() draggable="false">{ text }</a>
+            },
+            _ => html! {
+                <a class="entry published" href=href title=text.clone() draggable="false">{ text }</a>
+            },
+        }
+    }
+}
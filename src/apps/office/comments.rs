@@ -0,0 +1,832 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::OfficePage;
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Comment, CommentQuery, CommentStatus, Paginated};
+use crate::components::{Button, ButtonColor, Card, Pagination, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{document, format_relative_time, window, EventListener};
+use crate::traits::YieldStyle;
+use crate::Result;
+
+// Key, description.
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("j / k", "下一則 / 上一則"),
+    ("x", "選取"),
+    ("a", "核准"),
+    ("s", "垃圾留言"),
+    ("d", "移至回收桶"),
+    ("u", "退回待審核"),
+    ("r", "回覆"),
+];
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct CommentsPageProps {
+    pub client: Client,
+    // Set while the page is behind an overlay, where shortcuts must not moderate comments.
+    #[prop_or(false)]
+    pub suspended: bool,
+}
+
+impl YieldStyle for CommentsPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-comments-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .tabs {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+                border-bottom: 1px solid rgba(150, 150, 150, 0.3);
+                margin-bottom: 10px;
+            }
+
+            .tabs a {
+                padding: 10px 15px;
+                color: rgb(100, 100, 100);
+                cursor: pointer;
+                border-bottom: 2px solid transparent;
+            }
+
+            .tabs a.active {
+                color: black;
+                border-bottom-color: rgb(92, 184, 230);
+            }
+
+            .bulk {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: center;
+                width: 100%;
+                margin-bottom: 10px;
+            }
+
+            .bulk > * {
+                margin-right: 5px;
+            }
+
+            .bulk .count {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .content {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+            }
+
+            .list {
+                flex-grow: 1;
+                min-width: 0;
+            }
+
+            .comment {
+                display: flex;
+                flex-direction: row;
+                align-items: flex-start;
+                padding: 10px;
+                border-left: 3px solid transparent;
+                border-bottom: 1px solid rgba(150, 150, 150, 0.2);
+                cursor: pointer;
+            }
+
+            .comment:hover {
+                background-color: rgb(245, 245, 245);
+            }
+
+            .comment.focused {
+                border-left-color: rgb(92, 184, 230);
+                background-color: rgba(92, 184, 230, 0.05);
+            }
+
+            .comment input {
+                margin-top: 4px;
+                margin-right: 10px;
+            }
+
+            .comment .summary {
+                flex-grow: 1;
+                min-width: 0;
+            }
+
+            .comment .excerpt {
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }
+
+            .sub {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .thread {
+                display: flex;
+                flex-direction: column;
+                flex-shrink: 0;
+                width: 360px;
+                margin-left: 20px;
+            }
+
+            .thread > * {
+                margin-bottom: 10px;
+            }
+
+            .thread a {
+                color: inherit;
+            }
+
+            .parent, .current {
+                padding: 10px;
+                border-left: 3px solid rgba(150, 150, 150, 0.3);
+            }
+
+            .current {
+                border-left-color: rgb(92, 184, 230);
+            }
+
+            .body {
+                margin-top: 5px;
+                white-space: pre-wrap;
+                word-break: break-word;
+            }
+
+            .buttons {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+            }
+
+            .buttons > * {
+                margin-right: 5px;
+                margin-bottom: 5px;
+            }
+
+            textarea {
+                width: 100%;
+                height: 100px;
+                box-sizing: border-box;
+                padding: 10px 15px;
+                border: 1px solid rgb(150, 150, 150);
+                border-radius: 20px;
+                font-size: 1rem;
+                font-family: inherit;
+                resize: vertical;
+                outline: 0;
+            }
+
+            textarea:focus {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .shortcuts {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .shortcuts kbd {
+                display: inline-block;
+                min-width: 40px;
+                font-family: inherit;
+                color: rgb(100, 100, 100);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum CommentsMsg {
+    Load,
+    Loaded(Result<Paginated<Comment>>),
+    Tab(CommentStatus),
+    Page(u64),
+
+    Focus(usize),
+    // Moves the focus by the offset.
+    Move(isize),
+    ContextLoaded(u64, Result<Vec<Comment>>),
+
+    ToggleSelect(u64),
+    ToggleSelectAll,
+    ToggleFocusedSelect,
+
+    // Moderates the focused comment.
+    Moderate(CommentStatus),
+    ModerateSelected(CommentStatus),
+    Moderated(Result<()>),
+
+    StartReply,
+    ReplyInput(String),
+    SendReply,
+    CancelReply,
+    Replied(Result<Comment>),
+}
+
+#[derive(Debug)]
+pub(crate) struct CommentsPage {
+    props: CommentsPageProps,
+    link: ComponentLink<Self>,
+
+    query: CommentQuery,
+    comments: Option<Paginated<Comment>>,
+    failed: bool,
+
+    // Index of the comment in the current page.
+    focused: Option<usize>,
+    // The comments the focused one replies to.
+    context: Option<(u64, Vec<Comment>)>,
+    selected: HashSet<u64>,
+
+    reply: Option<String>,
+    reply_ref: NodeRef,
+    focus_reply: bool,
+    sending: bool,
+
+    // None while suspended.
+    keydown_listener: Option<EventListener>,
+}
+
+impl Component for CommentsPage {
+    type Message = CommentsMsg;
+    type Properties = CommentsPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(CommentsMsg::Load);
+
+        let keydown_listener = if props.suspended {
+            None
+        } else {
+            Some(Self::listen_keydown(link.clone()))
+        };
+
+        Self {
+            props,
+            link,
+
+            query: CommentQuery::default(),
+            comments: None,
+            failed: false,
+
+            focused: None,
+            context: None,
+            selected: HashSet::new(),
+
+            reply: None,
+            reply_ref: NodeRef::default(),
+            focus_reply: false,
+            sending: false,
+
+            keydown_listener,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            CommentsMsg::Load => {
+                let client = self.props.client.clone();
+                let query = self.query.clone();
+                self.link
+                    .send_future(async move { CommentsMsg::Loaded(client.comments(&query).await) });
+                false
+            }
+            CommentsMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        self.selected
+                            .retain(|id| m.items.iter().any(|c| c.id == *id));
+                        self.comments = Some(m);
+                        self.failed = false;
+                        self.focus(self.focused.unwrap_or(0));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load comments: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+            CommentsMsg::Tab(m) => {
+                if self.query.status == m {
+                    return false;
+                }
+                self.query.status = m;
+                self.query.page = 1;
+                self.reset();
+                self.link.send_message(CommentsMsg::Load);
+                true
+            }
+            CommentsMsg::Page(m) => {
+                self.query.page = m;
+                self.reset();
+                self.link.send_message(CommentsMsg::Load);
+                true
+            }
+
+            CommentsMsg::Focus(m) => {
+                self.focus(m);
+                true
+            }
+            CommentsMsg::Move(offset) => {
+                let index = match self.focused {
+                    Some(m) => (m as isize + offset).max(0) as usize,
+                    None => 0,
+                };
+                if Some(index) == self.focused || !self.focus(index) {
+                    return false;
+                }
+
+                // Keeps the focused comment on screen while moving with the keyboard.
+                if let Some(m) = document()
+                    .query_selector(&format!("[data-comment-index=\"{}\"]", index))
+                    .ok()
+                    .flatten()
+                {
+                    m.scroll_into_view_with_bool(false);
+                }
+                true
+            }
+            CommentsMsg::ContextLoaded(id, m) => {
+                match m {
+                    Ok(m) => self.context = Some((id, m)),
+                    Err(e) => {
+                        log::error!("Failed to load comment context: {}", e);
+                        self.context = Some((id, Vec::new()));
+                    }
+                }
+                true
+            }
+
+            CommentsMsg::ToggleSelect(id) => {
+                if !self.selected.remove(&id) {
+                    self.selected.insert(id);
+                }
+                true
+            }
+            CommentsMsg::ToggleSelectAll => {
+                let ids = self
+                    .comments
+                    .iter()
+                    .flat_map(|m| m.items.iter())
+                    .map(|m| m.id)
+                    .collect::<HashSet<_>>();
+
+                if !ids.is_empty() && self.selected == ids {
+                    self.selected.clear();
+                } else {
+                    self.selected = ids;
+                }
+                true
+            }
+            CommentsMsg::ToggleFocusedSelect => match self.focused_comment() {
+                Some(m) => {
+                    let id = m.id;
+                    self.update(CommentsMsg::ToggleSelect(id))
+                }
+                None => false,
+            },
+
+            CommentsMsg::Moderate(status) => match self.focused_comment() {
+                Some(m) => {
+                    let id = m.id;
+                    self.moderate(vec![id], status)
+                }
+                None => false,
+            },
+            CommentsMsg::ModerateSelected(status) => {
+                let ids = self.selected.iter().copied().collect::<Vec<_>>();
+                self.moderate(ids, status)
+            }
+            CommentsMsg::Moderated(m) => {
+                if let Err(e) = m {
+                    log::error!("Failed to moderate comments: {}", e);
+                    window()
+                        .alert_with_message("無法更新留言，請稍後再試。")
+                        .unwrap();
+                    self.link.send_message(CommentsMsg::Load);
+                    return false;
+                }
+
+                // Fills the page again once every comment on it has been moderated.
+                if let Some(ref m) = self.comments {
+                    if m.items.is_empty() && m.total > 0 {
+                        self.link.send_message(CommentsMsg::Load);
+                    }
+                }
+                false
+            }
+
+            CommentsMsg::StartReply => {
+                if self.focused_comment().is_none() {
+                    return false;
+                }
+                if self.reply.is_none() {
+                    self.reply = Some(String::new());
+                }
+                self.focus_reply = true;
+                true
+            }
+            CommentsMsg::ReplyInput(m) => {
+                self.reply = Some(m);
+                false
+            }
+            CommentsMsg::SendReply => {
+                let id = match self.focused_comment() {
+                    Some(m) => m.id,
+                    None => return false,
+                };
+                let body = match self.reply {
+                    Some(ref m) if !m.trim().is_empty() => m.clone(),
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                self.sending = true;
+                self.link.send_future(async move {
+                    CommentsMsg::Replied(client.reply_to_comment(id, &body).await)
+                });
+                true
+            }
+            CommentsMsg::CancelReply => {
+                self.reply = None;
+                true
+            }
+            CommentsMsg::Replied(m) => {
+                self.sending = false;
+                match m {
+                    Ok(_) => self.reply = None,
+                    Err(e) => {
+                        log::error!("Failed to reply to comment: {}", e);
+                        window()
+                            .alert_with_message("無法送出回覆，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            if props.suspended {
+                self.keydown_listener = None;
+            } else if self.keydown_listener.is_none() {
+                self.keydown_listener = Some(Self::listen_keydown(self.link.clone()));
+            }
+
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        if !self.focus_reply {
+            return;
+        }
+        self.focus_reply = false;
+
+        if let Some(m) = self.reply_ref.cast::<HtmlTextAreaElement>() {
+            let _ = m.focus();
+        }
+    }
+
+    fn view(&self) -> Html {
+        let tabs = CommentStatus::all().iter().map(|m| {
+            let status = *m;
+            html! {
+                <a class=if status == self.query.status { "active" } else { "" }
+                    onclick=self.link.callback(move |_| CommentsMsg::Tab(status))>{ m.label() }</a>
+            }
+        });
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="tabs">{ for tabs }</div>
+                    { self.render_content() }
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl CommentsPage {
+    fn listen_keydown(link: ComponentLink<Self>) -> EventListener {
+        EventListener::new(&document(), "keydown", move |e| {
+            let e = match e.dyn_ref::<KeyboardEvent>() {
+                Some(m) => m,
+                None => return,
+            };
+
+            if e.ctrl_key() || e.meta_key() || e.alt_key() || is_typing(e) {
+                return;
+            }
+
+            if let Some(m) = shortcut(&e.key()) {
+                e.prevent_default();
+                link.send_message(m);
+            }
+        })
+    }
+
+    fn reset(&mut self) {
+        self.comments = None;
+        self.focused = None;
+        self.context = None;
+        self.selected.clear();
+        self.reply = None;
+    }
+
+    fn focused_comment(&self) -> Option<&Comment> {
+        self.comments.as_ref()?.items.get(self.focused?)
+    }
+
+    // Returns false if there is no comment at the index.
+    fn focus(&mut self, index: usize) -> bool {
+        let comments = match self.comments {
+            Some(ref m) if !m.items.is_empty() => &m.items,
+            _ => {
+                self.focused = None;
+                self.context = None;
+                return false;
+            }
+        };
+
+        let index = index.min(comments.len() - 1);
+        let comment = &comments[index];
+        let previous = self.focused_comment().map(|m| m.id);
+        self.focused = Some(index);
+
+        if previous == Some(comment.id) {
+            return true;
+        }
+        self.reply = None;
+
+        match comment.parent_id {
+            Some(_) => {
+                let client = self.props.client.clone();
+                let id = comment.id;
+                self.context = None;
+                self.link.send_future(async move {
+                    CommentsMsg::ContextLoaded(id, client.comment_context(id).await)
+                });
+            }
+            None => self.context = Some((comment.id, Vec::new())),
+        }
+        true
+    }
+
+    // Takes the comments out of the list right away so triage is not held up by the server.
+    fn moderate(&mut self, ids: Vec<u64>, status: CommentStatus) -> ShouldRender {
+        if ids.is_empty() || status == self.query.status {
+            return false;
+        }
+
+        if let Some(ref mut m) = self.comments {
+            let before = m.items.len();
+            m.items.retain(|m| !ids.contains(&m.id));
+            m.total = m.total.saturating_sub((before - m.items.len()) as u64);
+        }
+        for id in ids.iter() {
+            self.selected.remove(id);
+        }
+        if let Some(m) = self.focused {
+            self.focus(m);
+        }
+
+        let client = self.props.client.clone();
+        self.link.send_future(async move {
+            CommentsMsg::Moderated(client.moderate_comments(&ids, status).await)
+        });
+        true
+    }
+
+    fn render_content(&self) -> Html {
+        let comments = match self.comments {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入留言，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        if comments.items.is_empty() {
+            return html! { <SigninSubTitle>{ "沒有留言" }</SigninSubTitle> };
+        }
+
+        let per_page = self.query.per_page;
+        let total_pages =
+            comments.total / per_page + if comments.total % per_page > 0 { 1 } else { 0 };
+
+        html! {
+            <>
+                { self.render_bulk_actions(comments) }
+                <div class="content">
+                    <div class="list">
+                        { for comments.items.iter().enumerate().map(|(i, m)| self.render_comment(i, m)) }
+                        <Pagination page=self.query.page total_pages=total_pages
+                            onchange=self.link.callback(CommentsMsg::Page) />
+                    </div>
+                    { self.render_thread() }
+                </div>
+            </>
+        }
+    }
+
+    fn render_bulk_actions(&self, comments: &Paginated<Comment>) -> Html {
+        let all_selected = comments.items.iter().all(|m| self.selected.contains(&m.id));
+        let disabled = self.selected.is_empty();
+
+        html! {
+            <div class="bulk">
+                <input type="checkbox" checked=all_selected
+                    onclick=self.link.callback(|_| CommentsMsg::ToggleSelectAll) />
+                { self.render_actions(disabled, CommentsMsg::ModerateSelected) }
+                <span class="count">{ format!("已選取 {} 則", self.selected.len()) }</span>
+            </div>
+        }
+    }
+
+    // Buttons that move comments to each of the other statuses.
+    fn render_actions<F>(&self, disabled: bool, f: F) -> Html
+    where
+        F: 'static + Fn(CommentStatus) -> CommentsMsg + Copy,
+    {
+        let button = |status: CommentStatus, label: &'static str, color: ButtonColor| {
+            if status == self.query.status {
+                return html! {};
+            }
+
+            html! {
+                <Button height="32px" color=color disabled=disabled
+                    onclick=self.link.callback(move |_| f(status))>{ label }</Button>
+            }
+        };
+
+        html! {
+            <>
+                { button(CommentStatus::Approved, "核准", ButtonColor::Green) }
+                { button(CommentStatus::Pending, "退回待審核", ButtonColor::Black) }
+                { button(CommentStatus::Spam, "垃圾留言", ButtonColor::Black) }
+                { button(CommentStatus::Trash, "移至回收桶", ButtonColor::Red) }
+            </>
+        }
+    }
+
+    fn render_comment(&self, index: usize, comment: &Comment) -> Html {
+        let id = comment.id;
+        let class = if self.focused == Some(index) {
+            "comment focused"
+        } else {
+            "comment"
+        };
+
+        html! {
+            <div class=class data-comment-index=index.to_string()
+                onclick=self.link.callback(move |_| CommentsMsg::Focus(index))>
+                <input type="checkbox" checked=self.selected.contains(&id)
+                    onclick=self.link.callback(move |e: MouseEvent| {
+                        e.stop_propagation();
+                        CommentsMsg::ToggleSelect(id)
+                    }) />
+                <div class="summary">
+                    <div>{ comment.author_name.clone() }</div>
+                    <div class="excerpt">{ comment.body.clone() }</div>
+                    <div class="sub">
+                        { format!("{} · {}", comment.post_title, format_relative_time(comment.created_at)) }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_thread(&self) -> Html {
+        let comment = match self.focused_comment() {
+            Some(m) => m,
+            None => return html! {},
+        };
+
+        let parents = match self.context {
+            Some((id, ref m)) if id == comment.id => html! {
+                { for m.iter().map(|m| html! {
+                    <div class="parent">
+                        <div class="sub">{ format!("{} · {}", m.author_name, format_relative_time(m.created_at)) }</div>
+                        <div class="body">{ m.body.clone() }</div>
+                    </div>
+                }) }
+            },
+            _ => html! { <Spinner /> },
+        };
+
+        let author = match comment.author_url {
+            Some(ref m) => html! {
+                <a href=m.clone() target="_blank" rel="noopener noreferrer">{ comment.author_name.clone() }</a>
+            },
+            None => html! { { comment.author_name.clone() } },
+        };
+
+        html! {
+            <div class="thread">
+                <a class="sub" href=OfficePage::EditPost(Some(comment.post_id)).to_hash()>{ comment.post_title.clone() }</a>
+                { parents }
+                <div class="current">
+                    <div>{ author }</div>
+                    <div class="sub">
+                        { format!("{} · {}", comment.author_email, format_relative_time(comment.created_at)) }
+                    </div>
+                    <div class="body">{ comment.body.clone() }</div>
+                </div>
+                <div class="buttons">
+                    { self.render_actions(false, CommentsMsg::Moderate) }
+                    {
+                        if self.reply.is_none() {
+                            html! {
+                                <Button height="32px" onclick=self.link.callback(|_| CommentsMsg::StartReply)>{ "回覆" }</Button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+                { self.render_reply() }
+                <div class="shortcuts">
+                    { for SHORTCUTS.iter().map(|(key, description)| html! {
+                        <div><kbd>{ key }</kbd>{ description }</div>
+                    }) }
+                </div>
+            </div>
+        }
+    }
+
+    fn render_reply(&self) -> Html {
+        let reply = match self.reply {
+            Some(ref m) => m,
+            None => return html! {},
+        };
+
+        html! {
+            <>
+                <textarea ref=self.reply_ref.clone() placeholder="回覆內容" value=reply.clone()
+                    oninput=self.link.callback(|e: InputData| CommentsMsg::ReplyInput(e.value))></textarea>
+                {
+                    if self.sending {
+                        html! { <Spinner /> }
+                    } else {
+                        html! {
+                            <div class="buttons">
+                                <Button height="32px" color=ButtonColor::Black
+                                    onclick=self.link.callback(|_| CommentsMsg::SendReply)>{ "送出" }</Button>
+                                <Button height="32px" onclick=self.link.callback(|_| CommentsMsg::CancelReply)>{ "取消" }</Button>
+                            </div>
+                        }
+                    }
+                }
+            </>
+        }
+    }
+}
+
+fn shortcut(key: &str) -> Option<CommentsMsg> {
+    Some(match key {
+        "j" | "ArrowDown" => CommentsMsg::Move(1),
+        "k" | "ArrowUp" => CommentsMsg::Move(-1),
+        "x" => CommentsMsg::ToggleFocusedSelect,
+        "a" => CommentsMsg::Moderate(CommentStatus::Approved),
+        "s" => CommentsMsg::Moderate(CommentStatus::Spam),
+        "d" => CommentsMsg::Moderate(CommentStatus::Trash),
+        "u" => CommentsMsg::Moderate(CommentStatus::Pending),
+        "r" => CommentsMsg::StartReply,
+        _ => return None,
+    })
+}
+
+// Shortcuts are left alone while typing into a field.
+fn is_typing(e: &KeyboardEvent) -> bool {
+    let target = match e.target().and_then(|m| m.dyn_into::<Element>().ok()) {
+        Some(m) => m,
+        None => return false,
+    };
+
+    match target.tag_name().as_str() {
+        "INPUT" | "TEXTAREA" | "SELECT" => true,
+        _ => target
+            .dyn_ref::<HtmlElement>()
+            .map(|m| m.is_content_editable())
+            .unwrap_or(false),
+    }
+}
@@ -0,0 +1,52 @@
+use yew::prelude::*;
+
+use crate::app_components::{SigninProfile, SigninTitle};
+use crate::backend::SigninResident;
+use crate::components::Card;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct DashboardPageProps {
+    pub resident: SigninResident,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DashboardPage {
+    props: DashboardPageProps,
+}
+
+impl Component for DashboardPage {
+    type Message = ();
+    type Properties = DashboardPageProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let name = self
+            .props
+            .resident
+            .display_name
+            .clone()
+            .unwrap_or_else(|| self.props.resident.name.clone());
+
+        html! {
+            <Card with_margin={ false } width={ "100%" } height={ "300px" }>
+                <SigninTitle>{ format!("歡迎回來，{}", name) }</SigninTitle>
+                <SigninProfile resident={ self.props.resident.clone() } />
+            </Card>
+        }
+    }
+}
@@ -0,0 +1,1011 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use wasm_bindgen::JsCast;
+use web_sys::{DataTransfer, File, FileList};
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use crate::app_components::SigninSubTitle;
+use crate::backend::{
+    upload_resumable, Client, Media, MediaQuery, MediaUpdate, MediaUpload, Paginated, UploadOptions,
+};
+use crate::components::{Button, ButtonColor, Card, Input, InputValue, Pagination, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{document, format_date, window, EventListener};
+use crate::imaging::{is_processable, ImageOptions};
+use crate::services::{ImageWorker, PendingUpload, UploadStore};
+use crate::traits::YieldStyle;
+use crate::{Error, Result};
+
+// Larger files are uploaded in chunks that can be resumed.
+const RESUMABLE_SIZE: f64 = 5.0 * 1024.0 * 1024.0;
+
+// Keys of pending uploads running in this tab, there may be more than one media library.
+static RUNNING_UPLOADS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+// Images are never uploaded with their metadata, they fail if there is no worker to strip it.
+async fn prepare_upload(file: File, worker: Option<ImageWorker>) -> Result<MediaUpload> {
+    if !is_processable(&file.type_()) {
+        return Ok(MediaUpload::from(file));
+    }
+
+    match worker {
+        Some(m) => m.process(&file).await,
+        None => Err(Error::Image(
+            "the image worker is not available to strip metadata".to_string(),
+        )),
+    }
+}
+
+// Kept in IndexedDB until the server has every byte, so it resumes after a reload.
+async fn upload_pending<F>(client: &Client, pending: PendingUpload, on_progress: F) -> Result<Media>
+where
+    F: Fn(f64),
+{
+    RUNNING_UPLOADS.lock().unwrap().insert(pending.key.clone());
+
+    // Uploads still work without IndexedDB, they just cannot be resumed after a reload.
+    let store = match UploadStore::open().await {
+        Ok(m) => Some(m),
+        Err(e) => {
+            log::warn!("Failed to open upload store: {}", e);
+            None
+        }
+    };
+
+    if let Some(ref m) = store {
+        if let Err(e) = m.put(&pending).await {
+            log::warn!("Failed to save pending upload: {}", e);
+        }
+    }
+
+    let on_created = |location: &str| {
+        let mut pending = pending.clone();
+        pending.location = Some(location.to_string());
+
+        if let Some(store) = store.clone() {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = store.put(&pending).await {
+                    log::warn!("Failed to save pending upload: {}", e);
+                }
+            });
+        }
+    };
+
+    let uploaded = async {
+        let location = upload_resumable(
+            client,
+            &pending.upload.file,
+            &pending.metadata(),
+            pending.location.clone(),
+            &UploadOptions::default(),
+            on_created,
+            on_progress,
+        )
+        .await?;
+
+        client
+            .complete_upload(&location, pending.upload.thumbnail.as_ref())
+            .await
+    }
+    .await;
+
+    if let (Ok(_), Some(m)) = (&uploaded, store) {
+        if let Err(e) = m.delete(&pending.key).await {
+            log::warn!("Failed to remove finished upload: {}", e);
+        }
+    }
+
+    RUNNING_UPLOADS.lock().unwrap().remove(&pending.key);
+    uploaded
+}
+
+fn files_of(list: Option<FileList>) -> Vec<File> {
+    match list {
+        Some(m) => (0..m.length()).filter_map(|i| m.get(i)).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn format_size(size: u64) -> String {
+    let size = size as f64;
+
+    if size < 1024.0 {
+        format!("{} B", size)
+    } else if size < 1024.0 * 1024.0 {
+        format!("{:.1} KB", size / 1024.0)
+    } else {
+        format!("{:.1} MB", size / 1024.0 / 1024.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Upload {
+    key: u64,
+    filename: String,
+    // Images are resized before uploading.
+    processing: bool,
+    // Set for uploads that can be resumed.
+    pending: Option<PendingUpload>,
+    // Between 0 and 1.
+    progress: f64,
+    failed: bool,
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct MediaLibraryProps {
+    pub client: Client,
+    pub image_options: ImageOptions,
+
+    // Shows an insert button for the selected file if set.
+    #[prop_or(None)]
+    pub onpick: Option<Callback<Media>>,
+}
+
+impl YieldStyle for MediaLibraryProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-media-library".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+            width: 100%;
+
+            .drop-zone {
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                justify-content: center;
+                width: 100%;
+                box-sizing: border-box;
+                padding: 20px;
+                margin-bottom: 10px;
+                border: 2px dashed rgb(200, 200, 200);
+                border-radius: 5px;
+                color: rgb(150, 150, 150);
+                transition: border-color 0.2s, background-color 0.2s;
+            }
+
+            .drop-zone.active {
+                border-color: rgb(92, 184, 230);
+                background-color: rgba(92, 184, 230, 0.1);
+            }
+
+            .drop-zone label {
+                color: rgb(92, 184, 230);
+                cursor: pointer;
+            }
+
+            .drop-zone input {
+                display: none;
+            }
+
+            .upload {
+                width: 100%;
+                margin-bottom: 5px;
+                font-size: 0.8rem;
+            }
+
+            .upload progress {
+                display: block;
+                width: 100%;
+                height: 4px;
+                margin-top: 3px;
+                border: 0;
+                border-radius: 2px;
+                background-color: rgba(150, 150, 150, 0.2);
+                overflow: hidden;
+
+                -webkit-appearance: none;
+                -moz-appearance: none;
+                appearance: none;
+            }
+
+            .upload progress::-webkit-progress-bar {
+                background-color: transparent;
+            }
+
+            .upload progress::-webkit-progress-value {
+                background-color: rgb(92, 184, 230);
+                transition: width 0.2s;
+            }
+
+            .upload progress::-moz-progress-bar {
+                background-color: rgb(92, 184, 230);
+            }
+
+            .upload.failed {
+                color: rgb(220, 53, 69);
+                cursor: pointer;
+            }
+
+            .upload.failed a {
+                text-decoration: underline;
+            }
+
+            .content {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+            }
+
+            .grid {
+                flex-grow: 1;
+                display: grid;
+                grid-template-columns: repeat(auto-fill, minmax(120px, 1fr));
+                grid-gap: 10px;
+                align-content: start;
+            }
+
+            .item {
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                padding: 5px;
+                border-radius: 5px;
+                border: 2px solid transparent;
+                cursor: pointer;
+                font-size: 0.8rem;
+                overflow: hidden;
+            }
+
+            .item.selected {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .thumbnail {
+                display: flex;
+                align-items: center;
+                justify-content: center;
+                width: 100%;
+                height: 100px;
+                background-color: rgb(245, 245, 245);
+                color: rgb(150, 150, 150);
+                font-size: 2rem;
+            }
+
+            .thumbnail img {
+                max-width: 100%;
+                max-height: 100%;
+                object-fit: contain;
+            }
+
+            .filename {
+                width: 100%;
+                margin-top: 5px;
+                text-align: center;
+                white-space: nowrap;
+                overflow: hidden;
+                text-overflow: ellipsis;
+            }
+
+            .details {
+                display: flex;
+                flex-direction: column;
+                flex-shrink: 0;
+                width: 280px;
+                margin-left: 20px;
+            }
+
+            .details > * {
+                margin-bottom: 10px;
+            }
+
+            .details img {
+                max-width: 100%;
+            }
+
+            .details .meta {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+                word-break: break-all;
+            }
+
+            .details .buttons {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+            }
+
+            .details .buttons > * {
+                margin-right: 5px;
+                margin-bottom: 5px;
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum MediaLibraryMsg {
+    Load,
+    Loaded(Result<Paginated<Media>>),
+    Page(u64),
+
+    DragOver(bool),
+    Upload(Vec<File>),
+    PendingLoaded(Result<Vec<PendingUpload>>),
+    Processed(u64, Option<PendingUpload>),
+    Resume(u64),
+    UploadProgress(u64, f64),
+    Uploaded(u64, Result<Media>),
+    DismissUpload(u64),
+
+    Select(u64),
+    AltInput(InputValue),
+    CaptionInput(InputValue),
+    Save,
+    Saved(Result<Media>),
+    Delete,
+    Deleted(u64, Result<()>),
+    Pick,
+}
+
+#[derive(Debug)]
+pub(crate) struct MediaLibrary {
+    props: MediaLibraryProps,
+    link: ComponentLink<Self>,
+
+    query: MediaQuery,
+    media: Option<Paginated<Media>>,
+    failed: bool,
+
+    drag_over: bool,
+    uploads: Vec<Upload>,
+    next_upload_key: u64,
+    // Started on the first image upload.
+    image_worker: Option<ImageWorker>,
+
+    selected: Option<u64>,
+    alt: String,
+    caption: String,
+    saving: bool,
+
+    _paste_listener: EventListener,
+}
+
+impl Component for MediaLibrary {
+    type Message = MediaLibraryMsg;
+    type Properties = MediaLibraryProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(MediaLibraryMsg::Load);
+        link.send_future(async {
+            let pending = match UploadStore::open().await {
+                Ok(m) => m.all().await,
+                Err(e) => Err(e),
+            };
+            MediaLibraryMsg::PendingLoaded(pending)
+        });
+
+        let paste_link = link.clone();
+        let paste_listener = EventListener::new(&document(), "paste", move |e| {
+            // ClipboardEvent is only available with the unstable apis of web-sys.
+            let files = files_of(
+                js_sys::Reflect::get(&e, &"clipboardData".into())
+                    .ok()
+                    .and_then(|m| m.dyn_into::<DataTransfer>().ok())
+                    .and_then(|m| m.files()),
+            );
+
+            // Pasting text is left to the browser.
+            if !files.is_empty() {
+                e.prevent_default();
+                paste_link.send_message(MediaLibraryMsg::Upload(files));
+            }
+        });
+
+        Self {
+            props,
+            link,
+
+            query: MediaQuery::default(),
+            media: None,
+            failed: false,
+
+            drag_over: false,
+            uploads: Vec::new(),
+            next_upload_key: 0,
+            image_worker: None,
+
+            selected: None,
+            alt: String::new(),
+            caption: String::new(),
+            saving: false,
+
+            _paste_listener: paste_listener,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            MediaLibraryMsg::Load => {
+                let client = self.props.client.clone();
+                let query = self.query.clone();
+                self.link.send_future(async move {
+                    MediaLibraryMsg::Loaded(client.media(&query).await)
+                });
+                false
+            }
+            MediaLibraryMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        self.media = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load media: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+            MediaLibraryMsg::Page(m) => {
+                self.query.page = m;
+                self.media = None;
+                self.link.send_message(MediaLibraryMsg::Load);
+                true
+            }
+
+            MediaLibraryMsg::DragOver(m) => {
+                if self.drag_over == m {
+                    return false;
+                }
+                self.drag_over = m;
+                true
+            }
+            MediaLibraryMsg::Upload(files) => {
+                self.drag_over = false;
+
+                for file in files {
+                    let key = self.next_upload_key;
+                    self.next_upload_key += 1;
+
+                    let worker = if is_processable(&file.type_()) {
+                        self.image_worker()
+                    } else {
+                        None
+                    };
+
+                    self.uploads.push(Upload {
+                        key,
+                        filename: file.name(),
+                        processing: worker.is_some(),
+                        pending: None,
+                        progress: 0.0,
+                        failed: false,
+                    });
+
+                    let client = self.props.client.clone();
+                    let link = self.link.clone();
+                    self.link.send_future(async move {
+                        let upload = match prepare_upload(file, worker).await {
+                            Ok(m) => m,
+                            Err(e) => return MediaLibraryMsg::Uploaded(key, Err(e)),
+                        };
+
+                        if upload.file.size() > RESUMABLE_SIZE {
+                            let pending = PendingUpload::new(upload);
+                            link.send_message(MediaLibraryMsg::Processed(
+                                key,
+                                Some(pending.clone()),
+                            ));
+
+                            let uploaded = upload_pending(&client, pending, move |m| {
+                                link.send_message(MediaLibraryMsg::UploadProgress(key, m))
+                            })
+                            .await;
+                            return MediaLibraryMsg::Uploaded(key, uploaded);
+                        }
+                        link.send_message(MediaLibraryMsg::Processed(key, None));
+
+                        let uploaded = client
+                            .upload_media(&upload, move |m| {
+                                link.send_message(MediaLibraryMsg::UploadProgress(key, m))
+                            })
+                            .await;
+                        MediaLibraryMsg::Uploaded(key, uploaded)
+                    });
+                }
+                true
+            }
+            MediaLibraryMsg::PendingLoaded(m) => {
+                let pending = match m {
+                    Ok(m) => m,
+                    Err(e) => {
+                        log::warn!("Failed to load pending uploads: {}", e);
+                        return false;
+                    }
+                };
+
+                let running = RUNNING_UPLOADS.lock().unwrap().clone();
+                for pending in pending.into_iter().filter(|m| !running.contains(&m.key)) {
+                    let key = self.next_upload_key;
+                    self.next_upload_key += 1;
+
+                    self.uploads.push(Upload {
+                        key,
+                        filename: pending.upload.filename.clone(),
+                        processing: false,
+                        pending: Some(pending),
+                        progress: 0.0,
+                        failed: false,
+                    });
+                    self.resume_upload(key);
+                }
+                true
+            }
+            MediaLibraryMsg::Processed(key, pending) => {
+                match self.uploads.iter_mut().find(|m| m.key == key) {
+                    Some(m) => {
+                        m.processing = false;
+                        m.pending = pending;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            MediaLibraryMsg::Resume(key) => {
+                self.resume_upload(key);
+                true
+            }
+            MediaLibraryMsg::UploadProgress(key, progress) => {
+                match self.uploads.iter_mut().find(|m| m.key == key) {
+                    Some(m) => {
+                        m.progress = progress;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            MediaLibraryMsg::Uploaded(key, m) => {
+                match m {
+                    Ok(m) => {
+                        self.uploads.retain(|m| m.key != key);
+                        if let Some(ref mut page) = self.media {
+                            page.items.insert(0, m);
+                            page.total += 1;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to upload file: {}", e);
+                        if let Some(m) = self.uploads.iter_mut().find(|m| m.key == key) {
+                            m.failed = true;
+                        }
+                    }
+                }
+                true
+            }
+            MediaLibraryMsg::DismissUpload(key) => {
+                let pending = self
+                    .uploads
+                    .iter()
+                    .find(|m| m.key == key)
+                    .and_then(|m| m.pending.clone());
+                self.uploads.retain(|m| m.key != key);
+
+                // Dismissed uploads are not resumed anymore.
+                if let Some(pending) = pending {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let deleted = match UploadStore::open().await {
+                            Ok(m) => m.delete(&pending.key).await,
+                            Err(e) => Err(e),
+                        };
+                        if let Err(e) = deleted {
+                            log::warn!("Failed to remove pending upload: {}", e);
+                        }
+                    });
+                }
+                true
+            }
+
+            MediaLibraryMsg::Select(id) => {
+                self.selected = Some(id);
+                if let Some((alt, caption)) = self
+                    .selected_media()
+                    .map(|m| (m.alt.clone(), m.caption.clone()))
+                {
+                    self.alt = alt;
+                    self.caption = caption;
+                }
+                true
+            }
+            MediaLibraryMsg::AltInput(m) => {
+                self.alt = m.into_string();
+                false
+            }
+            MediaLibraryMsg::CaptionInput(m) => {
+                self.caption = m.into_string();
+                false
+            }
+            MediaLibraryMsg::Save => {
+                let id = match self.selected {
+                    Some(m) if !self.saving => m,
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                let update = MediaUpdate {
+                    alt: self.alt.trim().to_string(),
+                    caption: self.caption.trim().to_string(),
+                };
+
+                self.saving = true;
+                self.link.send_future(async move {
+                    MediaLibraryMsg::Saved(client.update_media(id, &update).await)
+                });
+                true
+            }
+            MediaLibraryMsg::Saved(m) => {
+                self.saving = false;
+                match m {
+                    Ok(m) => {
+                        if let Some(item) = self
+                            .media
+                            .as_mut()
+                            .and_then(|page| page.items.iter_mut().find(|item| item.id == m.id))
+                        {
+                            *item = m;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to update media: {}", e);
+                        window()
+                            .alert_with_message("無法儲存檔案資訊，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+            MediaLibraryMsg::Delete => {
+                let id = match self.selected {
+                    Some(m) if !self.saving => m,
+                    _ => return false,
+                };
+
+                let confirmed = window()
+                    .confirm_with_message("確定要刪除此檔案嗎？使用此檔案的文章將無法顯示它。")
+                    .unwrap_or(false);
+                if !confirmed {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                self.saving = true;
+                self.link.send_future(async move {
+                    MediaLibraryMsg::Deleted(id, client.delete_media(id).await)
+                });
+                true
+            }
+            MediaLibraryMsg::Deleted(id, m) => {
+                self.saving = false;
+                match m {
+                    Ok(_) => {
+                        if let Some(ref mut page) = self.media {
+                            page.items.retain(|m| m.id != id);
+                            page.total = page.total.saturating_sub(1);
+                        }
+                        if self.selected == Some(id) {
+                            self.selected = None;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to delete media: {}", e);
+                        window()
+                            .alert_with_message("無法刪除檔案，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+            MediaLibraryMsg::Pick => {
+                if let (Some(onpick), Some(m)) = (self.props.onpick.as_ref(), self.selected_media())
+                {
+                    // Uses what has been typed even if it has not been saved.
+                    let mut m = m.clone();
+                    m.alt = self.alt.trim().to_string();
+                    m.caption = self.caption.trim().to_string();
+                    onpick.emit(m);
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()
+                ondragover=self.link.callback(|e: DragEvent| {
+                    // Allows dropping.
+                    e.prevent_default();
+                    MediaLibraryMsg::DragOver(true)
+                })
+                ondragleave=self.link.callback(|_| MediaLibraryMsg::DragOver(false))
+                ondrop=self.link.callback(|e: DragEvent| {
+                    e.prevent_default();
+                    MediaLibraryMsg::Upload(files_of(e.data_transfer().and_then(|m| m.files())))
+                })>
+                { self.render_drop_zone() }
+                { for self.uploads.iter().map(|m| self.render_upload(m)) }
+                { self.render_content() }
+            </div>
+        }
+    }
+}
+
+impl MediaLibrary {
+    fn image_worker(&mut self) -> Option<ImageWorker> {
+        if self.image_worker.is_none() {
+            match ImageWorker::new(self.props.image_options.clone()) {
+                Ok(m) => self.image_worker = Some(m),
+                Err(e) => log::error!("Failed to start image worker: {}", e),
+            }
+        }
+
+        self.image_worker.clone()
+    }
+
+    fn resume_upload(&mut self, key: u64) {
+        let upload = match self.uploads.iter_mut().find(|m| m.key == key) {
+            Some(m) => m,
+            None => return,
+        };
+        let pending = match upload.pending {
+            Some(ref m) => m.clone(),
+            None => return,
+        };
+        upload.failed = false;
+
+        let client = self.props.client.clone();
+        let link = self.link.clone();
+        self.link.send_future(async move {
+            let uploaded = upload_pending(&client, pending, move |m| {
+                link.send_message(MediaLibraryMsg::UploadProgress(key, m))
+            })
+            .await;
+            MediaLibraryMsg::Uploaded(key, uploaded)
+        });
+    }
+
+    fn selected_media(&self) -> Option<&Media> {
+        let id = self.selected?;
+        self.media.as_ref()?.items.iter().find(|m| m.id == id)
+    }
+
+    fn render_drop_zone(&self) -> Html {
+        let class = if self.drag_over {
+            "drop-zone active"
+        } else {
+            "drop-zone"
+        };
+
+        html! {
+            <div class=class>
+                <div><i class="fas fa-cloud-upload-alt"></i>{ " 將檔案拖曳或貼上至此處上傳，或" }</div>
+                <label>
+                    { "選擇檔案" }
+                    <input type="file" multiple=true onchange=self.link.callback(|e: ChangeData| match e {
+                        ChangeData::Files(m) => MediaLibraryMsg::Upload(files_of(Some(m))),
+                        _ => MediaLibraryMsg::Upload(Vec::new()),
+                    }) />
+                </label>
+            </div>
+        }
+    }
+
+    fn render_upload(&self, upload: &Upload) -> Html {
+        if upload.failed && upload.pending.is_some() {
+            let key = upload.key;
+            return html! {
+                <div class="upload failed">
+                    { format!("上傳 {} 時中斷（{:.0}%）。", upload.filename, upload.progress * 100.0) }
+                    <a onclick=self.link.callback(move |_| MediaLibraryMsg::Resume(key))>{ "繼續上傳" }</a>
+                    { "・" }
+                    <a onclick=self.link.callback(move |_| MediaLibraryMsg::DismissUpload(key))>{ "取消" }</a>
+                </div>
+            };
+        }
+
+        if upload.failed {
+            let key = upload.key;
+            return html! {
+                <div class="upload failed" onclick=self.link.callback(move |_| MediaLibraryMsg::DismissUpload(key))>
+                    { format!("無法上傳 {}，按此關閉。", upload.filename) }
+                </div>
+            };
+        }
+
+        if upload.processing {
+            return html! {
+                <div class="upload">
+                    <div>{ format!("{}（處理中）", upload.filename) }</div>
+                </div>
+            };
+        }
+
+        html! {
+            <div class="upload">
+                <div>{ format!("{}（{:.0}%）", upload.filename, upload.progress * 100.0) }</div>
+                <progress max="1" value=upload.progress.to_string()></progress>
+            </div>
+        }
+    }
+
+    fn render_content(&self) -> Html {
+        let media = match self.media {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入媒體庫，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        if media.items.is_empty() {
+            return html! { <SigninSubTitle>{ "尚未上傳任何檔案" }</SigninSubTitle> };
+        }
+
+        let per_page = self.query.per_page;
+        let total_pages = media.total / per_page + if media.total % per_page > 0 { 1 } else { 0 };
+
+        html! {
+            <>
+                <div class="content">
+                    <div class="grid">
+                        { for media.items.iter().map(|m| self.render_item(m)) }
+                    </div>
+                    { self.render_details() }
+                </div>
+                <Pagination page=self.query.page total_pages=total_pages
+                    onchange=self.link.callback(MediaLibraryMsg::Page) />
+            </>
+        }
+    }
+
+    fn render_thumbnail(media: &Media) -> Html {
+        if media.is_image() {
+            html! { <img src=media.url.clone() alt=media.alt.clone() loading="lazy" /> }
+        } else {
+            html! { <i class="fas fa-file"></i> }
+        }
+    }
+
+    fn render_item(&self, media: &Media) -> Html {
+        let id = media.id;
+        let class = if self.selected == Some(id) {
+            "item selected"
+        } else {
+            "item"
+        };
+
+        html! {
+            <div class=class title=media.filename.clone() onclick=self.link.callback(move |_| MediaLibraryMsg::Select(id))>
+                <div class="thumbnail">{ Self::render_thumbnail(media) }</div>
+                <div class="filename">{ media.filename.clone() }</div>
+            </div>
+        }
+    }
+
+    fn render_details(&self) -> Html {
+        let media = match self.selected_media() {
+            Some(m) => m,
+            None => return html! {},
+        };
+
+        html! {
+            <div class="details">
+                { Self::render_thumbnail(media) }
+                <div class="meta">
+                    { format!("{}・{}・{}", media.filename, format_size(media.size), format_date(media.created_at)) }
+                </div>
+                <Input name="alt" placeholder="替代文字" value=self.alt.clone()
+                    oninput=self.link.callback(MediaLibraryMsg::AltInput) />
+                <Input name="caption" placeholder="說明" value=self.caption.clone()
+                    oninput=self.link.callback(MediaLibraryMsg::CaptionInput) />
+                { self.render_buttons() }
+            </div>
+        }
+    }
+
+    fn render_buttons(&self) -> Html {
+        if self.saving {
+            return html! { <Spinner /> };
+        }
+
+        let pick = if self.props.onpick.is_some() {
+            html! {
+                <Button height="32px" onclick=self.link.callback(|_| MediaLibraryMsg::Pick)>{ "插入文章" }</Button>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <div class="buttons">
+                { pick }
+                <Button height="32px" color=ButtonColor::Black
+                    onclick=self.link.callback(|_| MediaLibraryMsg::Save)>{ "儲存" }</Button>
+                <Button height="32px" color=ButtonColor::Red
+                    onclick=self.link.callback(|_| MediaLibraryMsg::Delete)>{ "刪除" }</Button>
+            </div>
+        }
+    }
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct MediaPageProps {
+    pub client: Client,
+    pub image_options: ImageOptions,
+}
+
+#[derive(Debug)]
+pub(crate) struct MediaPage {
+    props: MediaPageProps,
+}
+
+impl Component for MediaPage {
+    type Message = ();
+    type Properties = MediaPageProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                <MediaLibrary client={ self.props.client.clone() } image_options={ self.props.image_options.clone() } />
+            </Card>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use js_sys::Array;
+    use wasm_bindgen_test::*;
+    use web_sys::FilePropertyBag;
+
+    use super::*;
+
+    fn file(name: &str, content_type: &str) -> File {
+        let options = FilePropertyBag::new();
+        options.set_type(content_type);
+        File::new_with_str_sequence_and_options(&Array::of1(&"data".into()), name, &options)
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_prepare_upload_without_worker() {
+        let upload = prepare_upload(file("photo.jpg", "image/jpeg"), None).await;
+        assert!(matches!(upload, Err(Error::Image(_))));
+
+        let upload = prepare_upload(file("notes.pdf", "application/pdf"), None).await;
+        assert!(upload.is_ok());
+    }
+}
@@ -0,0 +1,627 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::AppProps;
+use crate::app_components::{
+    OfficeNav, OfficeNavItem, ReauthOverlay, ReauthPurpose, SigninSubTitle,
+};
+use crate::backend::{Client, Role, SigninResident};
+use crate::components::{Body, Card, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{call_after, window, EventListener, Instant};
+use crate::permission::Permission;
+use crate::services::{IdleTracker, TabEvent, TabSync};
+use crate::traits::YieldStyle;
+use crate::{Error, Result};
+
+mod calendar;
+mod comments;
+mod dashboard;
+mod media;
+mod post_editor;
+mod posts;
+mod profile;
+mod residents;
+mod revisions;
+mod security;
+mod sessions;
+mod taxonomy;
+
+use calendar::CalendarPage;
+use comments::CommentsPage;
+use dashboard::DashboardPage;
+use media::MediaPage;
+use post_editor::PostEditorPage;
+use posts::PostsPage;
+use profile::ProfilePage;
+use residents::ResidentsPage;
+use security::SecurityPage;
+use sessions::SessionsPage;
+use taxonomy::TaxonomyPage;
+
+// How long a reauthentication allows sensitive actions without asking again.
+//
+// Locking the office after the idle timeout of the manifest ends it early, so a timeout shorter
+// than this shortens it; a longer one does not extend it.
+const SUDO_DURATION: Duration = Duration::from_secs(10 * 60);
+const TOKEN_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OfficePage {
+    Dashboard,
+    Posts,
+    // None for a new post.
+    EditPost(Option<u64>),
+    Calendar,
+    Taxonomy,
+    Comments,
+    Media,
+    Residents,
+    Profile,
+    Security,
+    Sessions,
+}
+
+impl OfficePage {
+    fn from_hash(hash: &str) -> Self {
+        let path = hash.trim_start_matches('#').trim_matches('/');
+
+        match path {
+            "posts" => Self::Posts,
+            "posts/new" => Self::EditPost(None),
+            "calendar" => Self::Calendar,
+            "taxonomy" => Self::Taxonomy,
+            "comments" => Self::Comments,
+            "media" => Self::Media,
+            "residents" => Self::Residents,
+            "profile" => Self::Profile,
+            "security" => Self::Security,
+            "sessions" => Self::Sessions,
+            _ => match path.strip_prefix("posts/").map(|m| m.parse::<u64>()) {
+                Some(Ok(m)) => Self::EditPost(Some(m)),
+                _ => Self::Dashboard,
+            },
+        }
+    }
+
+    pub(crate) fn to_hash(&self) -> String {
+        match self {
+            Self::Dashboard => "#/".to_string(),
+            Self::Posts => "#/posts".to_string(),
+            Self::EditPost(Some(m)) => format!("#/posts/{}", m),
+            Self::EditPost(None) => "#/posts/new".to_string(),
+            Self::Calendar => "#/calendar".to_string(),
+            Self::Taxonomy => "#/taxonomy".to_string(),
+            Self::Comments => "#/comments".to_string(),
+            Self::Media => "#/media".to_string(),
+            Self::Residents => "#/residents".to_string(),
+            Self::Profile => "#/profile".to_string(),
+            Self::Security => "#/security".to_string(),
+            Self::Sessions => "#/sessions".to_string(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Dashboard => "首頁",
+            Self::Posts => "文章",
+            Self::EditPost(Some(_)) => "編輯文章",
+            Self::EditPost(None) => "新文章",
+            Self::Calendar => "行事曆",
+            Self::Taxonomy => "標籤與分類",
+            Self::Comments => "留言",
+            Self::Media => "媒體庫",
+            Self::Residents => "住民",
+            Self::Profile => "個人資料",
+            Self::Security => "帳戶安全",
+            Self::Sessions => "登入裝置",
+        }
+    }
+
+    // Pages the role is not allowed to visit are left out.
+    fn nav_pages(role: Role) -> Vec<Self> {
+        vec![
+            Self::Dashboard,
+            Self::Posts,
+            Self::Calendar,
+            Self::Taxonomy,
+            Self::Comments,
+            Self::Media,
+            Self::Residents,
+            Self::Profile,
+            Self::Security,
+            Self::Sessions,
+        ]
+        .into_iter()
+        .filter(|m| m.is_allowed(role))
+        .collect()
+    }
+
+    fn permission(&self) -> Option<Permission> {
+        match self {
+            Self::Taxonomy => Some(Permission::ManageTaxonomy),
+            Self::Comments => Some(Permission::ModerateComments),
+            Self::Media => Some(Permission::UploadMedia),
+            Self::Residents => Some(Permission::ManageResidents),
+            _ => None,
+        }
+    }
+
+    fn is_allowed(&self, role: Role) -> bool {
+        self.permission().map(|m| role.can(m)).unwrap_or(true)
+    }
+
+    // The item in the navigation that is highlighted for this page.
+    fn nav_page(&self) -> Self {
+        match self {
+            Self::EditPost(_) => Self::Posts,
+            m => m.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SudoAction {
+    ChangePassword,
+    ChangeEmail,
+    DisableOtp,
+    RegenerateApiToken,
+}
+
+impl SudoAction {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::ChangePassword => "更改密碼",
+            Self::ChangeEmail => "更改電子郵件",
+            Self::DisableOtp => "停用兩步驗證",
+            Self::RegenerateApiToken => "重新生成 API 令牌",
+        }
+    }
+}
+
+// Asks the office to confirm the identity of the resident before `proceed` is called.
+#[derive(Debug, Clone)]
+pub(crate) struct SudoRequest {
+    pub action: SudoAction,
+    pub proceed: Callback<()>,
+}
+
+pub(crate) enum OfficeMsg {
+    Navigate(OfficePage),
+    ResidentLoaded(Result<SigninResident>),
+    ResidentUpdated(SigninResident),
+
+    Idle,
+    Unlocked,
+
+    RequireSudo(SudoRequest),
+    SudoAuthenticated,
+    SudoCancelled,
+
+    SignOut,
+    SignedOut,
+
+    TabEvent(TabEvent),
+    RefreshToken,
+    TokenRefreshed(Result<()>),
+}
+
+#[derive(Debug)]
+pub(crate) struct OfficeApp {
+    link: ComponentLink<Self>,
+    props: AppProps,
+    client: Client,
+    page: OfficePage,
+    // IANA name of the site timezone.
+    timezone: String,
+
+    resident: Option<SigninResident>,
+
+    idle_tracker: Option<IdleTracker>,
+    locked: bool,
+
+    last_authenticated: Option<Instant>,
+    sudo_request: Option<SudoRequest>,
+
+    tab_sync: TabSync,
+    // Signed out in another tab.
+    signed_out: bool,
+    last_token_refresh: Instant,
+
+    _hashchange_listener: EventListener,
+}
+
+impl YieldStyle for OfficeApp {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-office-app".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: row;
+            align-items: flex-start;
+            width: 100%;
+            max-width: 1200px;
+            min-height: 100vh;
+
+            main {
+                flex-grow: 1;
+                padding: 20px;
+                box-sizing: border-box;
+                min-width: 0;
+            }
+
+            .loading {
+                width: 100%;
+                min-height: 100vh;
+                display: flex;
+                justify-content: center;
+                align-items: center;
+            }
+
+            @media only screen and (max-width: 800px) {
+                flex-direction: column;
+            }
+            "#
+        )
+    }
+}
+
+impl Component for OfficeApp {
+    type Message = OfficeMsg;
+    type Properties = AppProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let client = props.manifest.client().expect("endpoint is not valid.");
+        let timezone = props.manifest.timezone().name().to_string();
+
+        let hashchange_listener = {
+            let link = link.clone();
+            EventListener::new(&window(), "hashchange", move |_| {
+                let hash = window().location().hash().unwrap_or_default();
+                link.send_message(OfficeMsg::Navigate(OfficePage::from_hash(&hash)));
+            })
+        };
+
+        let tab_sync = {
+            let link = link.clone();
+            TabSync::new(move |m| link.send_message(OfficeMsg::TabEvent(m)))
+        };
+
+        {
+            let client = client.clone();
+            link.send_future(
+                async move { OfficeMsg::ResidentLoaded(client.current_resident().await) },
+            );
+        }
+
+        let hash = window().location().hash().unwrap_or_default();
+
+        Self {
+            link,
+            props,
+            client,
+            page: OfficePage::from_hash(&hash),
+            timezone,
+
+            resident: None,
+
+            idle_tracker: None,
+            locked: Client::is_locked(),
+
+            last_authenticated: None,
+            sudo_request: None,
+
+            tab_sync,
+            signed_out: false,
+            last_token_refresh: Instant::now(),
+
+            _hashchange_listener: hashchange_listener,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            OfficeMsg::Navigate(page) => {
+                if self.page != page {
+                    self.page = page;
+                    true
+                } else {
+                    false
+                }
+            }
+            OfficeMsg::ResidentLoaded(m) => self.on_resident_loaded(m),
+            OfficeMsg::ResidentUpdated(m) => {
+                self.resident = Some(m);
+                true
+            }
+
+            OfficeMsg::Idle => {
+                self.locked = true;
+                Client::set_locked(true);
+                self.last_authenticated = None;
+                self.sudo_request = None;
+                true
+            }
+            OfficeMsg::Unlocked => {
+                self.locked = false;
+                Client::set_locked(false);
+                self.last_authenticated = Some(Instant::now());
+                if let Some(ref m) = self.idle_tracker {
+                    m.reset();
+                }
+                true
+            }
+
+            OfficeMsg::RequireSudo(m) => self.on_require_sudo(m),
+            OfficeMsg::SudoAuthenticated => {
+                self.last_authenticated = Some(Instant::now());
+                if let Some(m) = self.sudo_request.take() {
+                    m.proceed.emit(());
+                }
+                true
+            }
+            OfficeMsg::SudoCancelled => {
+                self.sudo_request = None;
+                true
+            }
+
+            OfficeMsg::SignOut => {
+                let client = self.client.clone();
+                self.link.send_future(async move {
+                    if let Err(e) = client.sign_out().await {
+                        log::error!("Failed to sign out: {}", e);
+                    }
+                    OfficeMsg::SignedOut
+                });
+                false
+            }
+            OfficeMsg::SignedOut => {
+                // Other tabs have already been told if the sign out happened elsewhere.
+                if !self.signed_out {
+                    Client::set_token(None);
+                    self.tab_sync.broadcast(&TabEvent::SignedOut);
+                }
+                Self::redirect_to_signin();
+                false
+            }
+
+            OfficeMsg::TabEvent(m) => self.on_tab_event(m),
+            OfficeMsg::RefreshToken => {
+                // Another tab may have refreshed the shared token in the meantime.
+                if self.signed_out || self.last_token_refresh.elapsed() < TOKEN_REFRESH_INTERVAL {
+                    self.schedule_token_refresh();
+                    return false;
+                }
+
+                let client = self.client.clone();
+                self.link.send_future(async move {
+                    OfficeMsg::TokenRefreshed(client.refresh_token().await)
+                });
+                false
+            }
+            OfficeMsg::TokenRefreshed(m) => {
+                match m {
+                    Ok(_) => {
+                        self.last_token_refresh = Instant::now();
+                        self.tab_sync.broadcast(&TabEvent::TokenRefreshed);
+                    }
+                    Err(Error::Unauthorized) => {
+                        self.signed_out = true;
+                        return true;
+                    }
+                    Err(e) => log::error!("Failed to refresh token: {}", e),
+                }
+                self.schedule_token_refresh();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        // Currently you cannot modify props once set
+        false
+    }
+
+    fn view(&self) -> Html {
+        let resident = match self.resident {
+            Some(ref m) => m,
+            None => {
+                return html! {
+                    <Body grey_background={ true } title={ self.to_title() }>
+                        <div class=self.yield_style_class()>
+                            <div class="loading"><Spinner /></div>
+                        </div>
+                    </Body>
+                };
+            }
+        };
+
+        let nav_items = OfficePage::nav_pages(resident.role)
+            .into_iter()
+            .map(|m| OfficeNavItem {
+                label: m.title(),
+                href: m.to_hash(),
+                active: m == self.page.nav_page(),
+            })
+            .collect::<Vec<_>>();
+
+        html! {
+            <Body grey_background={ true } title={ self.to_title() }>
+                <div class=self.yield_style_class()>
+                    <OfficeNav title={ self.props.manifest.site_title.clone() } items={ nav_items } />
+                    <main>{ self.render_page(resident) }</main>
+                </div>
+                { self.render_overlay(resident) }
+            </Body>
+        }
+    }
+}
+
+impl OfficeApp {
+    fn to_title(&self) -> String {
+        format!("{} - {}", self.page.title(), self.props.manifest.site_title)
+    }
+
+    fn redirect_to_signin() {
+        window().location().set_href("./signin").unwrap();
+    }
+
+    fn on_resident_loaded(&mut self, result: Result<SigninResident>) -> ShouldRender {
+        match result {
+            Ok(m) => {
+                self.resident = Some(m);
+
+                let link = self.link.clone();
+                self.idle_tracker = Some(IdleTracker::new(
+                    self.props.manifest.idle_timeout(),
+                    move || link.send_message(OfficeMsg::Idle),
+                ));
+                self.schedule_token_refresh();
+                true
+            }
+            Err(Error::Unauthorized) => {
+                Self::redirect_to_signin();
+                false
+            }
+            Err(e) => {
+                log::error!("Failed to load resident: {}", e);
+                false
+            }
+        }
+    }
+
+    fn schedule_token_refresh(&self) {
+        let link = self.link.clone();
+        call_after(
+            move || link.send_message(OfficeMsg::RefreshToken),
+            TOKEN_REFRESH_INTERVAL,
+        );
+    }
+
+    fn on_tab_event(&mut self, event: TabEvent) -> ShouldRender {
+        match event {
+            TabEvent::SignedOut => {
+                self.signed_out = true;
+                self.sudo_request = None;
+                self.idle_tracker = None;
+                true
+            }
+            TabEvent::SignedIn => {
+                if self.signed_out {
+                    window().location().reload().unwrap();
+                }
+                false
+            }
+            TabEvent::TokenRefreshed => {
+                self.last_token_refresh = Instant::now();
+                false
+            }
+            m @ TabEvent::ThemeChanged { .. } | m @ TabEvent::LocaleChanged { .. } => {
+                m.apply_to_document();
+                false
+            }
+        }
+    }
+
+    fn on_require_sudo(&mut self, request: SudoRequest) -> ShouldRender {
+        let recently_authenticated = self
+            .last_authenticated
+            .map(|m| m.elapsed() < SUDO_DURATION)
+            .unwrap_or(false);
+
+        if recently_authenticated {
+            request.proceed.emit(());
+            false
+        } else {
+            self.sudo_request = Some(request);
+            true
+        }
+    }
+
+    fn render_page(&self, resident: &SigninResident) -> Html {
+        if !self.page.is_allowed(resident.role) {
+            return html! {
+                <Card with_margin={ false } width={ "100%" }>
+                    <SigninSubTitle>{ "你沒有權限檢視此頁面。" }</SigninSubTitle>
+                </Card>
+            };
+        }
+
+        match self.page {
+            OfficePage::Dashboard => html! {
+                <DashboardPage resident={ resident.clone() } />
+            },
+            OfficePage::Posts => html! {
+                <PostsPage client={ self.client.clone() } resident={ resident.clone() } />
+            },
+            OfficePage::EditPost(m) => html! {
+                <PostEditorPage client={ self.client.clone() } post_id={ m } timezone={ self.timezone.clone() }
+                    image_options={ self.props.manifest.image.clone() } can_publish={ resident.role.can(Permission::PublishPosts) } />
+            },
+            OfficePage::Calendar => html! {
+                <CalendarPage client={ self.client.clone() } timezone={ self.timezone.clone() }
+                    can_schedule={ resident.role.can(Permission::PublishPosts) } />
+            },
+            OfficePage::Taxonomy => html! {
+                <TaxonomyPage client={ self.client.clone() } />
+            },
+            OfficePage::Comments => html! {
+                <CommentsPage client={ self.client.clone() } suspended={ self.has_overlay() } />
+            },
+            OfficePage::Media => html! {
+                <MediaPage client={ self.client.clone() } image_options={ self.props.manifest.image.clone() } />
+            },
+            OfficePage::Residents => html! {
+                <ResidentsPage client={ self.client.clone() } resident={ resident.clone() } />
+            },
+            OfficePage::Profile => html! {
+                <ProfilePage client={ self.client.clone() } image_options={ self.props.manifest.image.clone() }
+                    onsudo=self.link.callback(OfficeMsg::RequireSudo)
+                    onupdated=self.link.callback(OfficeMsg::ResidentUpdated) />
+            },
+            OfficePage::Security => html! {
+                <SecurityPage client={ self.client.clone() } onsudo=self.link.callback(OfficeMsg::RequireSudo) />
+            },
+            OfficePage::Sessions => html! {
+                <SessionsPage client={ self.client.clone() } />
+            },
+        }
+    }
+
+    // The page stays mounted under an overlay so nothing is lost, but must not act on its own.
+    fn has_overlay(&self) -> bool {
+        self.signed_out || self.locked || self.sudo_request.is_some()
+    }
+
+    fn render_overlay(&self, resident: &SigninResident) -> Html {
+        if self.signed_out {
+            return html! {
+                <ReauthOverlay client={ self.client.clone() } resident={ resident.clone() } purpose={ ReauthPurpose::SignedOut }
+                    oncancel=self.link.callback(|_| OfficeMsg::SignedOut) />
+            };
+        }
+
+        if self.locked {
+            return html! {
+                <ReauthOverlay client={ self.client.clone() } resident={ resident.clone() } purpose={ ReauthPurpose::Lock }
+                    onauthenticated=self.link.callback(|_| OfficeMsg::Unlocked)
+                    oncancel=self.link.callback(|_| OfficeMsg::SignOut) />
+            };
+        }
+
+        if let Some(ref m) = self.sudo_request {
+            return html! {
+                <ReauthOverlay client={ self.client.clone() } resident={ resident.clone() } purpose={ ReauthPurpose::Sudo(m.action.title().into()) }
+                    onauthenticated=self.link.callback(|_| OfficeMsg::SudoAuthenticated)
+                    oncancel=self.link.callback(|_| OfficeMsg::SudoCancelled) />
+            };
+        }
+
+        html! {}
+    }
+}
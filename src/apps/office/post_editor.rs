@@ -0,0 +1,945 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::media::MediaLibrary;
+use super::revisions::RevisionHistory;
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Media, Post, PostDraft, PostStatus};
+use crate::components::{Button, ButtonColor, Card, DateTimePicker, Input, InputValue, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{
+    call_after, format_date_time, format_relative_time, now_timestamp, window, Instant,
+};
+use crate::imaging::ImageOptions;
+use crate::markdown::render_markdown;
+use crate::services::{DraftStore, LocalDraft};
+use crate::traits::YieldStyle;
+use crate::Result;
+
+// Local copies are cheap, the server is asked less often.
+const LOCAL_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(2);
+const SERVER_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct PostEditorPageProps {
+    pub client: Client,
+    // None for a new post.
+    pub post_id: Option<u64>,
+    // Scheduled times are entered in this timezone.
+    pub timezone: String,
+
+    // Used when images are inserted from the media library.
+    pub image_options: ImageOptions,
+    // Residents without the permission can only save drafts.
+    pub can_publish: bool,
+}
+
+impl YieldStyle for PostEditorPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-post-editor-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .fields {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: center;
+                width: 100%;
+            }
+
+            .fields > * {
+                margin: 5px;
+            }
+
+            .panes {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+                height: 60vh;
+                margin-top: 10px;
+            }
+
+            .panes > * {
+                width: 50%;
+                height: 100%;
+                box-sizing: border-box;
+                overflow-y: auto;
+                padding: 15px;
+            }
+
+            textarea {
+                resize: none;
+                border: 1px solid rgb(150, 150, 150);
+                border-radius: 5px;
+                font-family: monospace;
+                font-size: 0.9rem;
+                line-height: 1.6;
+                outline: 0;
+            }
+
+            .preview {
+                border-left: 1px solid rgba(150, 150, 150, 0.2);
+                line-height: 1.6;
+                word-break: break-word;
+            }
+
+            .preview img {
+                max-width: 100%;
+            }
+
+            .preview pre {
+                padding: 10px;
+                overflow-x: auto;
+                background-color: rgb(245, 245, 245);
+                border-radius: 5px;
+            }
+
+            .preview blockquote {
+                margin-left: 0;
+                padding-left: 15px;
+                border-left: 3px solid rgb(200, 200, 200);
+                color: rgb(100, 100, 100);
+            }
+
+            .preview table {
+                border-collapse: collapse;
+            }
+
+            .preview th, .preview td {
+                padding: 5px 10px;
+                border: 1px solid rgb(200, 200, 200);
+            }
+
+            .preview .footnote-definition {
+                font-size: 0.9rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .actions {
+                display: flex;
+                flex-direction: row;
+                justify-content: flex-end;
+                align-items: center;
+                width: 100%;
+                margin-top: 10px;
+            }
+
+            .actions > * {
+                margin-left: 10px;
+            }
+
+            .restore {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: center;
+                width: 100%;
+                box-sizing: border-box;
+                padding: 10px 15px;
+                margin-bottom: 10px;
+                border-radius: 5px;
+                background-color: rgba(255, 193, 7, 0.15);
+            }
+
+            .restore > div {
+                flex-grow: 1;
+            }
+
+            .restore > * {
+                margin: 5px;
+            }
+
+            .schedule {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                justify-content: flex-end;
+                align-items: center;
+                width: 100%;
+                margin-top: 10px;
+            }
+
+            .schedule > * {
+                margin-left: 10px;
+            }
+
+            .media-picker {
+                position: fixed;
+                top: 0;
+                left: 0;
+                width: 100%;
+                height: 100%;
+                display: flex;
+                align-items: center;
+                justify-content: center;
+                background-color: rgba(0, 0, 0, 0.4);
+                z-index: 100;
+            }
+
+            .media-picker > * {
+                overflow-y: auto;
+            }
+
+            .media-picker-header {
+                display: flex;
+                flex-direction: row;
+                align-items: center;
+                width: 100%;
+            }
+
+            .media-picker-header h3 {
+                flex-grow: 1;
+                margin-top: 0;
+                font-weight: normal;
+            }
+
+            .media-picker-header i {
+                cursor: pointer;
+                color: rgb(150, 150, 150);
+            }
+
+            .state {
+                font-size: 0.9rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Pane {
+    Editor,
+    Preview,
+}
+
+// What happens to the post after it is saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SaveAction {
+    Draft,
+    Publish,
+    Schedule(i64),
+}
+
+pub(crate) enum PostEditorMsg {
+    Load,
+    Loaded(Result<Post>),
+
+    TitleInput(InputValue),
+    SlugInput(InputValue),
+    TagsInput(InputValue),
+    BodyInput(String),
+
+    Scroll(Pane),
+
+    StoreOpened(Result<DraftStore>),
+    LocalDraftLoaded(Result<Option<LocalDraft>>),
+    Restore,
+    DiscardRestore,
+
+    AutosaveLocal,
+    AutosaveServer,
+    Autosaved(Result<()>),
+
+    ToggleHistory,
+    ToggleMedia,
+    InsertMedia(Media),
+    Restored(Post),
+
+    ToggleSchedule,
+    ScheduleAt(i64),
+
+    Save(SaveAction),
+    Saved(SaveAction, Result<Post>),
+    // Published or scheduled after it is saved.
+    Published(Result<Post>),
+}
+
+#[derive(Debug)]
+pub(crate) struct PostEditorPage {
+    props: PostEditorPageProps,
+    link: ComponentLink<Self>,
+
+    post: Option<Post>,
+    draft: PostDraft,
+    tags: String,
+    preview: String,
+    preview_outdated: bool,
+
+    loading: bool,
+    saving: bool,
+    failed: bool,
+    changed: bool,
+
+    editor_ref: NodeRef,
+    preview_ref: NodeRef,
+    // Setting scrollTop fires a scroll event on the other pane, which must not be synchronised back.
+    scrolled_by_sync: Option<Pane>,
+
+    show_history: bool,
+    show_media: bool,
+    show_schedule: bool,
+    schedule_at: Option<i64>,
+
+    store: Option<DraftStore>,
+    // A local copy newer than the post on the server, waiting for the resident to decide.
+    restorable: Option<LocalDraft>,
+    last_local_save: Instant,
+    local_save_pending: bool,
+    last_server_save: Instant,
+    server_save_pending: bool,
+    server_outdated: bool,
+}
+
+impl Component for PostEditorPage {
+    type Message = PostEditorMsg;
+    type Properties = PostEditorPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        if props.post_id.is_some() {
+            link.send_message(PostEditorMsg::Load);
+        }
+        link.send_future(async { PostEditorMsg::StoreOpened(DraftStore::open().await) });
+
+        Self {
+            props,
+            link,
+
+            post: None,
+            draft: PostDraft::default(),
+            tags: String::new(),
+            preview: String::new(),
+            preview_outdated: true,
+
+            loading: false,
+            saving: false,
+            failed: false,
+            changed: false,
+
+            editor_ref: NodeRef::default(),
+            preview_ref: NodeRef::default(),
+            scrolled_by_sync: None,
+
+            show_history: false,
+            show_media: false,
+            show_schedule: false,
+            schedule_at: None,
+
+            store: None,
+            restorable: None,
+            last_local_save: Instant::now(),
+            local_save_pending: false,
+            last_server_save: Instant::now(),
+            server_save_pending: false,
+            server_outdated: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PostEditorMsg::Load => {
+                let id = match self.props.post_id {
+                    Some(m) => m,
+                    None => return false,
+                };
+                let client = self.props.client.clone();
+                self.loading = true;
+                self.link
+                    .send_future(async move { PostEditorMsg::Loaded(client.post_by_id(id).await) });
+                true
+            }
+            PostEditorMsg::Loaded(m) => {
+                self.loading = false;
+                match m {
+                    Ok(m) => {
+                        self.set_post(m);
+                        self.load_local_draft();
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load post: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            PostEditorMsg::TitleInput(m) => {
+                self.draft.title = m.into_string();
+                self.mark_changed();
+                true
+            }
+            PostEditorMsg::SlugInput(m) => {
+                self.draft.slug = m.into_string();
+                self.mark_changed();
+                true
+            }
+            PostEditorMsg::TagsInput(m) => {
+                self.tags = m.into_string();
+                self.mark_changed();
+                true
+            }
+            PostEditorMsg::BodyInput(m) => {
+                self.preview = render_markdown(&m);
+                self.preview_outdated = true;
+                self.draft.body = m;
+                self.mark_changed();
+                true
+            }
+
+            PostEditorMsg::Scroll(m) => {
+                self.sync_scroll(m);
+                false
+            }
+
+            PostEditorMsg::StoreOpened(m) => {
+                match m {
+                    Ok(m) => {
+                        self.store = Some(m);
+                        self.load_local_draft();
+                    }
+                    Err(e) => log::warn!("Unsaved changes will not be kept locally: {}", e),
+                }
+                false
+            }
+            PostEditorMsg::LocalDraftLoaded(m) => {
+                let local = match m {
+                    Ok(Some(m)) => m,
+                    Ok(None) => return false,
+                    Err(e) => {
+                        log::error!("Failed to read local draft: {}", e);
+                        return false;
+                    }
+                };
+
+                let newer = match self.post {
+                    Some(ref m) => {
+                        local.saved_at > m.updated_at && local.draft != PostDraft::from(m)
+                    }
+                    None => true,
+                };
+
+                if newer {
+                    self.restorable = Some(local);
+                }
+                newer
+            }
+            PostEditorMsg::Restore => {
+                let local = match self.restorable.take() {
+                    Some(m) => m,
+                    None => return false,
+                };
+
+                self.tags = local.draft.tags.join(", ");
+                self.preview = render_markdown(&local.draft.body);
+                self.preview_outdated = true;
+                self.draft = local.draft;
+                self.mark_changed();
+                true
+            }
+            PostEditorMsg::DiscardRestore => {
+                if let Some(m) = self.restorable.take() {
+                    self.delete_local_draft(m.key);
+                }
+                true
+            }
+
+            PostEditorMsg::AutosaveLocal => {
+                self.local_save_pending = false;
+
+                // Keeps the old copy until the resident decides whether to restore it.
+                let store = match self.store {
+                    Some(ref m) if self.changed && self.restorable.is_none() => m.clone(),
+                    _ => return false,
+                };
+
+                let local = LocalDraft {
+                    key: self.local_key(),
+                    draft: self.current_draft(),
+                    saved_at: now_timestamp(),
+                };
+
+                self.last_local_save = Instant::now();
+                self.link
+                    .send_future(async move { PostEditorMsg::Autosaved(store.put(&local).await) });
+                false
+            }
+            PostEditorMsg::AutosaveServer => {
+                self.server_save_pending = false;
+
+                // New posts are only kept locally until they are saved for the first time.
+                let id = match self.post {
+                    Some(ref m) if self.server_outdated && !self.saving => m.id,
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                let draft = self.current_draft();
+
+                self.server_outdated = false;
+                self.last_server_save = Instant::now();
+                self.link.send_future(async move {
+                    PostEditorMsg::Autosaved(client.autosave_post(id, &draft).await)
+                });
+                false
+            }
+            PostEditorMsg::Autosaved(m) => {
+                if let Err(e) = m {
+                    log::error!("Failed to autosave post: {}", e);
+                }
+                false
+            }
+
+            PostEditorMsg::ToggleHistory => {
+                self.show_history = !self.show_history;
+                true
+            }
+            PostEditorMsg::ToggleMedia => {
+                self.show_media = !self.show_media;
+                true
+            }
+            PostEditorMsg::InsertMedia(m) => {
+                self.show_media = false;
+
+                // Inserts at the cursor, or at the end if the editor has never been focused.
+                let cursor = self
+                    .editor_ref
+                    .cast::<web_sys::HtmlTextAreaElement>()
+                    .and_then(|m| m.selection_start().ok().flatten())
+                    .map(|m| utf16_to_byte_offset(&self.draft.body, m))
+                    .unwrap_or_else(|| self.draft.body.len());
+
+                let mut body = self.draft.body.clone();
+                body.insert_str(cursor, &m.to_markdown());
+                self.update(PostEditorMsg::BodyInput(body))
+            }
+            PostEditorMsg::Restored(m) => {
+                self.delete_local_draft(self.local_key());
+                self.set_post(m);
+                true
+            }
+
+            PostEditorMsg::ToggleSchedule => {
+                self.show_schedule = !self.show_schedule;
+                true
+            }
+            PostEditorMsg::ScheduleAt(m) => {
+                self.schedule_at = Some(m);
+                true
+            }
+
+            PostEditorMsg::Save(action) => {
+                if self.saving || self.draft.title.trim().is_empty() {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                let id = self.post.as_ref().map(|m| m.id);
+                let draft = self.current_draft();
+
+                self.saving = true;
+                self.link.send_future(async move {
+                    let saved = match id {
+                        Some(id) => client.update_post(id, &draft).await,
+                        None => client.create_post(&draft).await,
+                    };
+
+                    PostEditorMsg::Saved(action, saved)
+                });
+                true
+            }
+            // The post is kept before it is published, so a failure to publish does not create it
+            // again on the next attempt.
+            PostEditorMsg::Saved(action, m) => {
+                let post = match m {
+                    Ok(m) => m,
+                    Err(e) => {
+                        self.saving = false;
+                        log::error!("Failed to save post: {}", e);
+                        window()
+                            .alert_with_message("無法儲存文章，請稍後再試。")
+                            .unwrap();
+                        return true;
+                    }
+                };
+
+                let client = self.props.client.clone();
+                let id = post.id;
+                self.on_saved(post);
+
+                match action {
+                    SaveAction::Draft => self.saving = false,
+                    SaveAction::Publish => self.link.send_future(async move {
+                        PostEditorMsg::Published(client.publish_post(id).await)
+                    }),
+                    SaveAction::Schedule(m) => self.link.send_future(async move {
+                        PostEditorMsg::Published(client.schedule_post(id, m).await)
+                    }),
+                }
+                true
+            }
+            PostEditorMsg::Published(m) => {
+                self.saving = false;
+                match m {
+                    Ok(m) => self.set_post(m),
+                    Err(e) => {
+                        log::error!("Failed to publish post: {}", e);
+                        window()
+                            .alert_with_message("文章已儲存，但無法發佈，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+
+        // The address changes after a new post is saved, which is the post already being edited.
+        let reload = props.post_id.is_some() && props.post_id != self.post.as_ref().map(|m| m.id);
+        self.props = props;
+
+        if reload {
+            self.post = None;
+            self.restorable = None;
+            self.link.send_message(PostEditorMsg::Load);
+        }
+        true
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        if !self.preview_outdated {
+            return;
+        }
+
+        if let Some(m) = self.preview_ref.cast::<web_sys::Element>() {
+            m.set_inner_html(&self.preview);
+            self.preview_outdated = false;
+        }
+    }
+
+    fn view(&self) -> Html {
+        if self.failed {
+            return html! { <SigninSubTitle>{ "無法載入文章，請稍後再試。" }</SigninSubTitle> };
+        }
+
+        if self.loading {
+            return html! { <Spinner /> };
+        }
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    { self.render_restore() }
+                    <div class="fields">
+                        <Input name="title" placeholder="標題" width="320px" value=self.draft.title.clone()
+                            oninput=self.link.callback(PostEditorMsg::TitleInput) />
+                        <Input name="slug" placeholder="網址名稱" width="200px" value=self.draft.slug.clone()
+                            oninput=self.link.callback(PostEditorMsg::SlugInput) />
+                        <Input name="tags" placeholder="標籤（以逗號分隔）" width="240px" value=self.tags.clone()
+                            oninput=self.link.callback(PostEditorMsg::TagsInput) />
+                    </div>
+                    <div class="panes">
+                        <textarea ref=self.editor_ref.clone() placeholder="以 Markdown 撰寫內文" value=self.draft.body.clone()
+                            oninput=self.link.callback(|e: InputData| PostEditorMsg::BodyInput(e.value))
+                            onscroll=self.link.callback(|_| PostEditorMsg::Scroll(Pane::Editor)) />
+                        <div class="preview" ref=self.preview_ref.clone()
+                            onscroll=self.link.callback(|_| PostEditorMsg::Scroll(Pane::Preview))></div>
+                    </div>
+                    { self.render_schedule() }
+                    <div class="actions">
+                        <div class="state">{ self.state_text() }</div>
+                        { self.render_actions() }
+                    </div>
+                </Card>
+                { self.render_history() }
+                { self.render_media_picker() }
+            </div>
+        }
+    }
+}
+
+impl PostEditorPage {
+    fn current_draft(&self) -> PostDraft {
+        let mut draft = self.draft.clone();
+        draft.tags = self
+            .tags
+            .split(',')
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+            .map(|m| m.to_string())
+            .collect();
+        draft
+    }
+
+    fn local_key(&self) -> String {
+        match self.post.as_ref().map(|m| m.id).or(self.props.post_id) {
+            Some(m) => format!("post-{}", m),
+            None => "post-new".to_string(),
+        }
+    }
+
+    fn mark_changed(&mut self) {
+        self.changed = true;
+        self.server_outdated = true;
+        self.schedule_autosave();
+    }
+
+    // Saves at most once per interval, the first change after a quiet period is saved immediately.
+    fn schedule_autosave(&mut self) {
+        if !self.local_save_pending {
+            self.local_save_pending = true;
+            let delay = LOCAL_AUTOSAVE_INTERVAL
+                .checked_sub(self.last_local_save.elapsed())
+                .unwrap_or_default();
+            let link = self.link.clone();
+            call_after(
+                move || link.send_message(PostEditorMsg::AutosaveLocal),
+                delay,
+            );
+        }
+
+        if self.post.is_some() && !self.server_save_pending {
+            self.server_save_pending = true;
+            let delay = SERVER_AUTOSAVE_INTERVAL
+                .checked_sub(self.last_server_save.elapsed())
+                .unwrap_or_default();
+            let link = self.link.clone();
+            call_after(
+                move || link.send_message(PostEditorMsg::AutosaveServer),
+                delay,
+            );
+        }
+    }
+
+    // Waits until both the post and the store are available.
+    fn load_local_draft(&self) {
+        let store = match self.store {
+            Some(ref m) if self.props.post_id.is_none() || self.post.is_some() => m.clone(),
+            _ => return,
+        };
+
+        let key = self.local_key();
+        self.link
+            .send_future(async move { PostEditorMsg::LocalDraftLoaded(store.get(&key).await) });
+    }
+
+    fn delete_local_draft(&self, key: String) {
+        if let Some(m) = self.store.clone() {
+            self.link
+                .send_future(async move { PostEditorMsg::Autosaved(m.delete(&key).await) });
+        }
+    }
+
+    fn on_saved(&mut self, post: Post) {
+        let created = self.post.is_none();
+        let id = post.id;
+        self.delete_local_draft(self.local_key());
+        self.set_post(post);
+
+        // Moves to the address of the post, so reloading keeps the editor open.
+        if created {
+            window()
+                .location()
+                .set_hash(&format!("#/posts/{}", id))
+                .unwrap();
+        }
+    }
+
+    fn set_post(&mut self, post: Post) {
+        self.draft = PostDraft::from(&post);
+        self.tags = post.tags.join(", ");
+        self.preview = render_markdown(&post.body);
+        self.preview_outdated = true;
+        self.changed = false;
+        self.server_outdated = false;
+        if post.status == PostStatus::Scheduled {
+            self.schedule_at = post.published_at;
+        }
+        self.post = Some(post);
+    }
+
+    fn render_actions(&self) -> Html {
+        if self.saving {
+            return html! { <Spinner /> };
+        }
+
+        let disabled = self.draft.title.trim().is_empty();
+        html! {
+            <>
+                { self.render_history_button() }
+                <Button color=ButtonColor::Black
+                    onclick=self.link.callback(|_| PostEditorMsg::ToggleMedia)>{ "插入媒體" }</Button>
+                <Button color=ButtonColor::Black disabled=disabled
+                    onclick=self.link.callback(|_| PostEditorMsg::Save(SaveAction::Draft))>{ "儲存草稿" }</Button>
+                {
+                    if self.props.can_publish {
+                        html! {
+                            <>
+                                <Button color=ButtonColor::Black disabled=disabled
+                                    onclick=self.link.callback(|_| PostEditorMsg::ToggleSchedule)>{ "排程" }</Button>
+                                <Button disabled=disabled
+                                    onclick=self.link.callback(|_| PostEditorMsg::Save(SaveAction::Publish))>{ "發佈" }</Button>
+                            </>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </>
+        }
+    }
+
+    fn render_schedule(&self) -> Html {
+        if !self.show_schedule || !self.props.can_publish {
+            return html! {};
+        }
+
+        // Posts cannot be scheduled in the past.
+        let schedule_at = self.schedule_at.filter(|m| *m > now_timestamp());
+        let disabled = self.saving || schedule_at.is_none() || self.draft.title.trim().is_empty();
+        let onclick = match schedule_at {
+            Some(m) => self
+                .link
+                .callback(move |_| PostEditorMsg::Save(SaveAction::Schedule(m))),
+            None => Callback::noop(),
+        };
+
+        html! {
+            <div class="schedule">
+                <div>{ "發佈時間" }</div>
+                <DateTimePicker value=self.schedule_at timezone=self.props.timezone.clone()
+                    onchange=self.link.callback(PostEditorMsg::ScheduleAt) />
+                <Button disabled=disabled onclick=onclick>{ "排程發佈" }</Button>
+            </div>
+        }
+    }
+
+    fn render_media_picker(&self) -> Html {
+        if !self.show_media {
+            return html! {};
+        }
+
+        html! {
+            <div class="media-picker">
+                <Card with_margin={ false } width={ "90%" } max_width={ "1200px" } max_height={ "90vh" } align_items={ "flex-start" }>
+                    <div class="media-picker-header">
+                        <h3>{ "插入媒體" }</h3>
+                        <i class="fas fa-times" onclick=self.link.callback(|_| PostEditorMsg::ToggleMedia)></i>
+                    </div>
+                    <MediaLibrary client={ self.props.client.clone() } image_options={ self.props.image_options.clone() }
+                        onpick=Some(self.link.callback(PostEditorMsg::InsertMedia)) />
+                </Card>
+            </div>
+        }
+    }
+
+    fn render_history_button(&self) -> Html {
+        if self.post.is_none() {
+            return html! {};
+        }
+
+        html! {
+            <Button color=ButtonColor::Black
+                onclick=self.link.callback(|_| PostEditorMsg::ToggleHistory)>{ "版本紀錄" }</Button>
+        }
+    }
+
+    fn render_history(&self) -> Html {
+        match self.post {
+            Some(ref m) if self.show_history => html! {
+                <RevisionHistory client={ self.props.client.clone() } post_id={ m.id } updated_at={ m.updated_at }
+                    onrestored=self.link.callback(PostEditorMsg::Restored) />
+            },
+            _ => html! {},
+        }
+    }
+
+    fn render_restore(&self) -> Html {
+        let local = match self.restorable {
+            Some(ref m) => m,
+            None => return html! {},
+        };
+
+        html! {
+            <div class="restore">
+                <div>{ format!("要還原於 {} 未儲存的變更嗎？", format_date_time(local.saved_at)) }</div>
+                <Button height="32px" onclick=self.link.callback(|_| PostEditorMsg::Restore)>{ "還原" }</Button>
+                <Button height="32px" color=ButtonColor::Black
+                    onclick=self.link.callback(|_| PostEditorMsg::DiscardRestore)>{ "捨棄" }</Button>
+            </div>
+        }
+    }
+
+    fn state_text(&self) -> String {
+        match self.post {
+            _ if self.changed => "尚未儲存".to_string(),
+            Some(ref m) => format!(
+                "{}・{}儲存",
+                m.status.label(),
+                format_relative_time(m.updated_at)
+            ),
+            None => "新文章".to_string(),
+        }
+    }
+
+    // Keeps both panes at the same relative position.
+    fn sync_scroll(&mut self, from: Pane) {
+        if self.scrolled_by_sync == Some(from) {
+            self.scrolled_by_sync = None;
+            return;
+        }
+
+        let (source, target, to) = match from {
+            Pane::Editor => (&self.editor_ref, &self.preview_ref, Pane::Preview),
+            Pane::Preview => (&self.preview_ref, &self.editor_ref, Pane::Editor),
+        };
+
+        let (source, target) = match (
+            source.cast::<web_sys::Element>(),
+            target.cast::<web_sys::Element>(),
+        ) {
+            (Some(source), Some(target)) => (source, target),
+            _ => return,
+        };
+
+        let scrollable = source.scroll_height() - source.client_height();
+        if scrollable <= 0 {
+            return;
+        }
+
+        let ratio = f64::from(source.scroll_top()) / f64::from(scrollable);
+        let target_scrollable = target.scroll_height() - target.client_height();
+        let top = (ratio * f64::from(target_scrollable)).round() as i32;
+
+        if target.scroll_top() != top {
+            target.set_scroll_top(top);
+            self.scrolled_by_sync = Some(to);
+        }
+    }
+}
+
+// Selection offsets from the browser count UTF-16 code units.
+fn utf16_to_byte_offset(s: &str, offset: u32) -> usize {
+    let mut count = 0;
+
+    for (i, c) in s.char_indices() {
+        if count >= offset as usize {
+            return i;
+        }
+        count += c.len_utf16();
+    }
+
+    s.len()
+}
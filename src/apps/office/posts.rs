@@ -0,0 +1,408 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::OfficePage;
+use crate::app_components::SigninSubTitle;
+use crate::backend::{
+    Client, Order, Paginated, PostQuery, PostSort, PostStatus, PostSummary, SigninResident,
+};
+use crate::components::{
+    Button, ButtonColor, Card, Input, InputValue, Pagination, SortOrder, Spinner, Table,
+    TableColumn,
+};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{format_relative_time, window};
+use crate::permission::Permission;
+use crate::traits::YieldStyle;
+use crate::Result;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct PostsPageProps {
+    pub client: Client,
+    // Posts of other residents can only be opened with the permission to edit them.
+    pub resident: SigninResident,
+}
+
+impl YieldStyle for PostsPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-posts-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .filters {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: center;
+                width: 100%;
+            }
+
+            .filters > * {
+                margin: 5px;
+            }
+
+            .new-post {
+                margin-left: auto;
+            }
+
+            a {
+                color: inherit;
+                text-decoration: none;
+            }
+
+            a:hover {
+                text-decoration: underline;
+            }
+
+            select {
+                height: 40px;
+                border-radius: 500px;
+                border: 1px solid rgb(150, 150, 150);
+                padding-left: 15px;
+                padding-right: 15px;
+                background-color: white;
+                font-size: 1rem;
+                outline: 0;
+            }
+
+            .status {
+                display: inline-block;
+                padding-left: 10px;
+                padding-right: 10px;
+                border-radius: 500px;
+                font-size: 0.8rem;
+                line-height: 22px;
+                color: white;
+                white-space: nowrap;
+            }
+
+            .status.draft {
+                background-color: rgb(150, 150, 150);
+            }
+
+            .status.published {
+                background-color: rgb(50, 191, 50);
+            }
+
+            .status.scheduled {
+                background-color: rgb(92, 184, 230);
+            }
+
+            .tag {
+                display: inline-block;
+                margin-right: 5px;
+                font-size: 0.8rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .sub {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum PostsMsg {
+    Load,
+    // With the sequence number of the load.
+    Loaded(u64, Result<Paginated<PostSummary>>),
+
+    StatusChange(String),
+    TagInput(InputValue),
+    AuthorInput(InputValue),
+    SearchInput(InputValue),
+    ApplyFilters,
+
+    Sort(&'static str),
+    Page(u64),
+    Ignore,
+}
+
+#[derive(Debug)]
+pub(crate) struct PostsPage {
+    props: PostsPageProps,
+    link: ComponentLink<Self>,
+
+    query: PostQuery,
+    posts: Option<Paginated<PostSummary>>,
+    loading: bool,
+    // Responses of earlier loads may arrive after the latest one.
+    load_seq: u64,
+    failed: bool,
+
+    tag_input: String,
+    author_input: String,
+    search_input: String,
+}
+
+impl Component for PostsPage {
+    type Message = PostsMsg;
+    type Properties = PostsPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(PostsMsg::Load);
+
+        Self {
+            props,
+            link,
+
+            query: PostQuery::default(),
+            posts: None,
+            loading: false,
+            load_seq: 0,
+            failed: false,
+
+            tag_input: String::new(),
+            author_input: String::new(),
+            search_input: String::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PostsMsg::Load => {
+                let client = self.props.client.clone();
+                let query = self.query.clone();
+                self.loading = true;
+                self.load_seq += 1;
+
+                let seq = self.load_seq;
+                self.link
+                    .send_future(async move { PostsMsg::Loaded(seq, client.posts(&query).await) });
+                true
+            }
+            PostsMsg::Loaded(seq, m) => {
+                if seq != self.load_seq {
+                    return false;
+                }
+
+                self.loading = false;
+                match m {
+                    Ok(m) => {
+                        self.posts = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load posts: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            PostsMsg::StatusChange(m) => {
+                self.query.status = PostStatus::all()
+                    .iter()
+                    .find(|status| status.key() == m)
+                    .copied();
+                self.reload_from_first_page()
+            }
+            PostsMsg::TagInput(m) => {
+                self.tag_input = m.into_string();
+                false
+            }
+            PostsMsg::AuthorInput(m) => {
+                self.author_input = m.into_string();
+                false
+            }
+            PostsMsg::SearchInput(m) => {
+                self.search_input = m.into_string();
+                false
+            }
+            PostsMsg::ApplyFilters => {
+                self.query.tag = non_empty(&self.tag_input);
+                self.query.author = non_empty(&self.author_input);
+                self.query.search = non_empty(&self.search_input);
+                self.reload_from_first_page()
+            }
+
+            PostsMsg::Sort(key) => {
+                let sort = match key {
+                    "title" => PostSort::Title,
+                    "status" => PostSort::Status,
+                    _ => PostSort::UpdatedAt,
+                };
+
+                if self.query.sort == sort {
+                    self.query.order = match self.query.order {
+                        Order::Asc => Order::Desc,
+                        Order::Desc => Order::Asc,
+                    };
+                } else {
+                    self.query.sort = sort;
+                    self.query.order = Order::Asc;
+                }
+                self.reload_from_first_page()
+            }
+            PostsMsg::Page(m) => {
+                self.query.page = m;
+                self.link.send_message(PostsMsg::Load);
+                false
+            }
+            PostsMsg::Ignore => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let filter_keyup = self.link.callback(|e: KeyboardEvent| {
+            if e.key_code() == 13 {
+                PostsMsg::ApplyFilters
+            } else {
+                PostsMsg::Ignore
+            }
+        });
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="filters">
+                        <Input name="search" placeholder="搜尋文章" width="240px" onkeyup=filter_keyup.clone()
+                            oninput=self.link.callback(PostsMsg::SearchInput) />
+                        <select onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(m) => PostsMsg::StatusChange(m.value()),
+                            _ => PostsMsg::Ignore,
+                        })>
+                            <option value="" selected=self.query.status.is_none()>{ "所有狀態" }</option>
+                            {
+                                for PostStatus::all().iter().map(|m| html! {
+                                    <option value=m.key() selected=self.query.status == Some(*m)>{ m.label() }</option>
+                                })
+                            }
+                        </select>
+                        <Input name="tag" placeholder="標籤" width="140px" onkeyup=filter_keyup.clone()
+                            oninput=self.link.callback(PostsMsg::TagInput) />
+                        <Input name="author" placeholder="作者" width="140px" onkeyup=filter_keyup
+                            oninput=self.link.callback(PostsMsg::AuthorInput) />
+                        <Button onclick=self.link.callback(|_| PostsMsg::ApplyFilters)>{ "篩選" }</Button>
+                        <div class="new-post">
+                            <Button color=ButtonColor::Green onclick=Callback::from(|_| {
+                                window().location().set_hash(&OfficePage::EditPost(None).to_hash()).unwrap();
+                            })>{ "新文章" }</Button>
+                        </div>
+                    </div>
+                    { self.render_posts() }
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl PostsPage {
+    fn reload_from_first_page(&mut self) -> ShouldRender {
+        self.query.page = 1;
+        self.link.send_message(PostsMsg::Load);
+        false
+    }
+
+    fn sort(&self) -> (&'static str, SortOrder) {
+        let key = match self.query.sort {
+            PostSort::Title => "title",
+            PostSort::Status => "status",
+            PostSort::UpdatedAt => "updated_at",
+        };
+
+        let order = match self.query.order {
+            Order::Asc => SortOrder::Ascending,
+            Order::Desc => SortOrder::Descending,
+        };
+
+        (key, order)
+    }
+
+    fn render_posts(&self) -> Html {
+        let posts = match self.posts {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入文章，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        let columns = vec![
+            TableColumn::new("title", "標題").sortable(),
+            TableColumn::new("status", "狀態").sortable().width("100px"),
+            TableColumn::new("author", "作者").width("140px"),
+            TableColumn::new("updated_at", "更新時間")
+                .sortable()
+                .width("120px"),
+            TableColumn::new("tags", "標籤"),
+        ];
+
+        let can_edit_others = self.props.resident.role.can(Permission::EditOthersPosts);
+
+        let rows = posts
+            .items
+            .iter()
+            .map(|m| {
+                let title = if can_edit_others || m.author.name == self.props.resident.name {
+                    html! { <a href=OfficePage::EditPost(Some(m.id)).to_hash()>{ m.title.clone() }</a> }
+                } else {
+                    html! { { m.title.clone() } }
+                };
+
+                vec![
+                    html! {
+                        <>
+                            { title }
+                            <div class="sub">{ format!("/{}", m.slug) }</div>
+                        </>
+                    },
+                    html! { <span class=format!("status {}", m.status.key())>{ m.status.label() }</span> },
+                    html! {
+                        { m.author.display_name() }
+                    },
+                    html! { { format_relative_time(m.updated_at) } },
+                    html! {
+                        { for m.tags.iter().map(|tag| html! { <span class="tag">{ format!("#{}", tag) }</span> }) }
+                    },
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let per_page = self.query.per_page;
+        let total_pages = posts.total / per_page + if posts.total % per_page > 0 { 1 } else { 0 };
+
+        html! {
+            <>
+                <Table columns=columns rows=rows sort=Some(self.sort()) empty_text="沒有符合條件的文章"
+                    onsort=self.link.callback(PostsMsg::Sort) />
+                {
+                    if self.loading {
+                        html! { <Spinner /> }
+                    } else {
+                        html! {
+                            <Pagination page=self.query.page total_pages=total_pages
+                                onchange=self.link.callback(PostsMsg::Page) />
+                        }
+                    }
+                }
+            </>
+        }
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
@@ -0,0 +1,583 @@
+use std::borrow::Cow;
+
+use js_sys::Uint8Array;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, File, Url};
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::{SudoAction, SudoRequest};
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Profile, ProfileUpdate, SigninResident};
+use crate::components::{
+    Avatar, Button, ButtonColor, Card, CropArea, ImageCropper, Input, InputType, InputValue,
+    Spinner,
+};
+use crate::css::{css, Stylesheet};
+use crate::helpers::window;
+use crate::imaging::ImageOptions;
+use crate::services::ImageWorker;
+use crate::traits::YieldStyle;
+use crate::{Error, Result};
+
+// Avatars are shown at 100px, this leaves room for high density screens.
+const AVATAR_SIZE: u32 = 400;
+const AVATAR_QUALITY: u8 = 90;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct ProfilePageProps {
+    pub client: Client,
+    pub image_options: ImageOptions,
+    pub onsudo: Callback<SudoRequest>,
+    // Emitted when the name or the avatar shown across the office has changed.
+    pub onupdated: Callback<SigninResident>,
+}
+
+impl YieldStyle for ProfilePageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-profile-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            & > div {
+                margin-bottom: 20px;
+            }
+
+            .title {
+                font-size: 1.2rem;
+                margin-bottom: 10px;
+            }
+
+            label {
+                font-size: 0.8rem;
+                color: rgb(100, 100, 100);
+                margin-top: 10px;
+                margin-bottom: 5px;
+            }
+
+            textarea {
+                width: 100%;
+                height: 120px;
+                box-sizing: border-box;
+                padding: 10px 15px;
+                border: 1px solid rgb(150, 150, 150);
+                border-radius: 20px;
+                font-size: 1rem;
+                font-family: inherit;
+                resize: vertical;
+                outline: 0;
+            }
+
+            textarea:focus {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .avatar {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: flex-start;
+                width: 100%;
+            }
+
+            .avatar > * {
+                margin-right: 20px;
+                margin-bottom: 10px;
+            }
+
+            .avatar-actions {
+                display: flex;
+                flex-direction: column;
+                align-items: flex-start;
+            }
+
+            .avatar-actions > * {
+                margin-bottom: 10px;
+            }
+
+            .buttons {
+                display: flex;
+                flex-direction: row;
+                margin-top: 10px;
+            }
+
+            .buttons > * {
+                margin-right: 5px;
+            }
+
+            .choose {
+                display: inline-block;
+                cursor: pointer;
+                color: rgb(92, 184, 230);
+            }
+
+            .choose input {
+                display: none;
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum ProfileMsg {
+    Load,
+    Loaded(Result<Profile>),
+
+    DisplayNameInput(InputValue),
+    EmailInput(InputValue),
+    BioInput(String),
+    Save,
+    Perform,
+    Saved(Result<Profile>),
+
+    ChooseAvatar(Option<File>),
+    AvatarRead(File, Result<Vec<u8>>),
+    Crop(CropArea),
+    ApplyCrop,
+    // The object URL of the avatar which was cropped.
+    Cropped(String, Result<Blob>),
+    CancelAvatar,
+    UploadAvatar,
+    AvatarUploaded(Result<Profile>),
+}
+
+// An avatar chosen from a file, cropped before uploading.
+#[derive(Debug)]
+struct AvatarSource {
+    filename: String,
+    data: Vec<u8>,
+    // Object URL of the original file for the cropper.
+    url: String,
+    crop: Option<CropArea>,
+    // The cropped avatar and its object URL.
+    cropped: Option<(Blob, String)>,
+}
+
+impl Drop for AvatarSource {
+    fn drop(&mut self) {
+        let _ = Url::revoke_object_url(&self.url);
+        if let Some((_, ref m)) = self.cropped {
+            let _ = Url::revoke_object_url(m);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ProfilePage {
+    props: ProfilePageProps,
+    link: ComponentLink<Self>,
+
+    profile: Option<Profile>,
+    failed: bool,
+
+    update: ProfileUpdate,
+    email_valid: bool,
+    saving: bool,
+
+    avatar: Option<AvatarSource>,
+    // Decoding a photo takes seconds, which would freeze the page on the main thread.
+    image_worker: Option<ImageWorker>,
+    cropping: bool,
+    uploading: bool,
+}
+
+impl Component for ProfilePage {
+    type Message = ProfileMsg;
+    type Properties = ProfilePageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(ProfileMsg::Load);
+
+        Self {
+            props,
+            link,
+
+            profile: None,
+            failed: false,
+
+            update: ProfileUpdate::default(),
+            email_valid: true,
+            saving: false,
+
+            avatar: None,
+            image_worker: None,
+            cropping: false,
+            uploading: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ProfileMsg::Load => {
+                let client = self.props.client.clone();
+                self.link
+                    .send_future(async move { ProfileMsg::Loaded(client.profile().await) });
+                false
+            }
+            ProfileMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        self.update = ProfileUpdate::from(&m);
+                        self.profile = Some(m);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load profile: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            ProfileMsg::DisplayNameInput(m) => {
+                let m = m.into_string();
+                self.update.display_name = if m.trim().is_empty() { None } else { Some(m) };
+                false
+            }
+            ProfileMsg::EmailInput(m) => {
+                self.email_valid = m.is_valid();
+                self.update.email = m.into_string();
+                false
+            }
+            ProfileMsg::BioInput(m) => {
+                self.update.bio = m;
+                false
+            }
+            ProfileMsg::Save => {
+                if !self.email_valid || self.update.email.trim().is_empty() {
+                    window()
+                        .alert_with_message("請輸入有效的電子郵件。")
+                        .unwrap();
+                    return false;
+                }
+
+                let email_changed = self
+                    .profile
+                    .as_ref()
+                    .map(|m| m.email != self.update.email)
+                    .unwrap_or(false);
+
+                // Whoever controls the email can reset the password.
+                if email_changed {
+                    self.props.onsudo.emit(SudoRequest {
+                        action: SudoAction::ChangeEmail,
+                        proceed: self.link.callback(|_| ProfileMsg::Perform),
+                    });
+                    false
+                } else {
+                    self.update(ProfileMsg::Perform)
+                }
+            }
+            ProfileMsg::Perform => {
+                let client = self.props.client.clone();
+                let update = self.update.clone();
+                self.saving = true;
+                self.link.send_future(async move {
+                    ProfileMsg::Saved(client.update_profile(&update).await)
+                });
+                true
+            }
+            ProfileMsg::Saved(m) => {
+                self.saving = false;
+                self.on_profile_changed(m, "無法儲存個人資料，請稍後再試。")
+            }
+
+            ProfileMsg::ChooseAvatar(m) => {
+                let file = match m {
+                    Some(m) => m,
+                    None => return false,
+                };
+
+                self.link.send_future(async move {
+                    let data = JsFuture::from(file.array_buffer())
+                        .await
+                        .map(|m| Uint8Array::new(&m).to_vec())
+                        .map_err(Error::from);
+                    ProfileMsg::AvatarRead(file, data)
+                });
+                false
+            }
+            ProfileMsg::AvatarRead(file, m) => {
+                let data = match m {
+                    Ok(m) => m,
+                    Err(e) => {
+                        log::error!("Failed to read avatar: {}", e);
+                        return false;
+                    }
+                };
+
+                let url = match Url::create_object_url_with_blob(&file) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        log::error!("Failed to show avatar: {:?}", e);
+                        return false;
+                    }
+                };
+
+                self.avatar = Some(AvatarSource {
+                    filename: file.name(),
+                    data,
+                    url,
+                    crop: None,
+                    cropped: None,
+                });
+                true
+            }
+            ProfileMsg::Crop(m) => {
+                if let Some(ref mut avatar) = self.avatar {
+                    avatar.crop = Some(m);
+                }
+                false
+            }
+            ProfileMsg::ApplyCrop => {
+                let (data, url, crop) = match self.avatar {
+                    Some(AvatarSource {
+                        ref data,
+                        ref url,
+                        crop: Some(crop),
+                        ..
+                    }) if !self.cropping => (data.clone(), url.clone(), crop),
+                    _ => return false,
+                };
+                let worker = match self.image_worker() {
+                    Some(m) => m,
+                    None => {
+                        window()
+                            .alert_with_message("無法處理此圖片，請稍後再試。")
+                            .unwrap();
+                        return false;
+                    }
+                };
+
+                self.cropping = true;
+                self.link.send_future(async move {
+                    let cropped = worker
+                        .crop_square(&data, crop, AVATAR_SIZE, AVATAR_QUALITY)
+                        .await;
+                    ProfileMsg::Cropped(url, cropped)
+                });
+                true
+            }
+            ProfileMsg::Cropped(url, m) => {
+                self.cropping = false;
+
+                // Another avatar may have been chosen in the meantime.
+                let avatar = match self.avatar {
+                    Some(ref mut m) if m.url == url => m,
+                    _ => return true,
+                };
+
+                match m.and_then(|m| {
+                    let url = Url::create_object_url_with_blob(&m)?;
+                    Ok((m, url))
+                }) {
+                    Ok(m) => {
+                        if let Some((_, ref url)) = avatar.cropped {
+                            let _ = Url::revoke_object_url(url);
+                        }
+                        avatar.cropped = Some(m);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to crop avatar: {}", e);
+                        window()
+                            .alert_with_message("無法處理此圖片，請選擇其他圖片。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+            ProfileMsg::CancelAvatar => {
+                self.avatar = None;
+                true
+            }
+            ProfileMsg::UploadAvatar => {
+                let (blob, filename) = match self.avatar {
+                    Some(AvatarSource {
+                        cropped: Some((ref blob, _)),
+                        ref filename,
+                        ..
+                    }) => (blob.clone(), avatar_filename(filename, blob)),
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                self.uploading = true;
+                self.link.send_future(async move {
+                    ProfileMsg::AvatarUploaded(client.upload_avatar(&blob, &filename).await)
+                });
+                true
+            }
+            ProfileMsg::AvatarUploaded(m) => {
+                self.uploading = false;
+                if m.is_ok() {
+                    self.avatar = None;
+                }
+                self.on_profile_changed(m, "無法上傳頭像，請稍後再試。")
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let profile = match self.profile {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! {
+                    <Card with_margin={ false } width={ "100%" }>
+                        <SigninSubTitle>{ "無法載入個人資料，請稍後再試。" }</SigninSubTitle>
+                    </Card>
+                }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "頭像" }</div>
+                    { self.render_avatar(profile) }
+                </Card>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "個人資料" }</div>
+                    <label>{ "顯示名稱" }</label>
+                    <Input name="display_name" placeholder=profile.name.clone() width="100%"
+                        value=self.update.display_name.clone().unwrap_or_default()
+                        oninput=self.link.callback(ProfileMsg::DisplayNameInput) />
+                    <label>{ "電子郵件" }</label>
+                    <Input name="email" type_=InputType::Email placeholder="電子郵件" width="100%" required=true
+                        value=self.update.email.clone() oninput=self.link.callback(ProfileMsg::EmailInput) />
+                    <label>{ "自我介紹" }</label>
+                    <textarea placeholder="自我介紹" value=self.update.bio.clone()
+                        oninput=self.link.callback(|e: InputData| ProfileMsg::BioInput(e.value))></textarea>
+                    <div class="buttons">
+                        {
+                            if self.saving {
+                                html! { <Spinner /> }
+                            } else {
+                                html! {
+                                    <Button onclick=self.link.callback(|_| ProfileMsg::Save)>{ "儲存" }</Button>
+                                }
+                            }
+                        }
+                    </div>
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl ProfilePage {
+    fn image_worker(&mut self) -> Option<ImageWorker> {
+        if self.image_worker.is_none() {
+            match ImageWorker::new(self.props.image_options.clone()) {
+                Ok(m) => self.image_worker = Some(m),
+                Err(e) => log::error!("Failed to start image worker: {}", e),
+            }
+        }
+
+        self.image_worker.clone()
+    }
+
+    fn on_profile_changed(&mut self, result: Result<Profile>, message: &str) -> ShouldRender {
+        match result {
+            Ok(m) => {
+                self.props.onupdated.emit(SigninResident::from(&m));
+                self.update = ProfileUpdate::from(&m);
+                self.profile = Some(m);
+            }
+            Err(e) => {
+                log::error!("Failed to update profile: {}", e);
+                window().alert_with_message(message).unwrap();
+            }
+        }
+        true
+    }
+
+    fn render_avatar(&self, profile: &Profile) -> Html {
+        let choose = html! {
+            <label class="choose">
+                { "選擇圖片" }
+                <input type="file" accept="image/jpeg,image/png,image/webp"
+                    onchange=self.link.callback(|e: ChangeData| match e {
+                        ChangeData::Files(m) => ProfileMsg::ChooseAvatar(m.get(0)),
+                        _ => ProfileMsg::ChooseAvatar(None),
+                    }) />
+            </label>
+        };
+
+        let avatar = match self.avatar {
+            Some(ref m) => m,
+            None => {
+                let resident = SigninResident::from(profile);
+                return html! {
+                    <div class="avatar">
+                        <Avatar src=resident.avatar_url(100) blurhash=resident.avatar_blurhash.clone()
+                            name=resident.display_name().to_string() />
+                        <div class="avatar-actions">{ choose }</div>
+                    </div>
+                };
+            }
+        };
+
+        let preview = match avatar.cropped {
+            Some((_, ref url)) => html! { <Avatar src=Some(url.clone()) /> },
+            None => html! {},
+        };
+
+        let upload = if self.uploading {
+            html! { <Spinner /> }
+        } else {
+            html! {
+                <Button color=ButtonColor::Green disabled=avatar.cropped.is_none()
+                    onclick=self.link.callback(|_| ProfileMsg::UploadAvatar)>{ "儲存頭像" }</Button>
+            }
+        };
+
+        html! {
+            <div class="avatar">
+                <ImageCropper src=avatar.url.clone() onchange=self.link.callback(ProfileMsg::Crop) />
+                <div class="avatar-actions">
+                    { preview }
+                    {
+                        if self.cropping {
+                            html! { <Spinner /> }
+                        } else {
+                            html! {
+                                <Button color=ButtonColor::Black onclick=self.link.callback(|_| ProfileMsg::ApplyCrop)>{ "預覽" }</Button>
+                            }
+                        }
+                    }
+                    { upload }
+                    <Button onclick=self.link.callback(|_| ProfileMsg::CancelAvatar)>{ "取消" }</Button>
+                    { choose }
+                </div>
+            </div>
+        }
+    }
+}
+
+// The extension follows the format of the cropped avatar.
+fn avatar_filename(filename: &str, blob: &Blob) -> String {
+    let stem = match filename.rfind('.') {
+        Some(i) if i > 0 => &filename[..i],
+        _ => filename,
+    };
+    let extension = if blob.type_() == "image/png" {
+        "png"
+    } else {
+        "jpg"
+    };
+
+    format!("{}.{}", stem, extension)
+}
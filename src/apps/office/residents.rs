@@ -0,0 +1,490 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Invitation, Resident, ResidentUpdate, Role, SigninResident};
+use crate::components::{
+    Button, ButtonColor, Card, Input, InputType, InputValue, Spinner, Table, TableColumn,
+};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{format_date, window};
+use crate::traits::YieldStyle;
+use crate::Result;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct ResidentsPageProps {
+    pub client: Client,
+    // The signed in resident, who cannot change their own role.
+    pub resident: SigninResident,
+}
+
+impl YieldStyle for ResidentsPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-residents-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .invite {
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                align-items: center;
+                width: 100%;
+                margin-bottom: 10px;
+            }
+
+            .invite > * {
+                margin: 5px;
+            }
+
+            .content {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+            }
+
+            .list {
+                flex-grow: 1;
+                min-width: 0;
+            }
+
+            a {
+                color: inherit;
+                cursor: pointer;
+            }
+
+            a:hover {
+                text-decoration: underline;
+            }
+
+            .sub {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .pending {
+                display: inline-block;
+                margin-left: 5px;
+                padding-left: 10px;
+                padding-right: 10px;
+                border-radius: 500px;
+                font-size: 0.8rem;
+                line-height: 22px;
+                color: white;
+                background-color: rgb(150, 150, 150);
+            }
+
+            .editor {
+                display: flex;
+                flex-direction: column;
+                width: 300px;
+                flex-shrink: 0;
+                margin-left: 20px;
+            }
+
+            .editor > * {
+                margin-bottom: 10px;
+            }
+
+            .editor h3 {
+                margin-top: 10px;
+                font-weight: normal;
+            }
+
+            .editor label {
+                font-size: 0.8rem;
+                color: rgb(100, 100, 100);
+            }
+
+            select {
+                height: 40px;
+                padding-left: 15px;
+                padding-right: 15px;
+                border: 1px solid rgb(150, 150, 150);
+                border-radius: 500px;
+                background-color: white;
+                font-size: 1rem;
+                outline: 0;
+            }
+
+            .editor select {
+                width: 100%;
+            }
+
+            select:focus {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .hint {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum ResidentsMsg {
+    Load,
+    Loaded(Result<Vec<Resident>>),
+
+    InviteEmailInput(InputValue),
+    InviteRoleChange(String),
+    Invite,
+    Invited(Result<Resident>),
+
+    Select(u64),
+    DisplayNameInput(InputValue),
+    EmailInput(InputValue),
+    RoleChange(String),
+    Save,
+    Saved(Result<Resident>),
+}
+
+#[derive(Debug)]
+pub(crate) struct ResidentsPage {
+    props: ResidentsPageProps,
+    link: ComponentLink<Self>,
+
+    residents: Option<Vec<Resident>>,
+    failed: bool,
+
+    invite_email: String,
+    invite_email_valid: bool,
+    invite_role: Role,
+    inviting: bool,
+
+    selected: Option<u64>,
+    update: Option<ResidentUpdate>,
+    saving: bool,
+}
+
+impl Component for ResidentsPage {
+    type Message = ResidentsMsg;
+    type Properties = ResidentsPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(ResidentsMsg::Load);
+
+        Self {
+            props,
+            link,
+
+            residents: None,
+            failed: false,
+
+            invite_email: String::new(),
+            invite_email_valid: false,
+            invite_role: Role::Author,
+            inviting: false,
+
+            selected: None,
+            update: None,
+            saving: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ResidentsMsg::Load => {
+                let client = self.props.client.clone();
+                self.link
+                    .send_future(async move { ResidentsMsg::Loaded(client.residents().await) });
+                false
+            }
+            ResidentsMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        self.residents = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load residents: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            ResidentsMsg::InviteEmailInput(m) => {
+                let is_valid = m.is_valid();
+                self.invite_email = m.into_string();
+                self.invite_email_valid = is_valid && !self.invite_email.trim().is_empty();
+                true
+            }
+            ResidentsMsg::InviteRoleChange(m) => {
+                if let Some(role) = role_from_key(&m) {
+                    self.invite_role = role;
+                }
+                false
+            }
+            ResidentsMsg::Invite => {
+                if !self.invite_email_valid {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                let invitation = Invitation {
+                    email: self.invite_email.trim().to_string(),
+                    role: self.invite_role,
+                };
+                self.inviting = true;
+                self.link.send_future(async move {
+                    ResidentsMsg::Invited(client.invite_resident(&invitation).await)
+                });
+                true
+            }
+            ResidentsMsg::Invited(m) => {
+                self.inviting = false;
+                match m {
+                    Ok(m) => {
+                        self.invite_email = String::new();
+                        self.invite_email_valid = false;
+                        if let Some(ref mut residents) = self.residents {
+                            residents.push(m);
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to invite resident: {}", e);
+                        window()
+                            .alert_with_message("無法發送邀請，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+
+            ResidentsMsg::Select(id) => {
+                self.update = self.resident(id).map(ResidentUpdate::from);
+                self.selected = Some(id);
+                true
+            }
+            ResidentsMsg::DisplayNameInput(m) => {
+                if let Some(ref mut update) = self.update {
+                    let m = m.into_string();
+                    update.display_name = if m.trim().is_empty() { None } else { Some(m) };
+                }
+                false
+            }
+            ResidentsMsg::EmailInput(m) => {
+                if let Some(ref mut update) = self.update {
+                    update.email = m.into_string();
+                }
+                false
+            }
+            ResidentsMsg::RoleChange(m) => {
+                if let (Some(update), Some(role)) = (self.update.as_mut(), role_from_key(&m)) {
+                    update.role = role;
+                }
+                false
+            }
+            ResidentsMsg::Save => {
+                let (id, update) = match (self.selected, self.update.clone()) {
+                    (Some(id), Some(m)) if !m.email.trim().is_empty() => (id, m),
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                self.saving = true;
+                self.link.send_future(async move {
+                    ResidentsMsg::Saved(client.update_resident(id, &update).await)
+                });
+                true
+            }
+            ResidentsMsg::Saved(m) => {
+                self.saving = false;
+                match m {
+                    Ok(m) => {
+                        if let Some(resident) = self
+                            .residents
+                            .as_mut()
+                            .and_then(|residents| residents.iter_mut().find(|r| r.id == m.id))
+                        {
+                            *resident = m;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save resident: {}", e);
+                        window()
+                            .alert_with_message("無法儲存變更，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    { self.render_invite() }
+                    <div class="content">
+                        <div class="list">{ self.render_residents() }</div>
+                        { self.render_editor() }
+                    </div>
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl ResidentsPage {
+    fn resident(&self, id: u64) -> Option<&Resident> {
+        self.residents.as_ref()?.iter().find(|m| m.id == id)
+    }
+
+    fn render_role_select<F>(&self, selected: Role, disabled: bool, onchange: F) -> Html
+    where
+        F: 'static + Fn(String) -> ResidentsMsg,
+    {
+        html! {
+            <select disabled=disabled onchange=self.link.callback(move |e: ChangeData| match e {
+                ChangeData::Select(m) => onchange(m.value()),
+                _ => onchange(String::new()),
+            })>
+                {
+                    for Role::all().iter().map(|m| html! {
+                        <option value=m.key() selected=*m == selected>{ m.label() }</option>
+                    })
+                }
+            </select>
+        }
+    }
+
+    fn render_invite(&self) -> Html {
+        html! {
+            <div class="invite">
+                <Input name="email" type_=InputType::Email placeholder="電子郵件" width="240px"
+                    value=self.invite_email.clone() oninput=self.link.callback(ResidentsMsg::InviteEmailInput) />
+                { self.render_role_select(self.invite_role, self.inviting, ResidentsMsg::InviteRoleChange) }
+                {
+                    if self.inviting {
+                        html! { <Spinner /> }
+                    } else {
+                        html! {
+                            <Button color=ButtonColor::Green disabled=!self.invite_email_valid
+                                onclick=self.link.callback(|_| ResidentsMsg::Invite)>{ "邀請" }</Button>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn render_residents(&self) -> Html {
+        let residents = match self.residents {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入住民，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        let columns = vec![
+            TableColumn::new("name", "名稱"),
+            TableColumn::new("email", "電子郵件"),
+            TableColumn::new("role", "角色").width("100px"),
+            TableColumn::new("created_at", "加入時間").width("120px"),
+        ];
+
+        let rows = residents
+            .iter()
+            .map(|m| {
+                let id = m.id;
+                let name = m.display_name.clone().unwrap_or_else(|| m.name.clone());
+                vec![
+                    html! {
+                        <>
+                            <a onclick=self.link.callback(move |_| ResidentsMsg::Select(id))>{ name }</a>
+                            {
+                                if m.pending {
+                                    html! { <span class="pending">{ "邀請中" }</span> }
+                                } else {
+                                    html! { <div class="sub">{ format!("@{}", m.name) }</div> }
+                                }
+                            }
+                        </>
+                    },
+                    html! { { m.email.clone() } },
+                    html! { { m.role.label() } },
+                    html! { { format_date(m.created_at) } },
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        html! {
+            <Table columns=columns rows=rows empty_text="尚未有任何住民" />
+        }
+    }
+
+    fn render_editor(&self) -> Html {
+        let (resident, update) = match (self.selected.and_then(|m| self.resident(m)), &self.update)
+        {
+            (Some(m), Some(update)) => (m, update),
+            _ => {
+                return html! {
+                    <div class="editor">
+                        <div class="hint">{ "選擇住民以編輯資料與角色。" }</div>
+                    </div>
+                }
+            }
+        };
+
+        let is_self = resident.name == self.props.resident.name;
+
+        html! {
+            <div class="editor">
+                <h3>{ format!("@{}", resident.name) }</h3>
+                <label>{ "顯示名稱" }</label>
+                <Input name="display_name" placeholder="顯示名稱" width="100%"
+                    value=update.display_name.clone().unwrap_or_default()
+                    oninput=self.link.callback(ResidentsMsg::DisplayNameInput) />
+                <label>{ "電子郵件" }</label>
+                <Input name="email" type_=InputType::Email placeholder="電子郵件" width="100%"
+                    value=update.email.clone() oninput=self.link.callback(ResidentsMsg::EmailInput) />
+                <label>{ "角色" }</label>
+                { self.render_role_select(update.role, is_self, ResidentsMsg::RoleChange) }
+                {
+                    if is_self {
+                        html! { <div class="hint">{ "你無法更改自己的角色。" }</div> }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if self.saving {
+                        html! { <Spinner /> }
+                    } else {
+                        html! {
+                            <div>
+                                <Button height="32px" color=ButtonColor::Black
+                                    onclick=self.link.callback(|_| ResidentsMsg::Save)>{ "儲存" }</Button>
+                            </div>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+fn role_from_key(key: &str) -> Option<Role> {
+    Role::all().iter().find(|m| m.key() == key).copied()
+}
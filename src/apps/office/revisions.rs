@@ -0,0 +1,294 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Post, Revision};
+use crate::components::{Button, ButtonColor, Card, DiffView, Spinner, Table, TableColumn};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{format_date_time, window};
+use crate::traits::YieldStyle;
+use crate::Result;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct RevisionHistoryProps {
+    pub client: Client,
+    pub post_id: u64,
+    // Reloads revisions when the post is saved.
+    pub updated_at: i64,
+
+    #[prop_or_else(Callback::noop)]
+    pub onrestored: Callback<Post>,
+}
+
+impl YieldStyle for RevisionHistoryProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-revision-history".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+            width: 100%;
+            margin-top: 20px;
+
+            h3 {
+                margin-top: 0;
+                font-weight: normal;
+            }
+
+            .title-change {
+                width: 100%;
+                margin-top: 20px;
+                margin-bottom: 10px;
+                font-size: 0.9rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .diff {
+                width: 100%;
+                margin-top: 10px;
+                padding-top: 10px;
+                border-top: 1px solid rgba(150, 150, 150, 0.2);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum RevisionHistoryMsg {
+    Load,
+    Loaded(Result<Vec<Revision>>),
+
+    SelectBase(u64),
+    SelectCompared(u64),
+
+    Restore(u64),
+    Restored(Result<Post>),
+}
+
+#[derive(Debug)]
+pub(crate) struct RevisionHistory {
+    props: RevisionHistoryProps,
+    link: ComponentLink<Self>,
+
+    revisions: Option<Vec<Revision>>,
+    failed: bool,
+    restoring: bool,
+
+    // The older side of the comparison.
+    base: Option<u64>,
+    compared: Option<u64>,
+}
+
+impl Component for RevisionHistory {
+    type Message = RevisionHistoryMsg;
+    type Properties = RevisionHistoryProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(RevisionHistoryMsg::Load);
+
+        Self {
+            props,
+            link,
+
+            revisions: None,
+            failed: false,
+            restoring: false,
+
+            base: None,
+            compared: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            RevisionHistoryMsg::Load => {
+                let client = self.props.client.clone();
+                let post_id = self.props.post_id;
+                self.link.send_future(async move {
+                    RevisionHistoryMsg::Loaded(client.revisions(post_id).await)
+                });
+                false
+            }
+            RevisionHistoryMsg::Loaded(m) => {
+                match m {
+                    Ok(m) => {
+                        // Compares the latest revision with the one before by default.
+                        self.compared = m.first().map(|m| m.id);
+                        self.base = m.get(1).or_else(|| m.first()).map(|m| m.id);
+                        self.revisions = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load revisions: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            RevisionHistoryMsg::SelectBase(m) => {
+                self.base = Some(m);
+                true
+            }
+            RevisionHistoryMsg::SelectCompared(m) => {
+                self.compared = Some(m);
+                true
+            }
+
+            RevisionHistoryMsg::Restore(revision_id) => {
+                let confirmed = window()
+                    .confirm_with_message("確定要還原至此版本嗎？尚未儲存的變更將會遺失。")
+                    .unwrap_or(false);
+
+                if !confirmed || self.restoring {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                let post_id = self.props.post_id;
+                self.restoring = true;
+                self.link.send_future(async move {
+                    RevisionHistoryMsg::Restored(
+                        client.restore_revision(post_id, revision_id).await,
+                    )
+                });
+                true
+            }
+            RevisionHistoryMsg::Restored(m) => {
+                self.restoring = false;
+                match m {
+                    // The list is reloaded when the new post arrives as props.
+                    Ok(m) => self.props.onrestored.emit(m),
+                    Err(e) => {
+                        log::error!("Failed to restore revision: {}", e);
+                        window()
+                            .alert_with_message("無法還原此版本，請稍後再試。")
+                            .unwrap();
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+
+        let reload =
+            props.post_id != self.props.post_id || props.updated_at != self.props.updated_at;
+        self.props = props;
+
+        if reload {
+            self.link.send_message(RevisionHistoryMsg::Load);
+        }
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <h3>{ "版本紀錄" }</h3>
+                    { self.render_revisions() }
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl RevisionHistory {
+    fn find(&self, id: Option<u64>) -> Option<&Revision> {
+        let id = id?;
+        self.revisions.as_ref()?.iter().find(|m| m.id == id)
+    }
+
+    fn render_revisions(&self) -> Html {
+        let revisions = match self.revisions {
+            Some(ref m) => m,
+            None if self.failed => {
+                return html! { <SigninSubTitle>{ "無法載入版本紀錄，請稍後再試。" }</SigninSubTitle> }
+            }
+            None => return html! { <Spinner /> },
+        };
+
+        let columns = vec![
+            TableColumn::new("base", "基準").width("60px"),
+            TableColumn::new("compared", "對照").width("60px"),
+            TableColumn::new("created_at", "時間"),
+            TableColumn::new("author", "作者"),
+            TableColumn::new("actions", "").width("100px"),
+        ];
+
+        let rows = revisions
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let id = m.id;
+                let author = m.author.display_name().to_string();
+
+                // The latest revision is the current content of the post.
+                let action = if i == 0 {
+                    html! { <SigninSubTitle>{ "目前版本" }</SigninSubTitle> }
+                } else if self.restoring {
+                    html! {}
+                } else {
+                    html! {
+                        <Button height="32px" color=ButtonColor::Black
+                            onclick=self.link.callback(move |_| RevisionHistoryMsg::Restore(id))>{ "還原" }</Button>
+                    }
+                };
+
+                vec![
+                    html! {
+                        <input type="radio" name="base" checked=self.base == Some(id)
+                            onclick=self.link.callback(move |_| RevisionHistoryMsg::SelectBase(id)) />
+                    },
+                    html! {
+                        <input type="radio" name="compared" checked=self.compared == Some(id)
+                            onclick=self.link.callback(move |_| RevisionHistoryMsg::SelectCompared(id)) />
+                    },
+                    html! { { format_date_time(m.created_at) } },
+                    html! { { author } },
+                    action,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        html! {
+            <>
+                <Table columns=columns rows=rows empty_text="沒有版本紀錄" />
+                { self.render_diff() }
+            </>
+        }
+    }
+
+    fn render_diff(&self) -> Html {
+        let (base, compared) = match (self.find(self.base), self.find(self.compared)) {
+            (Some(base), Some(compared)) => (base, compared),
+            _ => return html! {},
+        };
+
+        let title_change = if base.title != compared.title {
+            html! {
+                <div class="title-change">{ format!("標題：{} → {}", base.title, compared.title) }</div>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <>
+                { title_change }
+                <div class="diff">
+                    <DiffView old=base.body.clone() new=compared.body.clone() />
+                </div>
+            </>
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+use std::future::Future;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use super::{SudoAction, SudoRequest};
+use crate::app_components::SigninSubTitle;
+use crate::backend::{ApiToken, Client};
+use crate::components::{Button, ButtonColor, Card, Input, InputMsg, InputType, InputValue};
+use crate::css::{css, Stylesheet};
+use crate::helpers::WeakComponentLink;
+use crate::traits::YieldStyle;
+use crate::Result;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct SecurityPageProps {
+    pub client: Client,
+    pub onsudo: Callback<SudoRequest>,
+}
+
+impl YieldStyle for SecurityPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-security-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            & > div {
+                margin-bottom: 20px;
+            }
+
+            & > div > * {
+                margin-top: 8px;
+                margin-bottom: 8px;
+            }
+
+            .title {
+                font-size: 1.2rem;
+                width: 100%;
+            }
+
+            .token {
+                font-family: monospace;
+                word-break: break-all;
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum SecurityMsg {
+    PasswordInput(InputValue),
+    ConfirmPasswordInput(InputValue),
+
+    Request(SudoAction),
+    Perform(SudoAction),
+
+    PasswordChanged(Result<()>),
+    OtpDisabled(Result<()>),
+    ApiTokenRegenerated(Result<ApiToken>),
+}
+
+#[derive(Debug)]
+pub(crate) struct SecurityPage {
+    props: SecurityPageProps,
+    link: ComponentLink<Self>,
+    busy: bool,
+    message: Option<&'static str>,
+    api_token: Option<ApiToken>,
+
+    password_input_link: WeakComponentLink<Input>,
+    password_input_val: Option<InputValue>,
+
+    confirm_password_input_link: WeakComponentLink<Input>,
+    confirm_password_input_val: Option<InputValue>,
+}
+
+impl Component for SecurityPage {
+    type Message = SecurityMsg;
+    type Properties = SecurityPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            props,
+            link,
+            busy: false,
+            message: None,
+            api_token: None,
+
+            password_input_link: WeakComponentLink::default(),
+            password_input_val: None,
+
+            confirm_password_input_link: WeakComponentLink::default(),
+            confirm_password_input_val: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SecurityMsg::PasswordInput(m) => {
+                self.password_input_val = Some(m);
+                false
+            }
+            SecurityMsg::ConfirmPasswordInput(m) => {
+                self.confirm_password_input_val = Some(m);
+                false
+            }
+
+            SecurityMsg::Request(m) => {
+                if m == SudoAction::ChangePassword && self.validate_password().is_none() {
+                    return false;
+                }
+                self.require_sudo(m);
+                false
+            }
+            SecurityMsg::Perform(m) => self.perform(m),
+
+            SecurityMsg::PasswordChanged(m) => self.finish(m, "密碼已更改。"),
+            SecurityMsg::OtpDisabled(m) => self.finish(m, "兩步驗證已停用。"),
+            SecurityMsg::ApiTokenRegenerated(m) => match m {
+                Ok(m) => {
+                    self.api_token = Some(m);
+                    self.finish(Ok(()), "API 令牌已重新生成，請妥善保存。")
+                }
+                Err(e) => self.finish(Err(e), ""),
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()>
+                {
+                    if let Some(m) = self.message {
+                        html! {
+                            <Card with_margin={ false } width={ "100%" }>
+                                <SigninSubTitle>{ m }</SigninSubTitle>
+                            </Card>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "更改密碼" }</div>
+                    <Input type_=InputType::Password weak_link=self.password_input_link.clone()
+                        name="password" placeholder="新密碼" width="100%" oninput=self.link.callback(SecurityMsg::PasswordInput)
+                        required=true minlength=8 />
+                    <Input type_=InputType::Password weak_link=self.confirm_password_input_link.clone()
+                        name="confirm_password" placeholder="確認新密碼" width="100%" oninput=self.link.callback(SecurityMsg::ConfirmPasswordInput)
+                        required=true minlength=8 />
+                    <Button disabled=self.busy onclick=self.link.callback(|_| SecurityMsg::Request(SudoAction::ChangePassword))>{ "更改密碼" }</Button>
+                </Card>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "兩步驗證" }</div>
+                    <SigninSubTitle text_align="left">{ "停用兩步驗證後，登入時只需要輸入密碼。" }</SigninSubTitle>
+                    <Button color=ButtonColor::Red disabled=self.busy onclick=self.link.callback(|_| SecurityMsg::Request(SudoAction::DisableOtp))>{ "停用兩步驗證" }</Button>
+                </Card>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "API 令牌" }</div>
+                    <SigninSubTitle text_align="left">{ "重新生成後，舊的 API 令牌將會立即失效。" }</SigninSubTitle>
+                    {
+                        if let Some(ref m) = self.api_token {
+                            html! { <div class="token">{ m.token.clone() }</div> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <Button disabled=self.busy onclick=self.link.callback(|_| SecurityMsg::Request(SudoAction::RegenerateApiToken))>{ "重新生成 API 令牌" }</Button>
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl SecurityPage {
+    fn require_sudo(&self, action: SudoAction) {
+        if self.busy {
+            return;
+        }
+
+        let proceed_action = action.clone();
+        self.props.onsudo.emit(SudoRequest {
+            action,
+            proceed: self
+                .link
+                .callback(move |_| SecurityMsg::Perform(proceed_action.clone())),
+        });
+    }
+
+    fn perform(&mut self, action: SudoAction) -> ShouldRender {
+        let client = self.props.client.clone();
+
+        match action {
+            SudoAction::ChangePassword => {
+                let password = match self.validate_password() {
+                    Some(m) => m,
+                    None => return false,
+                };
+
+                self.start(async move {
+                    SecurityMsg::PasswordChanged(client.change_password(&password).await)
+                })
+            }
+            SudoAction::DisableOtp => {
+                self.start(async move { SecurityMsg::OtpDisabled(client.disable_otp().await) })
+            }
+            SudoAction::RegenerateApiToken => self.start(async move {
+                SecurityMsg::ApiTokenRegenerated(client.regenerate_api_token().await)
+            }),
+            // Performed by the profile page.
+            SudoAction::ChangeEmail => false,
+        }
+    }
+
+    fn validate_password(&self) -> Option<String> {
+        for link in [&self.password_input_link, &self.confirm_password_input_link].iter() {
+            if let Some(m) = link.borrow().as_ref() {
+                m.send_message(InputMsg::Validate);
+            }
+        }
+
+        let password = match self.password_input_val {
+            Some(ref m) if m.is_valid() => m.to_string(),
+            _ => return None,
+        };
+
+        match self.confirm_password_input_val {
+            Some(ref m) if m.is_valid() && **m == *password => Some(password),
+            Some(_) => {
+                if let Some(m) = self.confirm_password_input_link.borrow().as_ref() {
+                    m.send_message(InputMsg::BecomeInvalid("兩次輸入的密碼不一致".to_string()));
+                }
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn start<F>(&mut self, future: F) -> ShouldRender
+    where
+        F: 'static + Future<Output = SecurityMsg>,
+    {
+        self.busy = true;
+        self.message = None;
+        self.link.send_future(future);
+        true
+    }
+
+    fn finish(&mut self, result: Result<()>, success: &'static str) -> ShouldRender {
+        self.busy = false;
+        self.message = Some(match result {
+            Ok(_) => success,
+            Err(e) => {
+                log::error!("Failed to update security settings: {}", e);
+                "操作失敗，請稍後再試。"
+            }
+        });
+        true
+    }
+}
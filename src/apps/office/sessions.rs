@@ -0,0 +1,272 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Client, Session};
+use crate::components::{Button, ButtonColor, Card, Spinner};
+use crate::css::{css, Stylesheet};
+use crate::helpers::format_relative_time;
+use crate::traits::YieldStyle;
+use crate::user_agent::UserAgent;
+use crate::Result;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct SessionsPageProps {
+    pub client: Client,
+}
+
+impl YieldStyle for SessionsPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-sessions-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            & > div {
+                margin-bottom: 20px;
+            }
+
+            .title {
+                font-size: 1.2rem;
+            }
+
+            .session {
+                display: flex;
+                flex-direction: row;
+                align-items: center;
+                width: 100%;
+                padding-top: 10px;
+                padding-bottom: 10px;
+                border-bottom: 1px solid rgba(150, 150, 150, 0.3);
+            }
+
+            .session:last-child {
+                border-bottom: 0;
+            }
+
+            .session > i {
+                font-size: 1.5rem;
+                width: 50px;
+                text-align: center;
+                color: rgb(100, 100, 100);
+            }
+
+            .session .detail {
+                flex-grow: 1;
+            }
+
+            .session .sub {
+                font-size: 0.9rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .session .current {
+                color: rgb(50, 191, 50);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum SessionsMsg {
+    Load,
+    Loaded(Result<Vec<Session>>),
+    Revoke(String),
+    RevokeOthers,
+    Revoked(Result<()>),
+}
+
+#[derive(Debug)]
+pub(crate) struct SessionsPage {
+    props: SessionsPageProps,
+    link: ComponentLink<Self>,
+    sessions: Option<Vec<Session>>,
+    busy: bool,
+    failed: bool,
+}
+
+impl Component for SessionsPage {
+    type Message = SessionsMsg;
+    type Properties = SessionsPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(SessionsMsg::Load);
+
+        Self {
+            props,
+            link,
+            sessions: None,
+            busy: false,
+            failed: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let client = self.props.client.clone();
+
+        match msg {
+            SessionsMsg::Load => {
+                self.link
+                    .send_future(async move { SessionsMsg::Loaded(client.sessions().await) });
+                false
+            }
+            SessionsMsg::Loaded(m) => {
+                self.busy = false;
+                match m {
+                    Ok(mut m) => {
+                        // Current session first, then the most recently active ones.
+                        m.sort_by(|a, b| {
+                            b.current
+                                .cmp(&a.current)
+                                .then(b.last_active.cmp(&a.last_active))
+                        });
+                        self.sessions = Some(m);
+                        self.failed = false;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load sessions: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+            SessionsMsg::Revoke(id) => {
+                if self.busy {
+                    return false;
+                }
+                self.busy = true;
+                self.link.send_future(async move {
+                    SessionsMsg::Revoked(client.revoke_session(&id).await)
+                });
+                true
+            }
+            SessionsMsg::RevokeOthers => {
+                if self.busy {
+                    return false;
+                }
+                self.busy = true;
+                self.link.send_future(async move {
+                    SessionsMsg::Revoked(client.revoke_other_sessions().await)
+                });
+                true
+            }
+            SessionsMsg::Revoked(m) => {
+                if let Err(e) = m {
+                    log::error!("Failed to revoke session: {}", e);
+                    self.failed = true;
+                }
+                self.link.send_message(SessionsMsg::Load);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let sessions = match self.sessions {
+            Some(ref m) => m,
+            None => {
+                return html! {
+                    <div class=self.props.yield_style_class()>
+                        <Card with_margin={ false } width={ "100%" } height={ "200px" }>
+                            {
+                                if self.failed {
+                                    html! { <SigninSubTitle>{ "無法載入登入裝置，請稍後再試。" }</SigninSubTitle> }
+                                } else {
+                                    html! { <Spinner /> }
+                                }
+                            }
+                        </Card>
+                    </div>
+                };
+            }
+        };
+
+        let has_others = sessions.iter().any(|m| !m.current);
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="title">{ "已登入的裝置" }</div>
+                    <SigninSubTitle text_align="left">{ "如果你不認得以下任何一部裝置，請立即將其登出並更改密碼。" }</SigninSubTitle>
+                    { for sessions.iter().map(|m| self.render_session(m)) }
+                    {
+                        if self.failed {
+                            html! { <SigninSubTitle text_align="left">{ "操作失敗，請稍後再試。" }</SigninSubTitle> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        if has_others {
+                            html! {
+                                <Button color=ButtonColor::Red disabled=self.busy onclick=self.link.callback(|_| SessionsMsg::RevokeOthers)>
+                                    { "登出其它所有裝置" }
+                                </Button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl SessionsPage {
+    fn render_session(&self, session: &Session) -> Html {
+        let user_agent = UserAgent::parse(&session.user_agent);
+
+        let mut sub = Vec::with_capacity(3);
+        if let Some(ref m) = session.location {
+            sub.push(m.clone());
+        }
+        if let Some(ref m) = session.ip_address {
+            sub.push(m.clone());
+        }
+
+        let id = session.id.clone();
+
+        html! {
+            <div class="session">
+                <i class=user_agent.device.icon_class()></i>
+                <div class="detail">
+                    <div>{ format!("{} · {}", user_agent.browser, user_agent.os) }</div>
+                    <div class="sub">{ sub.join(" · ") }</div>
+                    {
+                        if session.current {
+                            html! { <div class="sub current">{ "目前使用中的裝置" }</div> }
+                        } else {
+                            html! { <div class="sub">{ format!("最後活動：{}", format_relative_time(session.last_active)) }</div> }
+                        }
+                    }
+                </div>
+                {
+                    if session.current {
+                        html! {}
+                    } else {
+                        html! {
+                            <Button color=ButtonColor::Red height="32px" disabled=self.busy onclick=self.link.callback(move |_| SessionsMsg::Revoke(id.clone()))>
+                                { "登出" }
+                            </Button>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,879 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use yew::prelude::*;
+use yewtil::future::LinkFuture;
+
+use crate::app_components::SigninSubTitle;
+use crate::backend::{Category, Client, Tag, TermDraft};
+use crate::components::{
+    Button, ButtonColor, Card, Input, InputValue, Spinner, Table, TableColumn,
+};
+use crate::css::{css, Stylesheet};
+use crate::helpers::window;
+use crate::traits::YieldStyle;
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Term {
+    Tag(u64),
+    Category(u64),
+    NewTag,
+    NewCategory,
+}
+
+impl Term {
+    fn is_tag(self) -> bool {
+        matches!(self, Term::Tag(_) | Term::NewTag)
+    }
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub(crate) struct TaxonomyPageProps {
+    pub client: Client,
+}
+
+impl YieldStyle for TaxonomyPageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-taxonomy-page".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: column;
+
+            .content {
+                display: flex;
+                flex-direction: row;
+                width: 100%;
+            }
+
+            .terms {
+                flex-grow: 1;
+                min-width: 0;
+            }
+
+            .section-header {
+                display: flex;
+                flex-direction: row;
+                align-items: center;
+                margin-top: 10px;
+                margin-bottom: 10px;
+            }
+
+            .section-header h3 {
+                flex-grow: 1;
+                margin: 0;
+                font-weight: normal;
+            }
+
+            a {
+                color: inherit;
+                cursor: pointer;
+            }
+
+            a:hover {
+                text-decoration: underline;
+            }
+
+            .sub {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            ul {
+                list-style: none;
+                margin: 0;
+                padding-left: 20px;
+            }
+
+            .tree > ul {
+                padding-left: 0;
+            }
+
+            .category {
+                display: flex;
+                flex-direction: row;
+                align-items: center;
+                padding: 5px 10px;
+                margin-bottom: 3px;
+                border-radius: 5px;
+                border: 2px solid transparent;
+                cursor: pointer;
+            }
+
+            .category:hover {
+                background-color: rgb(245, 245, 245);
+            }
+
+            .category.selected {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .category.dragging {
+                opacity: 0.4;
+            }
+
+            .category.drop-target, .root-drop.drop-target {
+                background-color: rgba(92, 184, 230, 0.1);
+                border-color: rgb(92, 184, 230);
+                border-style: dashed;
+            }
+
+            .category i {
+                margin-right: 10px;
+                color: rgb(200, 200, 200);
+                cursor: grab;
+            }
+
+            .category .count {
+                margin-left: auto;
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .root-drop {
+                padding: 10px;
+                margin-top: 5px;
+                border: 2px dashed rgb(200, 200, 200);
+                border-radius: 5px;
+                text-align: center;
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+
+            .editor {
+                display: flex;
+                flex-direction: column;
+                width: 300px;
+                flex-shrink: 0;
+                margin-left: 20px;
+            }
+
+            .editor > * {
+                margin-bottom: 10px;
+            }
+
+            .editor h3 {
+                margin-top: 10px;
+                font-weight: normal;
+            }
+
+            .editor label {
+                font-size: 0.8rem;
+                color: rgb(100, 100, 100);
+            }
+
+            textarea, select {
+                width: 100%;
+                box-sizing: border-box;
+                border: 1px solid rgb(150, 150, 150);
+                font-size: 1rem;
+                outline: 0;
+            }
+
+            textarea {
+                height: 100px;
+                padding: 10px 15px;
+                border-radius: 20px;
+                resize: vertical;
+                font-family: inherit;
+            }
+
+            select {
+                height: 40px;
+                padding-left: 15px;
+                padding-right: 15px;
+                border-radius: 500px;
+                background-color: white;
+            }
+
+            textarea:focus, select:focus {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .buttons {
+                display: flex;
+                flex-direction: row;
+            }
+
+            .buttons > * {
+                margin-right: 5px;
+            }
+
+            .hint {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+pub(crate) enum TaxonomyMsg {
+    Load,
+    TagsLoaded(Result<Vec<Tag>>),
+    CategoriesLoaded(Result<Vec<Category>>),
+
+    Select(Term),
+    NameInput(InputValue),
+    SlugInput(InputValue),
+    DescriptionInput(String),
+    ParentChange(String),
+    TargetChange(String),
+    Save,
+    Merge,
+    Delete,
+    // The term to select afterwards.
+    Saved(Result<Option<Term>>),
+
+    DragStart(u64),
+    // None for the top level.
+    DragOver(Option<u64>),
+    Drop(Option<u64>),
+    DragEnd,
+}
+
+#[derive(Debug)]
+pub(crate) struct TaxonomyPage {
+    props: TaxonomyPageProps,
+    link: ComponentLink<Self>,
+
+    tags: Option<Vec<Tag>>,
+    categories: Option<Vec<Category>>,
+    failed: bool,
+
+    selected: Option<Term>,
+    draft: TermDraft,
+    // The term posts are moved to when merging or deleting.
+    target: Option<u64>,
+    saving: bool,
+
+    dragging: Option<u64>,
+    drop_target: Option<Option<u64>>,
+}
+
+impl Component for TaxonomyPage {
+    type Message = TaxonomyMsg;
+    type Properties = TaxonomyPageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(TaxonomyMsg::Load);
+
+        Self {
+            props,
+            link,
+
+            tags: None,
+            categories: None,
+            failed: false,
+
+            selected: None,
+            draft: TermDraft::default(),
+            target: None,
+            saving: false,
+
+            dragging: None,
+            drop_target: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            TaxonomyMsg::Load => {
+                let client = self.props.client.clone();
+                self.link
+                    .send_future(async move { TaxonomyMsg::TagsLoaded(client.tags().await) });
+
+                let client = self.props.client.clone();
+                self.link.send_future(async move {
+                    TaxonomyMsg::CategoriesLoaded(client.categories().await)
+                });
+                false
+            }
+            TaxonomyMsg::TagsLoaded(m) => {
+                match m {
+                    Ok(m) => self.tags = Some(m),
+                    Err(e) => {
+                        log::error!("Failed to load tags: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+            TaxonomyMsg::CategoriesLoaded(m) => {
+                match m {
+                    Ok(m) => self.categories = Some(m),
+                    Err(e) => {
+                        log::error!("Failed to load categories: {}", e);
+                        self.failed = true;
+                    }
+                }
+                true
+            }
+
+            TaxonomyMsg::Select(term) => {
+                self.draft = match term {
+                    Term::Tag(id) => match self.tag(id) {
+                        Some(m) => m.into(),
+                        None => return false,
+                    },
+                    Term::Category(id) => match self.category(id) {
+                        Some(m) => m.into(),
+                        None => return false,
+                    },
+                    Term::NewTag | Term::NewCategory => TermDraft::default(),
+                };
+                self.selected = Some(term);
+                self.target = None;
+                true
+            }
+            TaxonomyMsg::NameInput(m) => {
+                self.draft.name = m.into_string();
+                false
+            }
+            TaxonomyMsg::SlugInput(m) => {
+                self.draft.slug = m.into_string();
+                false
+            }
+            TaxonomyMsg::DescriptionInput(m) => {
+                self.draft.description = m;
+                false
+            }
+            TaxonomyMsg::ParentChange(m) => {
+                self.draft.parent_id = m.parse().ok();
+                false
+            }
+            TaxonomyMsg::TargetChange(m) => {
+                self.target = m.parse().ok();
+                true
+            }
+            TaxonomyMsg::Save => {
+                let term = match self.selected {
+                    Some(m) if !self.draft.name.trim().is_empty() => m,
+                    _ => return false,
+                };
+
+                let client = self.props.client.clone();
+                let draft = self.draft.clone();
+                self.saving = true;
+                self.link.send_future(async move {
+                    let saved = match term {
+                        Term::Tag(id) => client.update_tag(id, &draft).await.map(|_| term),
+                        Term::Category(id) => {
+                            client.update_category(id, &draft).await.map(|_| term)
+                        }
+                        Term::NewTag => client.create_tag(&draft).await.map(|m| Term::Tag(m.id)),
+                        Term::NewCategory => client
+                            .create_category(&draft)
+                            .await
+                            .map(|m| Term::Category(m.id)),
+                    };
+                    TaxonomyMsg::Saved(saved.map(Some))
+                });
+                true
+            }
+            TaxonomyMsg::Merge => {
+                let (id, into) = match (self.selected, self.target) {
+                    (Some(Term::Tag(id)), Some(into)) if id != into => (id, into),
+                    _ => return false,
+                };
+
+                let confirmed = window()
+                    .confirm_with_message(&format!(
+                        "確定要將「{}」合併至「{}」嗎？此標籤將被刪除。",
+                        self.tag(id).map(|m| m.name.as_str()).unwrap_or_default(),
+                        self.tag(into).map(|m| m.name.as_str()).unwrap_or_default()
+                    ))
+                    .unwrap_or(false);
+                if !confirmed {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                self.saving = true;
+                self.link.send_future(async move {
+                    let merged = client.merge_tags(id, into).await;
+                    TaxonomyMsg::Saved(merged.map(|m| Some(Term::Tag(m.id))))
+                });
+                true
+            }
+            TaxonomyMsg::Delete => {
+                let term = match self.selected {
+                    Some(m @ Term::Tag(_)) | Some(m @ Term::Category(_)) => m,
+                    _ => return false,
+                };
+
+                let message = match (term.is_tag(), self.target) {
+                    (true, Some(_)) => "確定要刪除此標籤嗎？文章將改用所選的標籤。",
+                    (true, None) => "確定要刪除此標籤嗎？文章將不再有此標籤。",
+                    (false, Some(_)) => "確定要刪除此分類嗎？文章將移至所選的分類。",
+                    (false, None) => "確定要刪除此分類嗎？文章將不再屬於此分類。",
+                };
+                if !window().confirm_with_message(message).unwrap_or(false) {
+                    return false;
+                }
+
+                let client = self.props.client.clone();
+                let target = self.target;
+                self.saving = true;
+                self.link.send_future(async move {
+                    let deleted = match term {
+                        Term::Tag(id) => client.delete_tag(id, target).await,
+                        Term::Category(id) => client.delete_category(id, target).await,
+                        _ => Ok(()),
+                    };
+                    TaxonomyMsg::Saved(deleted.map(|_| None))
+                });
+                true
+            }
+            TaxonomyMsg::Saved(m) => {
+                self.saving = false;
+                match m {
+                    Ok(m) => {
+                        self.selected = m;
+                        self.target = None;
+                        self.link.send_message(TaxonomyMsg::Load);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save term: {}", e);
+                        window()
+                            .alert_with_message("無法儲存變更，請稍後再試。")
+                            .unwrap();
+                        self.link.send_message(TaxonomyMsg::Load);
+                    }
+                }
+                true
+            }
+
+            TaxonomyMsg::DragStart(id) => {
+                self.dragging = Some(id);
+                true
+            }
+            TaxonomyMsg::DragOver(target) => {
+                let target = match self.dragging {
+                    Some(id) if self.can_move(id, target) => Some(target),
+                    _ => None,
+                };
+
+                if self.drop_target != target {
+                    self.drop_target = target;
+                    true
+                } else {
+                    false
+                }
+            }
+            TaxonomyMsg::Drop(target) => {
+                let id = self.dragging.take();
+                self.drop_target = None;
+
+                let id = match id {
+                    Some(m) if self.can_move(m, target) => m,
+                    _ => return true,
+                };
+
+                let mut draft: TermDraft = match self.category(id) {
+                    Some(m) if m.parent_id != target => m.into(),
+                    _ => return true,
+                };
+                draft.parent_id = target;
+
+                // Moves it right away, the tree is reloaded once the server has it.
+                if let Some(m) = self
+                    .categories
+                    .as_mut()
+                    .and_then(|m| m.iter_mut().find(|m| m.id == id))
+                {
+                    m.parent_id = target;
+                }
+                if self.selected == Some(Term::Category(id)) {
+                    self.draft.parent_id = target;
+                }
+
+                let client = self.props.client.clone();
+                let selected = self.selected;
+                self.link.send_future(async move {
+                    let moved = client.update_category(id, &draft).await;
+                    TaxonomyMsg::Saved(moved.map(|_| selected))
+                });
+                true
+            }
+            TaxonomyMsg::DragEnd => {
+                self.dragging = None;
+                self.drop_target = None;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        if self.failed {
+            return html! {
+                <Card with_margin={ false } width={ "100%" }>
+                    <SigninSubTitle>{ "無法載入標籤與分類，請稍後再試。" }</SigninSubTitle>
+                </Card>
+            };
+        }
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <Card with_margin={ false } width={ "100%" } align_items={ "flex-start" }>
+                    <div class="content">
+                        <div class="terms">
+                            { self.render_tags() }
+                            { self.render_categories() }
+                        </div>
+                        { self.render_editor() }
+                    </div>
+                </Card>
+            </div>
+        }
+    }
+}
+
+impl TaxonomyPage {
+    fn tag(&self, id: u64) -> Option<&Tag> {
+        self.tags.as_ref()?.iter().find(|m| m.id == id)
+    }
+
+    fn category(&self, id: u64) -> Option<&Category> {
+        self.categories.as_ref()?.iter().find(|m| m.id == id)
+    }
+
+    // Whether id is of or is under the category of.
+    fn is_within(&self, id: u64, of: u64) -> bool {
+        let max_depth = self.categories.as_ref().map(|m| m.len()).unwrap_or(0);
+        let mut current = Some(id);
+
+        // The depth is limited in case the server sends a cycle.
+        for _ in 0..=max_depth {
+            match current {
+                Some(m) if m == of => return true,
+                Some(m) => current = self.category(m).and_then(|m| m.parent_id),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    // A category cannot be moved under itself or any of its children.
+    fn can_move(&self, id: u64, parent: Option<u64>) -> bool {
+        match parent {
+            Some(m) => !self.is_within(m, id),
+            None => true,
+        }
+    }
+
+    fn render_tags(&self) -> Html {
+        let tags = match self.tags {
+            Some(ref m) => m,
+            None => return html! { <Spinner /> },
+        };
+
+        let columns = vec![
+            TableColumn::new("name", "名稱"),
+            TableColumn::new("slug", "代稱"),
+            TableColumn::new("posts", "文章").width("80px"),
+        ];
+
+        let rows = tags
+            .iter()
+            .map(|m| {
+                let id = m.id;
+                vec![
+                    html! {
+                        <a onclick=self.link.callback(move |_| TaxonomyMsg::Select(Term::Tag(id)))>{ m.name.clone() }</a>
+                    },
+                    html! { <span class="sub">{ m.slug.clone() }</span> },
+                    html! { { m.post_count } },
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        html! {
+            <>
+                <div class="section-header">
+                    <h3>{ "標籤" }</h3>
+                    <Button height="32px" color=ButtonColor::Green
+                        onclick=self.link.callback(|_| TaxonomyMsg::Select(Term::NewTag))>{ "新標籤" }</Button>
+                </div>
+                <Table columns=columns rows=rows empty_text="尚未有任何標籤" />
+            </>
+        }
+    }
+
+    fn render_categories(&self) -> Html {
+        let categories = match self.categories {
+            Some(ref m) => m,
+            None => return html! { <Spinner /> },
+        };
+
+        // Categories with a missing parent are shown at the top level.
+        let mut children: HashMap<Option<u64>, Vec<&Category>> = HashMap::new();
+        for m in categories.iter() {
+            let parent = m.parent_id.filter(|id| self.category(*id).is_some());
+            children.entry(parent).or_default().push(m);
+        }
+
+        let root_class = if self.drop_target == Some(None) {
+            "root-drop drop-target"
+        } else {
+            "root-drop"
+        };
+
+        let root_drop = if self.dragging.is_some() {
+            html! {
+                <div class=root_class
+                    ondragover=self.link.callback(|e: DragEvent| {
+                        e.prevent_default();
+                        TaxonomyMsg::DragOver(None)
+                    })
+                    ondrop=self.link.callback(|e: DragEvent| {
+                        e.prevent_default();
+                        TaxonomyMsg::Drop(None)
+                    })>
+                    { "拖曳至此處成為頂層分類" }
+                </div>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <>
+                <div class="section-header">
+                    <h3>{ "分類" }</h3>
+                    <Button height="32px" color=ButtonColor::Green
+                        onclick=self.link.callback(|_| TaxonomyMsg::Select(Term::NewCategory))>{ "新分類" }</Button>
+                </div>
+                {
+                    if categories.is_empty() {
+                        html! { <div class="sub">{ "尚未有任何分類" }</div> }
+                    } else {
+                        html! {
+                            <div class="tree">
+                                { self.render_category_children(&children, None) }
+                                { root_drop }
+                            </div>
+                        }
+                    }
+                }
+            </>
+        }
+    }
+
+    fn render_category_children(
+        &self,
+        children: &HashMap<Option<u64>, Vec<&Category>>,
+        parent: Option<u64>,
+    ) -> Html {
+        let categories = match children.get(&parent) {
+            Some(m) => m,
+            None => return html! {},
+        };
+
+        html! {
+            <ul>
+                {
+                    for categories.iter().map(|m| html! {
+                        <li>
+                            { self.render_category(m) }
+                            { self.render_category_children(children, Some(m.id)) }
+                        </li>
+                    })
+                }
+            </ul>
+        }
+    }
+
+    fn render_category(&self, category: &Category) -> Html {
+        let id = category.id;
+
+        let mut class = vec!["category"];
+        if self.selected == Some(Term::Category(id)) {
+            class.push("selected");
+        }
+        if self.dragging == Some(id) {
+            class.push("dragging");
+        }
+        if self.drop_target == Some(Some(id)) {
+            class.push("drop-target");
+        }
+
+        html! {
+            <div class=class draggable="true"
+                onclick=self.link.callback(move |_| TaxonomyMsg::Select(Term::Category(id)))
+                ondragstart=self.link.callback(move |e: DragEvent| {
+                    // Firefox does not start dragging without data.
+                    if let Some(m) = e.data_transfer() {
+                        let _ = m.set_data("text/plain", &id.to_string());
+                    }
+                    TaxonomyMsg::DragStart(id)
+                })
+                ondragover=self.link.callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    TaxonomyMsg::DragOver(Some(id))
+                })
+                ondrop=self.link.callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    TaxonomyMsg::Drop(Some(id))
+                })
+                ondragend=self.link.callback(|_| TaxonomyMsg::DragEnd)>
+                <i class="fas fa-grip-vertical"></i>
+                <span>{ category.name.clone() }</span>
+                <span class="count">{ category.post_count }</span>
+            </div>
+        }
+    }
+
+    fn render_editor(&self) -> Html {
+        let term = match self.selected {
+            Some(m) => m,
+            None => {
+                return html! {
+                    <div class="editor">
+                        <div class="hint">{ "選擇標籤或分類以編輯，拖曳分類以調整層級。" }</div>
+                    </div>
+                }
+            }
+        };
+
+        let title = match term {
+            Term::Tag(_) => "編輯標籤",
+            Term::Category(_) => "編輯分類",
+            Term::NewTag => "新標籤",
+            Term::NewCategory => "新分類",
+        };
+
+        html! {
+            <div class="editor">
+                <h3>{ title }</h3>
+                <Input name="name" placeholder="名稱" width="100%" value=self.draft.name.clone()
+                    oninput=self.link.callback(TaxonomyMsg::NameInput) />
+                <Input name="slug" placeholder="代稱（留空則自動產生）" width="100%" value=self.draft.slug.clone()
+                    oninput=self.link.callback(TaxonomyMsg::SlugInput) />
+                <textarea placeholder="描述" value=self.draft.description.clone()
+                    oninput=self.link.callback(|e: InputData| TaxonomyMsg::DescriptionInput(e.value))></textarea>
+                { self.render_parent_select(term) }
+                {
+                    if self.saving {
+                        html! { <Spinner /> }
+                    } else {
+                        html! {
+                            <div class="buttons">
+                                <Button height="32px" color=ButtonColor::Black
+                                    onclick=self.link.callback(|_| TaxonomyMsg::Save)>{ "儲存" }</Button>
+                            </div>
+                        }
+                    }
+                }
+                { self.render_removal(term) }
+            </div>
+        }
+    }
+
+    fn render_parent_select(&self, term: Term) -> Html {
+        let id = match term {
+            Term::Category(m) => Some(m),
+            Term::NewCategory => None,
+            _ => return html! {},
+        };
+
+        let options = self
+            .categories
+            .iter()
+            .flatten()
+            .filter(|m| id.map(|id| !self.is_within(m.id, id)).unwrap_or(true))
+            .map(|m| {
+                html! {
+                    <option value=m.id.to_string() selected=self.draft.parent_id == Some(m.id)>{ m.name.clone() }</option>
+                }
+            });
+
+        html! {
+            <>
+                <label>{ "上層分類" }</label>
+                <select onchange=self.link.callback(|e: ChangeData| match e {
+                    ChangeData::Select(m) => TaxonomyMsg::ParentChange(m.value()),
+                    _ => TaxonomyMsg::ParentChange(String::new()),
+                })>
+                    <option value="" selected=self.draft.parent_id.is_none()>{ "無（頂層分類）" }</option>
+                    { for options }
+                </select>
+            </>
+        }
+    }
+
+    // Merging and deleting, for existing terms.
+    fn render_removal(&self, term: Term) -> Html {
+        let (id, label, none_label) = match term {
+            Term::Tag(m) => (m, "將文章改用標籤", "無（移除此標籤）"),
+            Term::Category(m) => (m, "將文章移至分類", "無（移出此分類）"),
+            _ => return html! {},
+        };
+
+        let options: Vec<(u64, String)> = if term.is_tag() {
+            self.tags
+                .iter()
+                .flatten()
+                .filter(|m| m.id != id)
+                .map(|m| (m.id, m.name.clone()))
+                .collect()
+        } else {
+            self.categories
+                .iter()
+                .flatten()
+                .filter(|m| m.id != id)
+                .map(|m| (m.id, m.name.clone()))
+                .collect()
+        };
+
+        let merge = if term.is_tag() {
+            html! {
+                <Button height="32px" disabled=self.target.is_none() || self.saving
+                    onclick=self.link.callback(|_| TaxonomyMsg::Merge)>{ "合併" }</Button>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <>
+                <label>{ label }</label>
+                <select onchange=self.link.callback(|e: ChangeData| match e {
+                    ChangeData::Select(m) => TaxonomyMsg::TargetChange(m.value()),
+                    _ => TaxonomyMsg::TargetChange(String::new()),
+                })>
+                    <option value="" selected=self.target.is_none()>{ none_label }</option>
+                    {
+                        for options.into_iter().map(|(id, name)| html! {
+                            <option value=id.to_string() selected=self.target == Some(id)>{ name }</option>
+                        })
+                    }
+                </select>
+                <div class="buttons">
+                    { merge }
+                    <Button height="32px" color=ButtonColor::Red disabled=self.saving
+                        onclick=self.link.callback(|_| TaxonomyMsg::Delete)>{ "刪除" }</Button>
+                </div>
+                {
+                    if term.is_tag() {
+                        html! { <div class="hint">{ "合併會將文章改用所選的標籤，然後刪除此標籤。" }</div> }
+                    } else {
+                        html! { <div class="hint">{ "刪除分類後，其子分類將移至上層分類。" }</div> }
+                    }
+                }
+            </>
+        }
+    }
+}
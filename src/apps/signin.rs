@@ -7,15 +7,23 @@ use yew::prelude::*;
 use yewtil::future::LinkFuture;
 
 use crate::app_components;
-use crate::backend::SigninResident;
+use crate::backend::{Client, SigninProgress, SigninResident, SigninThrottle};
 use crate::components;
-use crate::helpers::{call_after, document, sleep, WeakComponentLink};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{call_after, document, window, Instant, WeakComponentLink};
 use crate::manifest::Manifest;
+use crate::services::{TabEvent, TabSync};
 use crate::traits::YieldStyle;
+use crate::{Error, Result};
 
-use app_components::{BottomLinks, FlexGrow, SigninLogo, SigninProfile, SigninSubTitle};
+use app_components::{
+    BottomLinks, FlexGrow, SigninLogo, SigninProfile, SigninSubTitle, SigninTitle,
+};
 use components::{Body, Button, Card, Input, InputMsg, InputType, InputValue, Spinner};
 
+// Also written into the prerendered shell of the static pages.
+pub(crate) const SIGNIN_FOOTER: &str = "© 2020 未來領域管理局";
+
 #[derive(Clone, Properties, Debug, PartialEq)]
 pub(crate) struct SigninAppProps {
     pub manifest: Manifest,
@@ -26,6 +34,24 @@ impl SigninAppProps {
     pub fn set_first_section(&mut self, section: SigninSection) {
         self.first_section = section;
     }
+
+    pub fn card_height(&self) -> &'static str {
+        if self.first_section == SigninSection::Name {
+            "500px"
+        } else {
+            "550px"
+        }
+    }
+
+    pub fn to_title(&self) -> String {
+        format!("登入 - {}", self.manifest.site_title)
+    }
+
+    pub fn logo_url(&self) -> String {
+        self.manifest
+            .get_asset_url("images/favicon-wide.png")
+            .unwrap()
+    }
 }
 
 impl From<Manifest> for SigninAppProps {
@@ -44,29 +70,44 @@ pub(crate) enum SigninSection {
     Otp,
     Signup,
     SignupFinish,
+    Locked,
+    UnlockEmailSent,
 }
 
 impl SigninSection {
-    fn title(&self) -> &'static str {
+    pub fn title(&self) -> &'static str {
         match self {
             Self::Name => "歡迎來到未來領域管理局",
             Self::Password => "歡迎回來",
             Self::Otp => "額外的安全驗證",
             Self::Signup => "歡迎來到未來領域管理局",
             Self::SignupFinish => "註冊成功",
+            Self::Locked => "帳戶已被鎖定",
+            Self::UnlockEmailSent => "解鎖郵件已發送",
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SigninStatus {
+    Idle,
+    Busy,
+    // Attempts are rejected by the backend until the instant.
+    Throttled(Instant),
+}
+
+#[derive(Debug)]
 pub(crate) struct SigninApp {
     link: ComponentLink<Self>,
     root_ref: NodeRef,
     props: SigninAppProps,
     section: SigninSection,
-    busy: bool,
+    status: SigninStatus,
+    client: Client,
 
     resident: Option<SigninResident>,
+    // Sent back with the otp to finish signing in.
+    otp_challenge: Option<String>,
 
     name_input_link: WeakComponentLink<Input>,
     name_input_val: Option<InputValue>,
@@ -79,6 +120,8 @@ pub(crate) struct SigninApp {
 
     otp_input_link: WeakComponentLink<Input>,
     otp_input_val: Option<InputValue>,
+
+    tab_sync: TabSync,
 }
 
 #[derive(Debug, Clone)]
@@ -88,43 +131,100 @@ pub(crate) enum SigninMsg {
 
     NameInput(InputValue),
     NameNext,
+    ResidentFound(SigninResident),
 
     PasswordInput(InputValue),
     PasswordNext,
+    OtpRequired(String),
 
     OtpInput(InputValue),
     OtpNext,
+    SignedIn,
 
     EmailInput(InputValue),
 
     SignupNext,
 
+    Throttled(SigninThrottle),
+    ThrottleTick,
+    RequestUnlock,
+
+    TabEvent(TabEvent),
+
     Ignore,
 }
 
-impl YieldStyle for SigninApp {
+impl From<Error> for SigninMsg {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Throttled(m) => Self::Throttled(m),
+            e => {
+                log::error!("Failed to sign in: {}", e);
+                Self::Restart
+            }
+        }
+    }
+}
+
+impl From<Result<SigninSection>> for SigninMsg {
+    fn from(m: Result<SigninSection>) -> Self {
+        match m {
+            Ok(m) => Self::NextSection(m),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+impl YieldStyle for SigninAppProps {
     fn element_name(&self) -> Cow<'static, str> {
         "fl-signin-app".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        r#"
-        max-width: 1200px;
-        display: block;
-        padding-top: 20px;
-        & > div > * {
-            margin-top: 12px;
-            margin-bottom: 12px;
-        }
-        footer {
-            font-size: 0.8rem;
-            height: 30px;
-            line-height: 30px;
-            text-align: center;
-            color: rgb(150, 150, 150);
-        }
-        "#
-        .into()
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            max-width: 1200px;
+            display: block;
+            padding-top: 20px;
+            & > div > * {
+                margin-top: 12px;
+                margin-bottom: 12px;
+            }
+            .busy {
+                flex-grow: 1;
+                display: flex;
+                flex-direction: column;
+                justify-content: center;
+                align-items: center;
+                text-align: center;
+            }
+            .finish {
+                flex-grow: 1;
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                justify-content: space-around;
+                width: 100%;
+                margin-top: 80px;
+                margin-bottom: 80px;
+            }
+            .link {
+                text-decoration: none;
+                outline: 0 !important;
+            }
+            .link.button {
+                display: block;
+                width: 100%;
+            }
+            footer {
+                font-size: 0.8rem;
+                height: 30px;
+                line-height: 30px;
+                text-align: center;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
     }
 }
 
@@ -133,15 +233,23 @@ impl Component for SigninApp {
     type Properties = SigninAppProps;
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         let first_section = props.first_section.clone();
+        let client = props.manifest.client().expect("endpoint is not valid.");
+
+        let tab_sync = {
+            let link = link.clone();
+            TabSync::new(move |m| link.send_message(SigninMsg::TabEvent(m)))
+        };
 
         Self {
             link,
             props,
             root_ref: NodeRef::default(),
             section: first_section,
-            busy: false,
+            status: SigninStatus::Idle,
+            client,
 
             resident: None,
+            otp_challenge: None,
 
             name_input_link: WeakComponentLink::default(),
             name_input_val: None,
@@ -154,6 +262,8 @@ impl Component for SigninApp {
 
             otp_input_link: WeakComponentLink::default(),
             otp_input_val: None,
+
+            tab_sync,
         }
     }
 
@@ -177,18 +287,40 @@ impl Component for SigninApp {
                 false
             }
             SigninMsg::NameNext => self.on_name_next(),
+            SigninMsg::ResidentFound(m) => {
+                self.resident = Some(m);
+                self.section = SigninSection::Password;
+                self.status = SigninStatus::Idle;
+                true
+            }
             SigninMsg::PasswordNext => self.on_password_next(),
+            SigninMsg::OtpRequired(m) => {
+                self.otp_challenge = Some(m);
+                self.section = SigninSection::Otp;
+                self.status = SigninStatus::Idle;
+                true
+            }
             SigninMsg::OtpNext => self.on_otp_next(),
+            SigninMsg::SignedIn => self.on_signed_in(),
             SigninMsg::SignupNext => self.on_signup_next(),
 
+            SigninMsg::Throttled(m) => self.on_throttled(m),
+            SigninMsg::ThrottleTick => self.on_throttle_tick(),
+            SigninMsg::RequestUnlock => self.on_request_unlock(),
+
+            SigninMsg::TabEvent(TabEvent::SignedIn) => {
+                // Signed in from another tab, continue to the office.
+                window().location().set_href("./").unwrap();
+                false
+            }
+            SigninMsg::TabEvent(m) => {
+                m.apply_to_document();
+                false
+            }
+
             SigninMsg::NextSection(section) => {
                 self.section = section;
-                self.busy = false;
-                self.resident = Some(SigninResident {
-                        name: "futursolo".into(),
-                        display_name: Some("星川かえで".into()),
-                        avatar_url: Some("https://www.gravatar.com/avatar/0dd494a963ae648caebe34288b664ca6?s=200&d=mp".into()),
-                    });
+                self.status = SigninStatus::Idle;
                 true
             }
 
@@ -206,17 +338,13 @@ impl Component for SigninApp {
     }
 
     fn view(&self) -> Html {
-        let logo_url = self
-            .props
-            .manifest
-            .get_asset_url("images/favicon-wide.png")
-            .unwrap();
+        let logo_url = self.props.logo_url();
 
         html! {
-            <Body grey_background={ true } title={ self.to_title() }>
-                <div class=self.yield_style_class() ref=self.root_ref.clone()>
-                    <Card with_margin={ false } max_width={ "400px" } width={ "calc(100vw - 40px)" } height={ self.card_height() }>
-                        <div style="font-size: 1.5rem;">{ self.section.title() }</div>
+            <Body grey_background={ true } title={ self.props.to_title() }>
+                <div class=self.props.yield_style_class() ref=self.root_ref.clone()>
+                    <Card with_margin={ false } max_width={ "400px" } width={ "calc(100vw - 40px)" } height={ self.props.card_height() }>
+                        <SigninTitle>{ self.section.title() }</SigninTitle>
                         {
                             if let Some(ref m) = self.resident {
                                 html!{ <SigninProfile resident={ m.clone() } /> }
@@ -228,7 +356,7 @@ impl Component for SigninApp {
                         }
                         { self.render_section() }
                     </Card>
-                    <footer>{ "© 2020 未來領域管理局" }</footer>
+                    <footer>{ SIGNIN_FOOTER }</footer>
                 </div>
             </Body>
         }
@@ -236,21 +364,10 @@ impl Component for SigninApp {
 }
 
 impl SigninApp {
-    fn card_height(&self) -> &'static str {
-        if self.props.first_section == SigninSection::Name {
-            "500px"
-        } else {
-            "550px"
-        }
-    }
-
-    fn to_title(&self) -> String {
-        format!("登入 - {}", self.props.manifest.site_title)
-    }
-
     fn restart(&mut self) -> ShouldRender {
         let link = self.link.clone();
         self.resident = None;
+        self.otp_challenge = None;
         let first_section = self.props.first_section.clone();
         call_after(
             move || link.send_message(SigninMsg::NextSection(first_section.clone())),
@@ -260,6 +377,10 @@ impl SigninApp {
     }
 
     fn on_name_next(&mut self) -> ShouldRender {
+        if self.status != SigninStatus::Idle {
+            return false;
+        }
+
         self.name_input_link
             .borrow()
             .as_ref()
@@ -270,11 +391,15 @@ impl SigninApp {
             if !m.is_valid() {
                 return false;
             }
-            self.busy = true;
-
-            self.link.send_future(async {
-                sleep(Duration::from_secs(2)).await;
-                SigninMsg::NextSection(SigninSection::Password)
+            self.status = SigninStatus::Busy;
+
+            let client = self.client.clone();
+            let name = m.to_string();
+            self.link.send_future(async move {
+                match client.signin_resident(&name).await {
+                    Ok(m) => SigninMsg::ResidentFound(m),
+                    Err(e) => e.into(),
+                }
             });
             true
         } else {
@@ -283,21 +408,35 @@ impl SigninApp {
     }
 
     fn on_password_next(&mut self) -> ShouldRender {
+        if self.status != SigninStatus::Idle {
+            return false;
+        }
+
         self.password_input_link
             .borrow()
             .as_ref()
             .unwrap()
             .send_message(InputMsg::Validate);
 
+        let name = match self.signin_name() {
+            Some(m) => m,
+            None => return false,
+        };
+
         if let Some(ref m) = self.password_input_val {
             if !m.is_valid() {
                 return false;
             }
-            self.busy = true;
-
-            self.link.send_future(async {
-                sleep(Duration::from_secs(2)).await;
-                SigninMsg::NextSection(SigninSection::Otp)
+            self.status = SigninStatus::Busy;
+
+            let client = self.client.clone();
+            let password = m.to_string();
+            self.link.send_future(async move {
+                match client.signin_password(&name, &password).await {
+                    Ok(SigninProgress::Done) => SigninMsg::SignedIn,
+                    Ok(SigninProgress::OtpRequired(m)) => SigninMsg::OtpRequired(m),
+                    Err(e) => e.into(),
+                }
             });
             true
         } else {
@@ -306,15 +445,52 @@ impl SigninApp {
     }
 
     fn on_otp_next(&mut self) -> ShouldRender {
+        if self.status != SigninStatus::Idle {
+            return false;
+        }
+
         self.otp_input_link
             .borrow()
             .as_ref()
             .unwrap()
             .send_message(InputMsg::Validate);
+
+        let challenge = match self.otp_challenge {
+            Some(ref m) => m.clone(),
+            None => return false,
+        };
+
+        if let Some(ref m) = self.otp_input_val {
+            if !m.is_valid() {
+                return false;
+            }
+            self.status = SigninStatus::Busy;
+
+            let client = self.client.clone();
+            let otp = m.to_string();
+            self.link.send_future(async move {
+                match client.signin_otp(&challenge, &otp).await {
+                    Ok(()) => SigninMsg::SignedIn,
+                    Err(e) => e.into(),
+                }
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    fn on_signed_in(&mut self) -> ShouldRender {
+        self.tab_sync.broadcast(&TabEvent::SignedIn);
+        window().location().set_href("./").unwrap();
         false
     }
 
     fn on_signup_next(&mut self) -> ShouldRender {
+        if self.status != SigninStatus::Idle {
+            return false;
+        }
+
         self.name_input_link
             .borrow()
             .as_ref()
@@ -331,7 +507,7 @@ impl SigninApp {
             .unwrap()
             .send_message(InputMsg::Validate);
 
-        let _name_val: String = if let Some(ref m) = self.name_input_val {
+        let name: String = if let Some(ref m) = self.name_input_val {
             if !m.is_valid() {
                 return false;
             }
@@ -341,7 +517,7 @@ impl SigninApp {
             return false;
         };
 
-        let _email_val: String = if let Some(ref m) = self.email_input_val {
+        let email: String = if let Some(ref m) = self.email_input_val {
             if !m.is_valid() {
                 return false;
             }
@@ -351,7 +527,7 @@ impl SigninApp {
             return false;
         };
 
-        let _password_val: String = if let Some(ref m) = self.password_input_val {
+        let password: String = if let Some(ref m) = self.password_input_val {
             if !m.is_valid() {
                 return false;
             }
@@ -361,15 +537,102 @@ impl SigninApp {
             return false;
         };
 
-        self.busy = true;
+        self.status = SigninStatus::Busy;
 
-        self.link.send_future(async {
-            sleep(Duration::from_secs(2)).await;
-            SigninMsg::NextSection(SigninSection::SignupFinish)
+        let client = self.client.clone();
+        self.link.send_future(async move {
+            client
+                .signup(&name, &email, &password)
+                .await
+                .map(|_| SigninSection::SignupFinish)
         });
         true
     }
 
+    fn on_throttled(&mut self, throttle: SigninThrottle) -> ShouldRender {
+        match throttle {
+            SigninThrottle::RetryAfter(m) => {
+                self.status = SigninStatus::Throttled(Instant::now() + m);
+                self.schedule_throttle_tick();
+            }
+            SigninThrottle::Locked => {
+                self.section = SigninSection::Locked;
+                self.status = SigninStatus::Idle;
+            }
+        }
+        true
+    }
+
+    fn on_throttle_tick(&mut self) -> ShouldRender {
+        if let SigninStatus::Throttled(until) = self.status {
+            if Instant::now() >= until {
+                self.status = SigninStatus::Idle;
+            } else {
+                self.schedule_throttle_tick();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn schedule_throttle_tick(&self) {
+        let link = self.link.clone();
+        call_after(
+            move || link.send_message(SigninMsg::ThrottleTick),
+            Duration::from_secs(1),
+        );
+    }
+
+    fn on_request_unlock(&mut self) -> ShouldRender {
+        if self.status != SigninStatus::Idle {
+            return false;
+        }
+
+        let name = match self.signin_name() {
+            Some(m) => m,
+            None => return false,
+        };
+        self.status = SigninStatus::Busy;
+
+        let client = self.client.clone();
+        self.link.send_future(async move {
+            client
+                .request_unlock(&name)
+                .await
+                .map(|_| SigninSection::UnlockEmailSent)
+        });
+        true
+    }
+
+    // The resident may be locked out before they are found.
+    fn signin_name(&self) -> Option<String> {
+        match self.resident {
+            Some(ref m) => Some(m.name.clone()),
+            None => self.name_input_val.as_ref().map(|m| m.to_string()),
+        }
+    }
+
+    fn is_throttled(&self) -> bool {
+        matches!(self.status, SigninStatus::Throttled(_))
+    }
+
+    fn button_text(&self, text: &'static str) -> Cow<'static, str> {
+        if let SigninStatus::Throttled(until) = self.status {
+            let now = Instant::now();
+            let remaining = if now >= until {
+                0
+            } else {
+                let m = until - now;
+                m.as_secs() + if m.subsec_nanos() > 0 { 1 } else { 0 }
+            };
+
+            format!("請在 {} 秒後重試", remaining).into()
+        } else {
+            text.into()
+        }
+    }
+
     fn name_callback(&self) -> Callback<KeyboardEvent> {
         self.link.callback(|e: KeyboardEvent| {
             if e.key_code() == 13 {
@@ -417,10 +680,10 @@ impl SigninApp {
     }
 
     fn render_section(&self) -> Html {
-        if self.busy {
+        if self.status == SigninStatus::Busy {
             return html! {
                 <>
-                    <div style="text-align: center; flex-grow: 1; display: flex; justify-content: center; align-items: center; flex-direction: column;">
+                    <div class="busy">
                         <SigninSubTitle>{ "請稍候..." }</SigninSubTitle>
                         <Spinner />
                     </div>
@@ -437,9 +700,9 @@ impl SigninApp {
                         placeholder="用戶名" width="100%" oninput=self.link.callback(|s| SigninMsg::NameInput(s))
                         required=true minlength=3 maxlength=32 pattern="[a-zA-Z0-9]+"
                         pattern_hint="用戶名只能是數字和大小寫半角英文字符" />
-                    <Button width="100%" onclick=self.link.callback(|_| SigninMsg::NameNext)>{ "下一步" }</Button>
+                    <Button width="100%" disabled=self.is_throttled() onclick=self.link.callback(|_| SigninMsg::NameNext)>{ self.button_text("下一步") }</Button>
                     <BottomLinks>
-                        <a href="./signup" style="text-decoration: none; outline: 0 !important;"><SigninSubTitle text_align="right">{ "註冊新帳戶" }</SigninSubTitle></a>
+                        <a class="link" href="./signup"><SigninSubTitle text_align="right">{ "註冊新帳戶" }</SigninSubTitle></a>
                         <span>{ "忘記密碼" }</span>
                     </BottomLinks>
                 </>
@@ -451,7 +714,7 @@ impl SigninApp {
                     <Input type_=InputType::Password onkeyup=self.password_callback() weak_link=self.password_input_link.clone()
                         name="password" placeholder="密碼" width="100%" oninput=self.link.callback(|s| SigninMsg::PasswordInput(s))
                         required=true minlength=8 />
-                    <Button width="100%" onclick=self.link.callback(|_| SigninMsg::PasswordNext)>{ "登入" }</Button>
+                    <Button width="100%" disabled=self.is_throttled() onclick=self.link.callback(|_| SigninMsg::PasswordNext)>{ self.button_text("登入") }</Button>
                     <BottomLinks>
                         <span onclick=self.link.callback(|_e| SigninMsg::Restart)>{ "使用其它帳戶" }</span>
                         <span>{ "忘記密碼" }</span>
@@ -463,7 +726,7 @@ impl SigninApp {
                     <FlexGrow />
                     <SigninSubTitle>{ "由於你已啟用兩步驗證，你需要輸入兩步驗證代碼來完成登入。" }</SigninSubTitle>
                     <Input onkeyup=self.otp_callback() weak_link=self.otp_input_link.clone() name="otpcode" placeholder="兩步驗證代碼" width="100%" oninput=self.link.callback(|s| SigninMsg::OtpInput(s)) required=true minlength=6 maxlength=6 pattern="[0-9]+" pattern_hint="兩步驗證代碼只能是六位數字" />
-                    <Button width="100%" onclick=self.link.callback(|_| SigninMsg::OtpNext)>{ "驗證" }</Button>
+                    <Button width="100%" disabled=self.is_throttled() onclick=self.link.callback(|_| SigninMsg::OtpNext)>{ self.button_text("驗證") }</Button>
                     <BottomLinks>
                         <span onclick=self.link.callback(|_e| SigninMsg::Restart)>{ "使用其它帳戶" }</span>
                         <span>{ "忘記密碼" }</span>
@@ -484,17 +747,35 @@ impl SigninApp {
                     <Input type_=InputType::Email onkeyup=self.signup_callback() weak_link=self.email_input_link.clone() name="email"
                         placeholder="電子郵件地址" width="100%" oninput=self.link.callback(|s| SigninMsg::EmailInput(s))
                         required=true />
-                    <Button width="100%" onclick=self.link.callback(|_| SigninMsg::SignupNext)>{ "註冊" }</Button>
+                    <Button width="100%" disabled=self.is_throttled() onclick=self.link.callback(|_| SigninMsg::SignupNext)>{ self.button_text("註冊") }</Button>
                     <BottomLinks>
-                        <a href="./signin" style="text-decoration: none; outline: 0 !important;"><SigninSubTitle>{ "已有帳戶？" }</SigninSubTitle></a>
+                        <a class="link" href="./signin"><SigninSubTitle>{ "已有帳戶？" }</SigninSubTitle></a>
                     </BottomLinks>
                 </>
             },
             SigninSection::SignupFinish => html! {
                 <>
-                    <div style="flex-grow: 1; display: flex; flex-direction: column; align-items: center; justify-content: space-around; width: 100%; margin-top: 80px; margin-bottom: 80px;">
+                    <div class="finish">
                         <SigninSubTitle>{ "請點擊「繼續」來登入。" }</SigninSubTitle>
-                        <a href="./signin" style="text-decoration: none; outline: 0 !important; width: 100%; display: block;"><Button width="100%">{ "繼續" }</Button></a>
+                        <a class="link button" href="./signin"><Button width="100%">{ "繼續" }</Button></a>
+                    </div>
+                </>
+            },
+            SigninSection::Locked => html! {
+                <>
+                    <FlexGrow />
+                    <SigninSubTitle>{ "由於登入失敗次數過多，你的帳戶已被暫時鎖定。你可以通過電子郵件接收解鎖連結來解鎖帳戶。" }</SigninSubTitle>
+                    <Button width="100%" onclick=self.link.callback(|_| SigninMsg::RequestUnlock)>{ "發送解鎖郵件" }</Button>
+                    <BottomLinks>
+                        <span onclick=self.link.callback(|_e| SigninMsg::Restart)>{ "使用其它帳戶" }</span>
+                    </BottomLinks>
+                </>
+            },
+            SigninSection::UnlockEmailSent => html! {
+                <>
+                    <div class="finish">
+                        <SigninSubTitle>{ "請查看你的電子郵件，並點擊郵件中的連結來解鎖帳戶。" }</SigninSubTitle>
+                        <Button width="100%" onclick=self.link.callback(|_| SigninMsg::Restart)>{ "返回" }</Button>
                     </div>
                 </>
             },
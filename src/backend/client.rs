@@ -0,0 +1,227 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::SigninThrottle;
+use crate::helpers::window;
+use crate::{Error, Result};
+
+const TOKEN_KEY: &str = "fl-office-token";
+// Set while the office is locked, so reloading does not skip the lock.
+const LOCKED_KEY: &str = "fl-office-locked";
+
+// The path segment set of the URL standard, with the separator.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+// Escapes a value from the backend so it stays a single segment of the path.
+//
+// Dot segments are refused, they would still move up the path when escaped.
+pub(crate) fn encode_segment(segment: &str) -> Result<String> {
+    match segment {
+        "" | "." | ".." => Err(Error::InvalidSegment(segment.to_string())),
+        m => Ok(utf8_percent_encode(m, SEGMENT).to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Client {
+    endpoint: Url,
+}
+
+impl Client {
+    pub fn new<S: AsRef<str>>(endpoint: S) -> Result<Self> {
+        let endpoint = endpoint.as_ref();
+        let endpoint = if endpoint.ends_with('/') {
+            Url::parse(endpoint)?
+        } else {
+            Url::parse(&format!("{}/", endpoint))?
+        };
+
+        Ok(Self { endpoint })
+    }
+
+    pub fn token() -> Option<String> {
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|m| m.get_item(TOKEN_KEY).ok().flatten())
+    }
+
+    // Storage can throw, such as when it is full or disabled, which only costs the session.
+    pub fn set_token(token: Option<&str>) {
+        let storage = match window().local_storage().ok().flatten() {
+            Some(m) => m,
+            None => return,
+        };
+
+        let stored = match token {
+            Some(m) => storage.set_item(TOKEN_KEY, m),
+            None => storage
+                .remove_item(TOKEN_KEY)
+                .and_then(|_| storage.remove_item(LOCKED_KEY)),
+        };
+        if let Err(e) = stored {
+            log::error!("Failed to store the token: {:?}", e);
+        }
+    }
+
+    pub fn is_locked() -> bool {
+        window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|m| m.get_item(LOCKED_KEY).ok().flatten())
+            .is_some()
+    }
+
+    pub fn set_locked(locked: bool) {
+        let storage = match window().local_storage().ok().flatten() {
+            Some(m) => m,
+            None => return,
+        };
+
+        let stored = if locked {
+            storage.set_item(LOCKED_KEY, "1")
+        } else {
+            storage.remove_item(LOCKED_KEY)
+        };
+        if let Err(e) = stored {
+            log::error!("Failed to store the lock: {:?}", e);
+        }
+    }
+
+    pub(crate) fn url(&self, path: &str) -> Result<Url> {
+        Ok(self.endpoint.join(path)?)
+    }
+
+    pub(crate) fn builder(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let url = self.url(path)?;
+        let builder = reqwest::Client::new().request(method, url);
+
+        Ok(match Self::token() {
+            Some(m) => builder.bearer_auth(m),
+            None => builder,
+        })
+    }
+
+    async fn send(builder: RequestBuilder) -> Result<Response> {
+        let resp = builder.send().await?;
+
+        if let Some(m) = SigninThrottle::from_response(&resp) {
+            return Err(Error::Throttled(m));
+        }
+
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(resp.error_for_status()?)
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let builder = self.builder(Method::GET, path)?;
+        Ok(Self::send(builder).await?.json().await?)
+    }
+
+    pub(crate) async fn get_with_query<T, Q>(&self, path: &str, query: &Q) -> Result<T>
+    where
+        T: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        let builder = self.builder(Method::GET, path)?.query(query);
+        Ok(Self::send(builder).await?.json().await?)
+    }
+
+    pub(crate) async fn post<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        let builder = self.builder(Method::POST, path)?.json(body);
+        Ok(Self::send(builder).await?.json().await?)
+    }
+
+    pub(crate) async fn put<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        let builder = self.builder(Method::PUT, path)?.json(body);
+        Ok(Self::send(builder).await?.json().await?)
+    }
+
+    // For endpoints that respond with an empty body.
+    pub(crate) async fn post_empty<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<()> {
+        let builder = self.builder(Method::POST, path)?.json(body);
+        Self::send(builder).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn delete(&self, path: &str) -> Result<()> {
+        let builder = self.builder(Method::DELETE, path)?;
+        Self::send(builder).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn delete_with_query<Q: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<()> {
+        let builder = self.builder(Method::DELETE, path)?.query(query);
+        Self::send(builder).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    #[test]
+    fn test_encode_segment() {
+        assert_eq!(encode_segment("a1b2-c3").unwrap(), "a1b2-c3");
+        assert_eq!(encode_segment("../residents").unwrap(), "..%2Fresidents");
+        assert_eq!(encode_segment("a?b#c d").unwrap(), "a%3Fb%23c%20d");
+        assert_eq!(encode_segment("100%").unwrap(), "100%25");
+
+        for m in &["", ".", ".."] {
+            assert!(matches!(encode_segment(m), Err(Error::InvalidSegment(_))));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_locked() {
+        Client::set_token(Some("token"));
+        Client::set_locked(true);
+        assert!(Client::is_locked());
+
+        Client::set_locked(false);
+        assert!(!Client::is_locked());
+
+        // Signing out ends the lock with the session.
+        Client::set_locked(true);
+        Client::set_token(None);
+        assert!(!Client::is_locked());
+        assert_eq!(Client::token(), None);
+    }
+}
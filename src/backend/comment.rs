@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Client, Paginated};
+use crate::Result;
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    Pending,
+    Approved,
+    Spam,
+    Trash,
+}
+
+impl CommentStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pending => "待審核",
+            Self::Approved => "已核准",
+            Self::Spam => "垃圾留言",
+            Self::Trash => "回收桶",
+        }
+    }
+
+    pub fn all() -> &'static [CommentStatus] {
+        &[Self::Pending, Self::Approved, Self::Spam, Self::Trash]
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Comment {
+    pub id: u64,
+    pub post_id: u64,
+    pub post_title: String,
+    // None for comments that are not replies.
+    pub parent_id: Option<u64>,
+    pub author_name: String,
+    pub author_email: String,
+    pub author_url: Option<String>,
+    // Plain text.
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: i64,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct CommentQuery {
+    // Starts from 1.
+    pub page: u64,
+    pub per_page: u64,
+    pub status: CommentStatus,
+}
+
+impl Default for CommentQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 20,
+            status: CommentStatus::Pending,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ModerateRequest<'a> {
+    ids: &'a [u64],
+    status: CommentStatus,
+}
+
+#[derive(Serialize)]
+struct ReplyRequest<'a> {
+    body: &'a str,
+}
+
+impl Client {
+    // Newest first.
+    pub async fn comments(&self, query: &CommentQuery) -> Result<Paginated<Comment>> {
+        self.get_with_query("comments", query).await
+    }
+
+    // The comments the comment replies to, the top level one first.
+    pub async fn comment_context(&self, id: u64) -> Result<Vec<Comment>> {
+        self.get(&format!("comments/{}/context", id)).await
+    }
+
+    // Moves all of the comments to the status at once.
+    pub async fn moderate_comments(&self, ids: &[u64], status: CommentStatus) -> Result<()> {
+        self.post_empty("comments/moderate", &ModerateRequest { ids, status })
+            .await
+    }
+
+    // Replies as the current resident, the reply is approved right away.
+    pub async fn reply_to_comment(&self, id: u64, body: &str) -> Result<Comment> {
+        self.post(&format!("comments/{}/replies", id), &ReplyRequest { body })
+            .await
+    }
+}
@@ -0,0 +1,179 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, File, FormData, ProgressEvent, XmlHttpRequest};
+
+use super::{Client, Paginated};
+use crate::helpers::EventListener;
+use crate::{Error, Result};
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Media {
+    pub id: u64,
+    pub url: String,
+    pub filename: String,
+    pub content_type: String,
+    // In bytes.
+    pub size: u64,
+    pub alt: String,
+    pub caption: String,
+    pub created_at: i64,
+}
+
+impl Media {
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+
+    // Markdown that embeds the file in a post.
+    pub fn to_markdown(&self) -> String {
+        let alt = self.alt.replace(&['[', ']'][..], "");
+
+        if !self.is_image() {
+            return format!("[{}]({})", alt_or(&alt, &self.filename), self.url);
+        }
+
+        if self.caption.is_empty() {
+            format!("![{}]({})", alt, self.url)
+        } else {
+            format!(
+                "![{}]({} \"{}\")",
+                alt,
+                self.url,
+                self.caption.replace('"', "\\\"")
+            )
+        }
+    }
+}
+
+fn alt_or<'a>(alt: &'a str, default: &'a str) -> &'a str {
+    if alt.is_empty() {
+        default
+    } else {
+        alt
+    }
+}
+
+// A file to upload, images may have been processed beforehand.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MediaUpload {
+    pub file: Blob,
+    pub filename: String,
+    pub thumbnail: Option<Blob>,
+}
+
+impl From<File> for MediaUpload {
+    fn from(file: File) -> Self {
+        Self {
+            filename: file.name(),
+            file: file.into(),
+            thumbnail: None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct MediaUpdate {
+    pub alt: String,
+    pub caption: String,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct MediaQuery {
+    // Starts from 1.
+    pub page: u64,
+    pub per_page: u64,
+}
+
+impl Default for MediaQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 40,
+        }
+    }
+}
+
+impl Client {
+    // Newest first.
+    pub async fn media(&self, query: &MediaQuery) -> Result<Paginated<Media>> {
+        self.get_with_query("media", query).await
+    }
+
+    pub async fn update_media(&self, id: u64, update: &MediaUpdate) -> Result<Media> {
+        self.put(&format!("media/{}", id), update).await
+    }
+
+    pub async fn delete_media(&self, id: u64) -> Result<()> {
+        self.delete(&format!("media/{}", id)).await
+    }
+
+    // on_progress receives a ratio between 0 and 1.
+    pub async fn upload_media<F>(&self, upload: &MediaUpload, on_progress: F) -> Result<Media>
+    where
+        F: 'static + Fn(f64),
+    {
+        let form = FormData::new()?;
+        form.append_with_blob_and_filename("file", &upload.file, &upload.filename)?;
+        if let Some(ref m) = upload.thumbnail {
+            form.append_with_blob_and_filename(
+                "thumbnail",
+                m,
+                &format!("thumbnail-{}", upload.filename),
+            )?;
+        }
+
+        self.post_form("media", &form, on_progress).await
+    }
+
+    // Uses XMLHttpRequest as fetch cannot report upload progress.
+    pub(super) async fn post_form<T, F>(
+        &self,
+        path: &str,
+        form: &FormData,
+        on_progress: F,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+        F: 'static + Fn(f64),
+    {
+        let xhr = XmlHttpRequest::new()?;
+        xhr.open("POST", self.url(path)?.as_str())?;
+        if let Some(m) = Self::token() {
+            xhr.set_request_header("Authorization", &format!("Bearer {}", m))?;
+        }
+
+        let _progress = EventListener::new(&xhr.upload()?.into(), "progress", move |e| {
+            if let Some(e) = e.dyn_ref::<ProgressEvent>() {
+                if e.length_computable() && e.total() > 0.0 {
+                    on_progress(e.loaded() / e.total());
+                }
+            }
+        });
+
+        let (s, r) = async_channel::bounded::<()>(1);
+        let _listeners = ["load", "error", "abort"]
+            .iter()
+            .map(|name| {
+                let s = s.clone();
+                EventListener::new(&xhr, name, move |_| {
+                    let _ = s.try_send(());
+                })
+            })
+            .collect::<Vec<_>>();
+
+        xhr.send_with_opt_form_data(Some(form))?;
+        let _ = r.recv().await;
+
+        match xhr.status()? {
+            // The request did not reach the server.
+            0 => Err(JsValue::from_str("Failed to upload file.").into()),
+            401 => Err(Error::Unauthorized),
+            200..=299 => {
+                let text = xhr.response_text()?.unwrap_or_default();
+                Ok(serde_json::from_str(&text)?)
+            }
+            m => Err(Error::Status(m)),
+        }
+    }
+}
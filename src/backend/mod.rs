@@ -1,3 +1,31 @@
+mod client;
+mod comment;
+mod media;
+mod post;
+mod profile;
+mod resident;
+mod security;
+mod session;
+mod signin;
 mod signin_resident;
+mod signin_throttle;
+mod taxonomy;
+mod upload;
 
+pub use client::Client;
+pub use comment::{Comment, CommentQuery, CommentStatus};
+pub use media::{Media, MediaQuery, MediaUpdate, MediaUpload};
+pub use post::{
+    Order, Paginated, Post, PostDraft, PostQuery, PostSort, PostStatus, PostSummary, Revision,
+};
+pub use profile::{Profile, ProfileUpdate};
+pub use resident::{Invitation, Resident, ResidentUpdate, Role};
+pub use security::ApiToken;
+pub use session::Session;
+pub use signin::SigninProgress;
 pub use signin_resident::SigninResident;
+pub use signin_throttle::SigninThrottle;
+pub use taxonomy::{Category, Tag, TermDraft};
+pub use upload::{
+    upload_resumable, UploadFuture, UploadMetadata, UploadOptions, UploadServer, UploadSource,
+};
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Client, SigninResident};
+use crate::Result;
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Published,
+    Scheduled,
+}
+
+impl PostStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Draft => "草稿",
+            Self::Published => "已發佈",
+            Self::Scheduled => "已排程",
+        }
+    }
+
+    // The same as the serialized form.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Scheduled => "scheduled",
+        }
+    }
+
+    pub fn all() -> &'static [PostStatus] {
+        &[Self::Draft, Self::Published, Self::Scheduled]
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct PostSummary {
+    pub id: u64,
+    pub title: String,
+    pub slug: String,
+    pub status: PostStatus,
+    pub author: SigninResident,
+    // Unix timestamp in seconds.
+    pub updated_at: i64,
+    // When the post has been or will be published.
+    pub published_at: Option<i64>,
+    pub tags: Vec<String>,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Post {
+    pub id: u64,
+    pub title: String,
+    pub slug: String,
+    pub status: PostStatus,
+    pub tags: Vec<String>,
+    // Markdown.
+    pub body: String,
+    pub updated_at: i64,
+    pub published_at: Option<i64>,
+}
+
+// Fields that can be edited by the post editor.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostDraft {
+    pub title: String,
+    pub slug: String,
+    pub tags: Vec<String>,
+    pub body: String,
+}
+
+impl From<&Post> for PostDraft {
+    fn from(post: &Post) -> Self {
+        Self {
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            tags: post.tags.clone(),
+            body: post.body.clone(),
+        }
+    }
+}
+
+// A saved state of a post.
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Revision {
+    pub id: u64,
+    pub author: SigninResident,
+    pub created_at: i64,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostSort {
+    Title,
+    Status,
+    UpdatedAt,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct PostQuery {
+    // Starts from 1.
+    pub page: u64,
+    pub per_page: u64,
+    pub sort: PostSort,
+    pub order: Order,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PostStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+}
+
+impl Default for PostQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 20,
+            sort: PostSort::UpdatedAt,
+            order: Order::Desc,
+
+            status: None,
+            tag: None,
+            author: None,
+            search: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ScheduleRequest {
+    publish_at: i64,
+}
+
+#[derive(Serialize)]
+struct CalendarQuery {
+    from: i64,
+    to: i64,
+}
+
+impl Client {
+    pub async fn posts(&self, query: &PostQuery) -> Result<Paginated<PostSummary>> {
+        self.get_with_query("posts", query).await
+    }
+
+    pub async fn post_by_id(&self, id: u64) -> Result<Post> {
+        self.get(&format!("posts/{}", id)).await
+    }
+
+    pub async fn create_post(&self, draft: &PostDraft) -> Result<Post> {
+        self.post("posts", draft).await
+    }
+
+    pub async fn update_post(&self, id: u64, draft: &PostDraft) -> Result<Post> {
+        self.put(&format!("posts/{}", id), draft).await
+    }
+
+    // Keeps unsaved changes on the server without changing the post itself.
+    pub async fn autosave_post(&self, id: u64, draft: &PostDraft) -> Result<()> {
+        self.post_empty(&format!("posts/{}/autosave", id), draft)
+            .await
+    }
+
+    // Publishes the post automatically at the given unix timestamp.
+    pub async fn schedule_post(&self, id: u64, publish_at: i64) -> Result<Post> {
+        self.post(
+            &format!("posts/{}/schedule", id),
+            &ScheduleRequest { publish_at },
+        )
+        .await
+    }
+
+    // Scheduled and published posts with published_at in [from, to).
+    pub async fn calendar_posts(&self, from: i64, to: i64) -> Result<Vec<PostSummary>> {
+        self.get_with_query("posts/calendar", &CalendarQuery { from, to })
+            .await
+    }
+
+    // Newest first.
+    pub async fn revisions(&self, post_id: u64) -> Result<Vec<Revision>> {
+        self.get(&format!("posts/{}/revisions", post_id)).await
+    }
+
+    pub async fn restore_revision(&self, post_id: u64, revision_id: u64) -> Result<Post> {
+        self.post(
+            &format!("posts/{}/revisions/{}/restore", post_id, revision_id),
+            &(),
+        )
+        .await
+    }
+
+    pub async fn publish_post(&self, id: u64) -> Result<Post> {
+        self.post(&format!("posts/{}/publish", id), &()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_key() {
+        for m in PostStatus::all() {
+            assert_eq!(serde_json::to_value(m).unwrap(), m.key());
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{Blob, FormData};
+
+use super::{Client, Role, SigninResident};
+use crate::Result;
+
+// The signed in resident with the fields only they can see.
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub email: String,
+    pub bio: String,
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub avatar_blurhash: Option<String>,
+    pub role: Role,
+}
+
+impl From<&Profile> for SigninResident {
+    fn from(m: &Profile) -> Self {
+        Self {
+            name: m.name.clone(),
+            display_name: m.display_name.clone(),
+            avatar_url: m.avatar_url.clone(),
+            avatar_blurhash: m.avatar_blurhash.clone(),
+            email: Some(m.email.clone()),
+            role: m.role,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Serialize)]
+pub struct ProfileUpdate {
+    pub display_name: Option<String>,
+    pub email: String,
+    pub bio: String,
+}
+
+impl From<&Profile> for ProfileUpdate {
+    fn from(m: &Profile) -> Self {
+        Self {
+            display_name: m.display_name.clone(),
+            email: m.email.clone(),
+            bio: m.bio.clone(),
+        }
+    }
+}
+
+impl Client {
+    pub async fn profile(&self) -> Result<Profile> {
+        self.get("residents/current/profile").await
+    }
+
+    pub async fn update_profile(&self, update: &ProfileUpdate) -> Result<Profile> {
+        self.put("residents/current/profile", update).await
+    }
+
+    // The avatar is expected to be square, it is not cropped by the server.
+    pub async fn upload_avatar(&self, avatar: &Blob, filename: &str) -> Result<Profile> {
+        let form = FormData::new()?;
+        form.append_with_blob_and_filename("avatar", avatar, filename)?;
+
+        self.post_form("residents/current/avatar", &form, |_| {})
+            .await
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use super::Client;
+use crate::Result;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Owner,
+    Editor,
+    Author,
+    // The least privileged role until the server says otherwise.
+    #[default]
+    Contributor,
+}
+
+impl Role {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Owner => "擁有者",
+            Self::Editor => "編輯",
+            Self::Author => "作者",
+            Self::Contributor => "投稿者",
+        }
+    }
+
+    // The same as the serialized form.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Editor => "editor",
+            Self::Author => "author",
+            Self::Contributor => "contributor",
+        }
+    }
+
+    // Most privileged first.
+    pub fn all() -> &'static [Role] {
+        &[Self::Owner, Self::Editor, Self::Author, Self::Contributor]
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Resident {
+    pub id: u64,
+    pub name: String,
+    pub display_name: Option<String>,
+    pub email: String,
+    pub avatar_url: Option<String>,
+    pub role: Role,
+    // Invited residents that have not signed up yet.
+    pub pending: bool,
+    pub created_at: i64,
+}
+
+// Profile fields that can be edited by residents with the permission to manage residents.
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct ResidentUpdate {
+    pub display_name: Option<String>,
+    pub email: String,
+    pub role: Role,
+}
+
+impl From<&Resident> for ResidentUpdate {
+    fn from(m: &Resident) -> Self {
+        Self {
+            display_name: m.display_name.clone(),
+            email: m.email.clone(),
+            role: m.role,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct Invitation {
+    pub email: String,
+    pub role: Role,
+}
+
+impl Client {
+    // Sorted by name, pending invitations last.
+    pub async fn residents(&self) -> Result<Vec<Resident>> {
+        self.get("residents").await
+    }
+
+    // Sends an email with a link to sign up.
+    pub async fn invite_resident(&self, invitation: &Invitation) -> Result<Resident> {
+        self.post("residents/invitations", invitation).await
+    }
+
+    pub async fn update_resident(&self, id: u64, update: &ResidentUpdate) -> Result<Resident> {
+        self.put(&format!("residents/{}", id), update).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_key() {
+        for m in Role::all() {
+            assert_eq!(serde_json::to_value(m).unwrap(), m.key());
+            assert_eq!(serde_json::from_value::<Role>(m.key().into()).unwrap(), *m);
+        }
+    }
+}
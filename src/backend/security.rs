@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::Client;
+use crate::Result;
+
+#[derive(Serialize)]
+struct ChangePasswordBody<'a> {
+    password: &'a str,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct ApiToken {
+    pub token: String,
+}
+
+// These actions require the session to have been reauthenticated recently.
+impl Client {
+    pub async fn change_password(&self, password: &str) -> Result<()> {
+        self.post_empty(
+            "residents/current/password",
+            &ChangePasswordBody { password },
+        )
+        .await
+    }
+
+    pub async fn disable_otp(&self) -> Result<()> {
+        self.post_empty("residents/current/otp/disable", &()).await
+    }
+
+    pub async fn regenerate_api_token(&self) -> Result<ApiToken> {
+        self.post("residents/current/api-token", &()).await
+    }
+}
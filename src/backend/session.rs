@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::client::encode_segment;
+use super::Client;
+use crate::Result;
+
+#[derive(Serialize)]
+struct ReauthenticateBody<'a> {
+    password: &'a str,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub user_agent: String,
+    // Unix timestamp in seconds.
+    pub last_active: i64,
+    // Approximate location resolved from the ip address by the backend.
+    pub location: Option<String>,
+    pub ip_address: Option<String>,
+    // Whether this is the session making the request.
+    pub current: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TokenResponse {
+    pub token: String,
+}
+
+impl Client {
+    // Exchanges the current token for a fresh one and stores it.
+    pub async fn refresh_token(&self) -> Result<()> {
+        let resp: TokenResponse = self.post("sessions/current/refresh", &()).await?;
+        Self::set_token(Some(&resp.token));
+        Ok(())
+    }
+
+    pub async fn sessions(&self) -> Result<Vec<Session>> {
+        self.get("sessions").await
+    }
+
+    pub async fn revoke_session(&self, id: &str) -> Result<()> {
+        self.delete(&format!("sessions/{}", encode_segment(id)?))
+            .await
+    }
+
+    pub async fn revoke_other_sessions(&self) -> Result<()> {
+        self.post_empty("sessions/revoke-others", &()).await
+    }
+
+    // Confirms the password of the signed-in resident.
+    // This also grants the session a short window to perform sensitive actions.
+    pub async fn reauthenticate(&self, password: &str) -> Result<()> {
+        self.post_empty(
+            "sessions/current/reauthenticate",
+            &ReauthenticateBody { password },
+        )
+        .await
+    }
+
+    pub async fn sign_out(&self) -> Result<()> {
+        self.post_empty("sessions/current/signout", &()).await?;
+        Self::set_token(None);
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use super::session::TokenResponse;
+use super::{Client, SigninResident};
+use crate::Result;
+
+#[derive(Serialize)]
+struct NameBody<'a> {
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+struct PasswordBody<'a> {
+    name: &'a str,
+    password: &'a str,
+}
+
+#[derive(Serialize)]
+struct OtpBody<'a> {
+    challenge: &'a str,
+    otp: &'a str,
+}
+
+#[derive(Serialize)]
+struct SignupBody<'a> {
+    name: &'a str,
+    email: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum PasswordResponse {
+    Done { token: String },
+    OtpRequired { challenge: String },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum SigninProgress {
+    // The token has been stored.
+    Done,
+    // The challenge has to be sent back with the otp.
+    OtpRequired(String),
+}
+
+// Throttled and locked attempts fail with Error::Throttled.
+impl Client {
+    pub async fn signin_resident(&self, name: &str) -> Result<SigninResident> {
+        self.post("signin/resident", &NameBody { name }).await
+    }
+
+    pub async fn signin_password(&self, name: &str, password: &str) -> Result<SigninProgress> {
+        let resp: PasswordResponse = self
+            .post("signin/password", &PasswordBody { name, password })
+            .await?;
+
+        Ok(match resp {
+            PasswordResponse::Done { token } => {
+                Self::signed_in(&token);
+                SigninProgress::Done
+            }
+            PasswordResponse::OtpRequired { challenge } => SigninProgress::OtpRequired(challenge),
+        })
+    }
+
+    pub async fn signin_otp(&self, challenge: &str, otp: &str) -> Result<()> {
+        let resp: TokenResponse = self.post("signin/otp", &OtpBody { challenge, otp }).await?;
+        Self::signed_in(&resp.token);
+        Ok(())
+    }
+
+    // A new session starts unlocked.
+    fn signed_in(token: &str) {
+        Self::set_token(Some(token));
+        Self::set_locked(false);
+    }
+
+    // Sends an email with a link to unlock the account.
+    pub async fn request_unlock(&self, name: &str) -> Result<()> {
+        self.post_empty("signin/unlock", &NameBody { name }).await
+    }
+
+    pub async fn signup(&self, name: &str, email: &str, password: &str) -> Result<()> {
+        self.post_empty(
+            "signup",
+            &SignupBody {
+                name,
+                email,
+                password,
+            },
+        )
+        .await
+    }
+}
@@ -1,19 +1,62 @@
-#[derive(PartialEq, Debug, Clone)]
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::{Client, Role};
+use crate::helpers::window;
+use crate::Result;
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
 pub struct SigninResident {
     pub name: String,
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
+    // A BlurHash of the avatar, shown while it is loading.
+    #[serde(default)]
+    pub avatar_blurhash: Option<String>,
+    // Used to find a Gravatar when there is no avatar.
+    #[serde(default)]
+    pub email: Option<String>,
+    // Not sent before signing in.
+    #[serde(default)]
+    pub role: Role,
 }
 
 impl SigninResident {
-    pub fn avatar_url(&self) -> String {
+    // The size is the rendered width in CSS pixels.
+    //
+    // None if there is neither an avatar nor an email, the initials are shown instead.
+    pub fn avatar_url(&self, size: u32) -> Option<String> {
         self.avatar_url
-            .as_ref()
-            .map(|m| m.to_string())
-            .unwrap_or_else(|| {
-                "https://www.gravatar.com/avatar/55ea921519aa759f6a15711ad4faea7c?s=200&d=mp"
-                    .to_string()
-            })
+            .clone()
+            .or_else(|| self.email.as_deref().map(|m| gravatar_url(m, size)))
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+fn gravatar_hash(email: &str) -> String {
+    Sha256::digest(email.trim().to_lowercase().as_bytes())
+        .iter()
+        .map(|m| format!("{:02x}", m))
+        .collect()
+}
+
+// d=404 fails the image if there is no Gravatar, so the initials are shown instead of a placeholder.
+fn gravatar_url(email: &str, size: u32) -> String {
+    let pixels = (size as f64 * window().device_pixel_ratio().max(1.0)).ceil() as u32;
+
+    format!(
+        "https://www.gravatar.com/avatar/{}?s={}&d=404",
+        gravatar_hash(email),
+        pixels.clamp(1, 2048)
+    )
+}
+
+impl Client {
+    pub async fn current_resident(&self) -> Result<SigninResident> {
+        self.get("residents/current").await
     }
 }
 
@@ -23,6 +66,22 @@ impl Default for SigninResident {
             name: "".to_string(),
             display_name: None,
             avatar_url: None,
+            avatar_blurhash: None,
+            email: None,
+            role: Role::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gravatar_hash() {
+        assert_eq!(
+            gravatar_hash(" MyEmailAddress@example.com "),
+            "84059b07d4be67b806386c0aad8070a23f18836bbaae342275dc0a83414c32ee"
+        );
+    }
+}
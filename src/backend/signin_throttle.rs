@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum SigninThrottle {
+    // Too many attempts, try again after the duration.
+    RetryAfter(Duration),
+    // Too many failed attempts, the resident has to unlock via email.
+    Locked,
+}
+
+impl SigninThrottle {
+    const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+    pub fn from_response(resp: &Response) -> Option<Self> {
+        Self::from_parts(resp.status(), resp.headers())
+    }
+
+    fn from_parts(status: StatusCode, headers: &HeaderMap) -> Option<Self> {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => {
+                // Only delay-seconds, an http-date is treated as missing.
+                let retry_after = headers
+                    .get(RETRY_AFTER)
+                    .and_then(|m| m.to_str().ok())
+                    .and_then(|m| m.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(Self::DEFAULT_RETRY_AFTER);
+
+                Some(Self::RetryAfter(retry_after))
+            }
+            StatusCode::LOCKED => Some(Self::Locked),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn retry_after(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_retry_after() {
+        assert_eq!(
+            SigninThrottle::from_parts(StatusCode::TOO_MANY_REQUESTS, &retry_after("30")),
+            Some(SigninThrottle::RetryAfter(Duration::from_secs(30)))
+        );
+        assert_eq!(
+            SigninThrottle::from_parts(StatusCode::TOO_MANY_REQUESTS, &retry_after(" 5 ")),
+            Some(SigninThrottle::RetryAfter(Duration::from_secs(5)))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_default() {
+        let expected = Some(SigninThrottle::RetryAfter(
+            SigninThrottle::DEFAULT_RETRY_AFTER,
+        ));
+
+        assert_eq!(
+            SigninThrottle::from_parts(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new()),
+            expected
+        );
+        assert_eq!(
+            SigninThrottle::from_parts(
+                StatusCode::TOO_MANY_REQUESTS,
+                &retry_after("Wed, 21 Oct 2015 07:28:00 GMT")
+            ),
+            expected
+        );
+        assert_eq!(
+            SigninThrottle::from_parts(StatusCode::TOO_MANY_REQUESTS, &retry_after("-1")),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_locked() {
+        assert_eq!(
+            SigninThrottle::from_parts(StatusCode::LOCKED, &retry_after("30")),
+            Some(SigninThrottle::Locked)
+        );
+    }
+
+    #[test]
+    fn test_not_throttled() {
+        for status in &[
+            StatusCode::OK,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            assert_eq!(
+                SigninThrottle::from_parts(*status, &retry_after("30")),
+                None
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use super::Client;
+use crate::Result;
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Tag {
+    pub id: u64,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub post_count: u64,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+pub struct Category {
+    pub id: u64,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    // None for top level categories.
+    pub parent_id: Option<u64>,
+    pub post_count: u64,
+}
+
+// Used to create or update a tag or a category, parent_id is ignored for tags.
+#[derive(PartialEq, Debug, Clone, Default, Serialize)]
+pub struct TermDraft {
+    pub name: String,
+    // Generated from the name by the server if empty.
+    pub slug: String,
+    pub description: String,
+    pub parent_id: Option<u64>,
+}
+
+impl From<&Tag> for TermDraft {
+    fn from(m: &Tag) -> Self {
+        Self {
+            name: m.name.clone(),
+            slug: m.slug.clone(),
+            description: m.description.clone(),
+            parent_id: None,
+        }
+    }
+}
+
+impl From<&Category> for TermDraft {
+    fn from(m: &Category) -> Self {
+        Self {
+            name: m.name.clone(),
+            slug: m.slug.clone(),
+            description: m.description.clone(),
+            parent_id: m.parent_id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MergeRequest {
+    into: u64,
+}
+
+#[derive(Serialize)]
+struct DeleteQuery {
+    // Posts are moved to this term, or lose the term if None.
+    reassign_to: Option<u64>,
+}
+
+impl Client {
+    // Sorted by name.
+    pub async fn tags(&self) -> Result<Vec<Tag>> {
+        self.get("tags").await
+    }
+
+    pub async fn create_tag(&self, draft: &TermDraft) -> Result<Tag> {
+        self.post("tags", draft).await
+    }
+
+    pub async fn update_tag(&self, id: u64, draft: &TermDraft) -> Result<Tag> {
+        self.put(&format!("tags/{}", id), draft).await
+    }
+
+    // Posts tagged with the tag are tagged with the other one instead, then the tag is removed.
+    pub async fn merge_tags(&self, id: u64, into: u64) -> Result<Tag> {
+        self.post(&format!("tags/{}/merge", id), &MergeRequest { into })
+            .await
+    }
+
+    pub async fn delete_tag(&self, id: u64, reassign_to: Option<u64>) -> Result<()> {
+        self.delete_with_query(&format!("tags/{}", id), &DeleteQuery { reassign_to })
+            .await
+    }
+
+    // Sorted by name, parents are not guaranteed to come before their children.
+    pub async fn categories(&self) -> Result<Vec<Category>> {
+        self.get("categories").await
+    }
+
+    pub async fn create_category(&self, draft: &TermDraft) -> Result<Category> {
+        self.post("categories", draft).await
+    }
+
+    pub async fn update_category(&self, id: u64, draft: &TermDraft) -> Result<Category> {
+        self.put(&format!("categories/{}", id), draft).await
+    }
+
+    // Children of the category are moved to its parent.
+    pub async fn delete_category(&self, id: u64, reassign_to: Option<u64>) -> Result<()> {
+        self.delete_with_query(
+            &format!("categories/{}", id),
+            &DeleteQuery { reassign_to },
+        )
+        .await
+    }
+}
@@ -0,0 +1,577 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, FormData};
+
+use super::{Client, Media};
+use crate::helpers::sleep;
+use crate::{Error, Result};
+
+const TUS_VERSION: &str = "1.0.0";
+
+pub type UploadFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + 'a>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadMetadata {
+    pub filename: String,
+    pub content_type: String,
+}
+
+impl UploadMetadata {
+    // Values of Upload-Metadata are base64 encoded.
+    fn to_header(&self) -> String {
+        format!(
+            "filename {},content_type {}",
+            base64::encode(&self.filename),
+            base64::encode(&self.content_type)
+        )
+    }
+}
+
+// The server side of a resumable upload, following the core protocol of tus.
+// See: https://tus.io/protocols/resumable-upload.html
+pub trait UploadServer {
+    // Starts an upload and returns its location.
+    fn create<'a>(&'a self, size: u64, metadata: &'a UploadMetadata) -> UploadFuture<'a, String>;
+
+    // Bytes the server has received so far.
+    fn offset<'a>(&'a self, location: &'a str) -> UploadFuture<'a, u64>;
+
+    // Appends a chunk at offset and returns the new offset.
+    fn append<'a>(
+        &'a self,
+        location: &'a str,
+        offset: u64,
+        chunk: Vec<u8>,
+    ) -> UploadFuture<'a, u64>;
+}
+
+pub trait UploadSource {
+    fn size(&self) -> u64;
+
+    fn read(&self, start: u64, end: u64) -> UploadFuture<'_, Vec<u8>>;
+}
+
+impl UploadSource for Blob {
+    fn size(&self) -> u64 {
+        Blob::size(self) as u64
+    }
+
+    fn read(&self, start: u64, end: u64) -> UploadFuture<'_, Vec<u8>> {
+        Box::pin(async move {
+            let chunk = self.slice_with_f64_and_f64(start as f64, end as f64)?;
+            let buf = JsFuture::from(chunk.array_buffer()).await?;
+            Ok(js_sys::Uint8Array::new(&buf).to_vec())
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadOptions {
+    pub chunk_size: u64,
+    // Consecutive failures before giving up.
+    pub max_retries: u32,
+    // Multiplied by the number of failures so far.
+    pub retry_delay: Duration,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 5 * 1024 * 1024,
+            max_retries: 5,
+            retry_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+// The server no longer knows about the upload.
+fn is_gone(e: &Error) -> bool {
+    matches!(e, Error::Status(404) | Error::Status(410))
+}
+
+fn is_retryable(e: &Error) -> bool {
+    match e {
+        Error::Networking(_) | Error::UploadStalled(_) => true,
+        // 409 means the offset is not what the server has, which is asked again before retrying.
+        Error::Status(m) => *m == 409 || *m >= 500 || is_gone(e),
+        _ => false,
+    }
+}
+
+// Uploads the source in chunks, resuming from what the server has if location is set.
+//
+// on_created receives the location of a new upload, which should be kept to resume later.
+// on_progress receives a ratio between 0 and 1.
+// Returns the location once the server has every byte.
+pub async fn upload_resumable<S, C, P>(
+    server: &S,
+    source: &dyn UploadSource,
+    metadata: &UploadMetadata,
+    location: Option<String>,
+    options: &UploadOptions,
+    mut on_created: C,
+    on_progress: P,
+) -> Result<String>
+where
+    S: UploadServer + ?Sized,
+    C: FnMut(&str),
+    P: Fn(f64),
+{
+    let size = source.size();
+    let mut location = location;
+    // None if it has to be asked from the server.
+    let mut offset: Option<u64> = None;
+    let mut retries = 0;
+
+    loop {
+        let result = match location {
+            None => match server.create(size, metadata).await {
+                Ok(m) => {
+                    on_created(&m);
+                    location = Some(m);
+                    offset = Some(0);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+            Some(ref loc) => match offset {
+                None => server.offset(loc).await.map(|m| offset = Some(m)),
+                Some(m) if m >= size => {
+                    on_progress(1.0);
+                    return Ok(loc.clone());
+                }
+                Some(m) => {
+                    let chunk = source.read(m, size.min(m + options.chunk_size)).await?;
+                    match server.append(loc, m, chunk).await {
+                        // The same chunk would be sent forever, so it counts as a failure.
+                        Ok(next) if next <= m => Err(Error::UploadStalled(m)),
+                        Ok(next) => {
+                            // Only chunks that went through reset the failures.
+                            retries = 0;
+                            offset = Some(next);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            },
+        };
+
+        match result {
+            Ok(()) => {
+                if let Some(m) = offset {
+                    on_progress(if size == 0 {
+                        1.0
+                    } else {
+                        m as f64 / size as f64
+                    });
+                }
+            }
+            Err(e) if is_retryable(&e) && retries < options.max_retries => {
+                retries += 1;
+                log::warn!("Upload interrupted ({}), retrying.", e);
+
+                if is_gone(&e) {
+                    location = None;
+                }
+                offset = None;
+
+                if options.retry_delay > Duration::from_secs(0) {
+                    sleep(options.retry_delay * retries).await;
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl Client {
+    // Upload-Offset and Location have to be exposed to CORS by the server.
+    async fn send_tus(builder: RequestBuilder) -> Result<Response> {
+        let resp = builder.header("Tus-Resumable", TUS_VERSION).send().await?;
+
+        match resp.status() {
+            StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
+            m if m.is_success() => Ok(resp),
+            m => Err(Error::Status(m.as_u16())),
+        }
+    }
+
+    fn header_of(resp: &Response, name: &str) -> Result<String> {
+        resp.headers()
+            .get(name)
+            .and_then(|m| m.to_str().ok())
+            .map(|m| m.to_string())
+            .ok_or_else(|| JsValue::from_str(&format!("{} is missing.", name)).into())
+    }
+
+    // Adds a finished upload to the media library.
+    pub async fn complete_upload(&self, location: &str, thumbnail: Option<&Blob>) -> Result<Media> {
+        let form = FormData::new()?;
+        if let Some(m) = thumbnail {
+            form.append_with_blob_and_filename("thumbnail", m, "thumbnail")?;
+        }
+
+        self.post_form(&format!("{}/complete", location), &form, |_| {})
+            .await
+    }
+}
+
+impl UploadServer for Client {
+    fn create<'a>(&'a self, size: u64, metadata: &'a UploadMetadata) -> UploadFuture<'a, String> {
+        Box::pin(async move {
+            let builder = self
+                .builder(Method::POST, "media/uploads")?
+                .header("Upload-Length", size.to_string())
+                .header("Upload-Metadata", metadata.to_header());
+            let resp = Self::send_tus(builder).await?;
+
+            // The location may be relative.
+            let location = Self::header_of(&resp, "Location")?;
+            Ok(self.url("media/uploads/")?.join(&location)?.to_string())
+        })
+    }
+
+    fn offset<'a>(&'a self, location: &'a str) -> UploadFuture<'a, u64> {
+        Box::pin(async move {
+            let builder = self
+                .builder(Method::HEAD, location)?
+                .header("Cache-Control", "no-store");
+            let resp = Self::send_tus(builder).await?;
+
+            Self::header_of(&resp, "Upload-Offset")?
+                .parse()
+                .map_err(|_| JsValue::from_str("Upload-Offset is not valid.").into())
+        })
+    }
+
+    fn append<'a>(
+        &'a self,
+        location: &'a str,
+        offset: u64,
+        chunk: Vec<u8>,
+    ) -> UploadFuture<'a, u64> {
+        Box::pin(async move {
+            let builder = self
+                .builder(Method::PATCH, location)?
+                .header("Upload-Offset", offset.to_string())
+                .header("Content-Type", "application/offset+octet-stream")
+                .body(chunk);
+            let resp = Self::send_tus(builder).await?;
+
+            Self::header_of(&resp, "Upload-Offset")?
+                .parse()
+                .map_err(|_| JsValue::from_str("Upload-Offset is not valid.").into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    impl UploadSource for Vec<u8> {
+        fn size(&self) -> u64 {
+            self.len() as u64
+        }
+
+        fn read(&self, start: u64, end: u64) -> UploadFuture<'_, Vec<u8>> {
+            let chunk = self[start as usize..end as usize].to_vec();
+            Box::pin(async move { Ok(chunk) })
+        }
+    }
+
+    // Keeps uploads in memory and fails on request.
+    #[derive(Default)]
+    struct MemoryServer {
+        uploads: RefCell<HashMap<String, (u64, Vec<u8>)>>,
+        next_id: Cell<u64>,
+        // Bytes of the next chunk that are stored before the connection drops.
+        drop_next_append: Cell<Option<usize>>,
+        // Appends succeed without storing anything.
+        stall_appends: Cell<bool>,
+        requests: Cell<u64>,
+    }
+
+    impl MemoryServer {
+        fn data(&self, location: &str) -> Vec<u8> {
+            self.uploads.borrow()[location].1.clone()
+        }
+
+        fn forget(&self, location: &str) {
+            self.uploads.borrow_mut().remove(location);
+        }
+    }
+
+    impl UploadServer for MemoryServer {
+        fn create<'a>(
+            &'a self,
+            size: u64,
+            _metadata: &'a UploadMetadata,
+        ) -> UploadFuture<'a, String> {
+            self.requests.set(self.requests.get() + 1);
+
+            let location = format!("memory://uploads/{}", self.next_id.get());
+            self.next_id.set(self.next_id.get() + 1);
+            self.uploads
+                .borrow_mut()
+                .insert(location.clone(), (size, Vec::new()));
+
+            Box::pin(async move { Ok(location) })
+        }
+
+        fn offset<'a>(&'a self, location: &'a str) -> UploadFuture<'a, u64> {
+            self.requests.set(self.requests.get() + 1);
+
+            let result = match self.uploads.borrow().get(location) {
+                Some(m) => Ok(m.1.len() as u64),
+                None => Err(Error::Status(404)),
+            };
+            Box::pin(async move { result })
+        }
+
+        fn append<'a>(
+            &'a self,
+            location: &'a str,
+            offset: u64,
+            chunk: Vec<u8>,
+        ) -> UploadFuture<'a, u64> {
+            self.requests.set(self.requests.get() + 1);
+
+            let mut uploads = self.uploads.borrow_mut();
+            let result = match uploads.get_mut(location) {
+                None => Err(Error::Status(404)),
+                Some(m) if m.1.len() as u64 != offset => Err(Error::Status(409)),
+                Some(m) if m.1.len() + chunk.len() > m.0 as usize => Err(Error::Status(413)),
+                Some(_) if self.stall_appends.get() => Ok(offset),
+                Some(m) => match self.drop_next_append.take() {
+                    Some(kept) => {
+                        m.1.extend_from_slice(&chunk[..kept.min(chunk.len())]);
+                        Err(Error::Status(502))
+                    }
+                    None => {
+                        m.1.extend_from_slice(&chunk);
+                        Ok(m.1.len() as u64)
+                    }
+                },
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    fn metadata() -> UploadMetadata {
+        UploadMetadata {
+            filename: "photo.jpg".to_string(),
+            content_type: "image/jpeg".to_string(),
+        }
+    }
+
+    fn options(chunk_size: u64) -> UploadOptions {
+        UploadOptions {
+            chunk_size,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(0),
+        }
+    }
+
+    fn source(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_uploads_in_chunks() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+            let progress = RefCell::new(Vec::new());
+
+            let location = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                None,
+                &options(300),
+                |_| {},
+                |m| progress.borrow_mut().push(m),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(server.data(&location), file);
+            // create and 4 chunks.
+            assert_eq!(server.requests.get(), 5);
+            assert_eq!(progress.borrow().last(), Some(&1.0));
+        });
+    }
+
+    #[test]
+    fn test_resumes_from_server_offset() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+
+            let location = server.create(1000, &metadata()).await.unwrap();
+            server
+                .append(&location, 0, file[..600].to_vec())
+                .await
+                .unwrap();
+            server.requests.set(0);
+
+            let resumed = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                Some(location.clone()),
+                &options(300),
+                |_| panic!("A new upload should not be created."),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(resumed, location);
+            assert_eq!(server.data(&location), file);
+            // HEAD and the 2 chunks left.
+            assert_eq!(server.requests.get(), 3);
+        });
+    }
+
+    #[test]
+    fn test_retries_after_partial_chunk() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+            server.drop_next_append.set(Some(120));
+
+            let location = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                None,
+                &options(500),
+                |_| {},
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(server.data(&location), file);
+        });
+    }
+
+    #[test]
+    fn test_starts_over_when_upload_is_gone() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+
+            let location = server.create(1000, &metadata()).await.unwrap();
+            server.forget(&location);
+
+            let created = RefCell::new(Vec::new());
+            let uploaded = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                Some(location.clone()),
+                &options(400),
+                |m| created.borrow_mut().push(m.to_string()),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+            assert_ne!(uploaded, location);
+            assert_eq!(*created.borrow(), vec![uploaded.clone()]);
+            assert_eq!(server.data(&uploaded), file);
+        });
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+
+            let location = server.create(1000, &metadata()).await.unwrap();
+            server.forget(&location);
+
+            // The server forgets every upload right after it is created.
+            let result = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                Some(location),
+                &options(400),
+                |m| server.forget(m),
+                |_| {},
+            )
+            .await;
+
+            assert!(matches!(result, Err(Error::Status(404))));
+        });
+    }
+
+    #[test]
+    fn test_gives_up_when_append_does_not_advance() {
+        block_on(async {
+            let server = MemoryServer::default();
+            let file = source(1000);
+            server.stall_appends.set(true);
+
+            let result = upload_resumable(
+                &server,
+                &file,
+                &metadata(),
+                None,
+                &options(400),
+                |_| {},
+                |_| {},
+            )
+            .await;
+
+            assert!(matches!(result, Err(Error::UploadStalled(0))));
+            // create, then an append and a HEAD for each of the 3 retries, and the last append.
+            assert_eq!(server.requests.get(), 1 + 3 * 2 + 1);
+        });
+    }
+
+    #[test]
+    fn test_uploads_empty_file() {
+        block_on(async {
+            let server = MemoryServer::default();
+
+            let location = upload_resumable(
+                &server,
+                &Vec::new(),
+                &metadata(),
+                None,
+                &options(400),
+                |_| {},
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+            assert!(server.data(&location).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_encodes_metadata() {
+        assert_eq!(
+            metadata().to_header(),
+            "filename cGhvdG8uanBn,content_type aW1hZ2UvanBlZw=="
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Writes critical styles and a loading shell into the static pages, so they are styled before the
+// wasm is loaded. Run it natively whenever the styles of the first screens change:
+//
+//     cargo run --bin prerender -- static
+//
+// The pages must have the markers of furtherland_office::prerender, which are kept.
+//
+// The prerender module is not built for wasm, where this binary does nothing.
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    native::main()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::env;
+    use std::error::Error;
+    use std::fs;
+    use std::path::Path;
+
+    use furtherland_office::prerender::{self, Prerendered};
+
+    type Prerender = fn(&str) -> furtherland_office::Result<Prerendered>;
+
+    const PAGES: &[(&str, Prerender)] = &[
+        ("signin.html", prerender::signin),
+        ("signup.html", prerender::signup),
+    ];
+
+    pub(crate) fn main() -> Result<(), Box<dyn Error>> {
+        let dir = env::args().nth(1).unwrap_or_else(|| "static".to_string());
+        let dir = Path::new(&dir);
+
+        let manifest = fs::read_to_string(dir.join("manifest.toml"))?;
+
+        for (name, prerender) in PAGES {
+            let path = dir.join(name);
+            let prerendered = prerender(&manifest)?;
+
+            let page = fs::read_to_string(&path)?;
+            let page = prerendered
+                .write_into(&page)
+                .ok_or_else(|| format!("{} has no prerender markers.", path.display()))?;
+            fs::write(&path, page)?;
+
+            println!(
+                "{}: {} styles, {} bytes of css.",
+                path.display(),
+                prerendered.classes.len(),
+                prerendered.css.len()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use std::f64::consts::PI;
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+const CHARACTERS: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+fn decode83(s: &[u8]) -> Option<u32> {
+    s.iter().try_fold(0u32, |value, c| {
+        let digit = CHARACTERS.iter().position(|m| m == c)? as u32;
+        Some(value * 83 + digit)
+    })
+}
+
+fn srgb_to_linear(value: u32) -> f64 {
+    let v = value as f64 / 255.0;
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let v = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+
+    (v * 255.0).round() as u8
+}
+
+fn sign_pow(value: f64, exp: f64) -> f64 {
+    value.abs().powf(exp).copysign(value)
+}
+
+fn decode_dc(value: u32) -> [f64; 3] {
+    [
+        srgb_to_linear(value >> 16),
+        srgb_to_linear((value >> 8) & 255),
+        srgb_to_linear(value & 255),
+    ]
+}
+
+fn decode_ac(value: u32, maximum: f64) -> [f64; 3] {
+    let quant = |m: u32| sign_pow((m as f64 - 9.0) / 9.0, 2.0) * maximum;
+
+    [
+        quant(value / (19 * 19)),
+        quant((value / 19) % 19),
+        quant(value % 19),
+    ]
+}
+
+// RGBA pixels of the placeholder of a BlurHash (https://blurha.sh), None if the hash is not valid.
+pub(crate) fn decode(hash: &str, width: u32, height: u32) -> Option<Vec<u8>> {
+    let hash = hash.as_bytes();
+    if hash.len() < 6 {
+        return None;
+    }
+
+    let size_flag = decode83(&hash[0..1])?;
+    let num_x = (size_flag % 9 + 1) as usize;
+    let num_y = (size_flag / 9 + 1) as usize;
+    if hash.len() != 4 + 2 * num_x * num_y {
+        return None;
+    }
+
+    let maximum = (decode83(&hash[1..2])? + 1) as f64 / 166.0;
+
+    let mut colours = Vec::with_capacity(num_x * num_y);
+    colours.push(decode_dc(decode83(&hash[2..6])?));
+    for i in 1..num_x * num_y {
+        colours.push(decode_ac(decode83(&hash[4 + i * 2..6 + i * 2])?, maximum));
+    }
+
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let mut pixel = [0.0; 3];
+
+            for j in 0..num_y {
+                for i in 0..num_x {
+                    let basis = (PI * x as f64 * i as f64 / width as f64).cos()
+                        * (PI * y as f64 * j as f64 / height as f64).cos();
+                    let colour = colours[i + j * num_x];
+
+                    for (m, c) in pixel.iter_mut().zip(colour.iter()) {
+                        *m += c * basis;
+                    }
+                }
+            }
+
+            pixels.extend(pixel.iter().map(|m| linear_to_srgb(*m)));
+            pixels.push(255);
+        }
+    }
+
+    Some(pixels)
+}
+
+// A PNG data URL of the placeholder, small sizes such as 32x32 are enough as it is scaled up.
+pub(crate) fn to_data_url(hash: &str, width: u32, height: u32) -> Option<String> {
+    let image = RgbaImage::from_raw(width, height, decode(hash, width, height)?)?;
+
+    let mut buf = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut buf, ImageOutputFormat::Png)
+        .ok()?;
+
+    Some(format!(
+        "data:image/png;base64,{}",
+        base64::encode(buf.into_inner())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_dc_only() {
+        // A single component of rgb(255, 128, 0).
+        let pixels = decode("00TNoS", 2, 2).unwrap();
+
+        assert_eq!(pixels.len(), 2 * 2 * 4);
+        for pixel in pixels.chunks(4) {
+            assert_eq!(pixel, &[255, 128, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let pixels = decode("LEHV6nWB2yk8pyo0adR*.7kCMdnj", 32, 32).unwrap();
+        assert_eq!(pixels.len(), 32 * 32 * 4);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode("", 32, 32).is_none());
+        // Too short for its size flag.
+        assert!(decode("LEHV6nWB2yk8", 32, 32).is_none());
+        assert!(decode("00\"\"\"\"", 32, 32).is_none());
+    }
+}
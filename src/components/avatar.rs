@@ -2,11 +2,26 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use super::ProgressiveImage;
+use crate::css::{css, Colour, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Clone, Debug, PartialEq)]
 pub struct AvatarProps {
-    pub src: String,
+    // The initials of the name are shown if there is no image or it fails to load.
+    #[prop_or_default]
+    pub src: Option<String>,
+
+    // Shown while the image is loading.
+    #[prop_or_default]
+    pub blurhash: Option<String>,
+
+    #[prop_or_default]
+    pub name: String,
+
+    // Width and height in CSS pixels.
+    #[prop_or(100)]
+    pub size: u32,
 }
 
 impl YieldStyle for AvatarProps {
@@ -14,35 +29,52 @@ impl YieldStyle for AvatarProps {
         "fl-avatar".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        format!(
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
             r#"
-            height: 100px;
-            width: 100px;
-            border-radius: 100%;
-            background-repeat: no-repeat;
-            background-size: contain;
-            background-position: center;
-            display: block;
-            background-image: url({});
-            "#,
-            &self.src
+                height: var(--fl-avatar-size);
+                width: var(--fl-avatar-size);
+                border-radius: 100%;
+                overflow: hidden;
+                flex-shrink: 0;
+                display: flex;
+                align-items: center;
+                justify-content: center;
+                background-color: var(--fl-avatar-colour);
+                color: white;
+                font-size: calc(var(--fl-avatar-size) * 0.4);
+
+                cursor: default;
+                -moz-user-select: none;
+                -webkit-user-select: none;
+                -ms-user-select: none;
+                user-select: none;
+                "#
         )
-        .into()
+    }
+
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("size", Some(format!("{}px", self.size))),
+            ("colour", Some(name_colour(&self.name).to_string())),
+        ]
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Avatar {
     props: AvatarProps,
-    link: ComponentLink<Self>,
+    root_ref: NodeRef,
 }
 
 impl Component for Avatar {
     type Message = ();
     type Properties = AvatarProps;
-    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        Self { props, link }
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self {
+            props,
+            root_ref: NodeRef::default(),
+        }
     }
 
     fn update(&mut self, _msg: Self::Message) -> ShouldRender {
@@ -59,9 +91,67 @@ impl Component for Avatar {
     }
 
     fn view(&self) -> Html {
+        let initials = html! { { initials(&self.props.name) } };
+
+        let content = match self.props.src {
+            Some(ref m) => html! {
+                <ProgressiveImage src=m.clone() blurhash=self.props.blurhash.clone()
+                    alt=self.props.name.clone() fallback=Some(initials) />
+            },
+            None => initials,
+        };
+
         html! {
-            <div class=self.props.yield_style_class()>
+            <div ref=self.root_ref.clone() class=self.props.yield_style_class() title=self.props.name.clone()>
+                { content }
             </div>
         }
     }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.props.apply_style_vars(&self.root_ref);
+    }
+}
+
+// The first letter of the first and the last word, or the first character of names without spaces
+// such as most Chinese and Japanese names.
+fn initials(name: &str) -> String {
+    let words = name.split_whitespace().collect::<Vec<_>>();
+
+    let initials = match words.as_slice() {
+        [] => return String::new(),
+        [m] => m.chars().take(1).collect::<String>(),
+        [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+    };
+
+    initials.to_uppercase()
+}
+
+// The same name always has the same colour, it is hashed with FNV-1a.
+fn name_colour(name: &str) -> Colour {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, m| {
+        (hash ^ m as u32).wrapping_mul(0x0100_0193)
+    });
+
+    Colour::Hsl((hash % 360) as u16, 45, 55)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Kaede Hoshikawa"), "KH");
+        assert_eq!(initials("Mary Jane van Dyke"), "MD");
+        assert_eq!(initials("futursolo"), "F");
+        assert_eq!(initials("星川かえで"), "星");
+        assert_eq!(initials("  "), "");
+    }
+
+    #[test]
+    fn test_name_colour_is_stable() {
+        assert_eq!(name_colour("futursolo"), name_colour("futursolo"));
+        assert_ne!(name_colour("futursolo"), name_colour("kaede"));
+    }
 }
@@ -1,12 +1,12 @@
 use std::borrow::Cow;
-use std::time::Duration;
 
-use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlElement;
 use yew::prelude::*;
 
-use crate::helpers::{call_after, document, is_mobile_safari, window, Instant};
+use crate::css::{css, Stylesheet};
+use crate::helpers::{document, is_mobile_safari};
+use crate::services::Viewport;
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Debug, Clone, PartialEq)]
@@ -22,39 +22,53 @@ impl YieldStyle for BodyProps {
         "fl-body".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = r#"
-        margin: 0;
-        padding: 0;
-        width: 100vw;
-        /* min-height: 100vh; */
-        font-size: 15px;
-
-        display: flex;
-        flex-direction: row;
-        justify-content: space-around;
-        align-items: center;
-
-        @media only screen and (max-width: 340px) {
-            font-size: 14px;
-        }
-        "#
-        .to_string();
+    fn yield_style_str(&self) -> Stylesheet {
+        self.style(is_mobile_safari())
+    }
+}
+
+impl BodyProps {
+    // The style in browsers other than mobile Safari, which the static pages are prerendered with.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn prerender_style(&self) -> Stylesheet {
+        self.style(false)
+    }
+
+    fn style(&self, mobile_safari: bool) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            margin: 0;
+            padding: 0;
+            width: 100vw;
+            /* min-height: 100vh; */
+            font-size: 15px;
+
+            display: flex;
+            flex-direction: row;
+            justify-content: space-around;
+            align-items: center;
+
+            @media only screen and (max-width: 340px) {
+                font-size: 14px;
+            }
+            "#
+        );
 
         if self.grey_background {
-            style_string.push_str("background-color: rgb(244, 245, 249);\n");
+            style += css!("background-color: rgb(244, 245, 249);");
         }
 
-        if !is_mobile_safari() {
-            style_string.push_str("min-height: 100vh;\n");
+        if !mobile_safari {
+            style += css!("min-height: 100vh;");
         }
 
-        style_string.into()
+        style
     }
 }
 
 pub enum BodyMsg {
-    UpdateViewHeight,
+    // The height of the viewport.
+    ViewportResized(f64),
 }
 
 type BodyParentChanged = bool;
@@ -68,9 +82,16 @@ struct BodyParent {
 
 impl BodyParent {
     fn new(element: HtmlElement, body_props: &BodyProps) -> Self {
+        // The class of a prerendered page, which is replaced if the style differs in this browser.
+        let style_class = element
+            .class_name()
+            .split_whitespace()
+            .find(|m| m.starts_with("fl-html-"))
+            .map(|m| m.to_string());
+
         Self {
             element,
-            style_class: None,
+            style_class,
             grey_background: body_props.grey_background,
         }
     }
@@ -80,7 +101,10 @@ impl BodyParent {
     }
 
     fn render(&mut self) -> BodyParentChanged {
-        let new_style_class = self.yield_style_class();
+        let new_style_class = HtmlStyle {
+            grey_background: self.grey_background,
+        }
+        .yield_style_class();
         if let Some(ref m) = self.style_class {
             if new_style_class.as_str() == m {
                 return false;
@@ -97,43 +121,62 @@ impl BodyParent {
     }
 }
 
-impl YieldStyle for BodyParent {
+// The style of the <html> element, which is outside of the app and set by Body.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HtmlStyle {
+    pub grey_background: bool,
+}
+
+impl YieldStyle for HtmlStyle {
     fn element_name(&self) -> Cow<'static, str> {
         "fl-html".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = r#"
-        margin: 0;
-        padding: 0;
-        width: 100vw;
-        /* min-height: 100vh; */
+    fn yield_style_str(&self) -> Stylesheet {
+        self.style(is_mobile_safari())
+    }
+}
+
+impl HtmlStyle {
+    // As BodyProps::prerender_style.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn prerender_style(&self) -> Stylesheet {
+        self.style(false)
+    }
 
-        &[lang="zh-hant"] {
-            font-family: "PingFang TC", "Helvetica Neue", Helvetica, sans-serif;
-        }
-        "#
-        .to_string();
+    fn style(&self, mobile_safari: bool) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            margin: 0;
+            padding: 0;
+            width: 100vw;
+            /* min-height: 100vh; */
+
+            &[lang="zh-hant"] {
+                font-family: "PingFang TC", "Helvetica Neue", Helvetica, sans-serif;
+            }
+            "#
+        );
 
         if self.grey_background {
-            style_string.push_str("background-color: rgb(244, 245, 249);\n");
+            style += css!("background-color: rgb(244, 245, 249);");
         }
 
-        if !is_mobile_safari() {
-            style_string.push_str("min-height: 100vh;\n");
+        if !mobile_safari {
+            style += css!("min-height: 100vh;");
         }
 
-        style_string.into()
+        style
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Body {
     props: BodyProps,
     parent: Option<BodyParent>,
-    link: ComponentLink<Self>,
     body_ref: NodeRef,
-    last_vh_update: Option<Instant>,
+    // Only on mobile Safari, where 100vh is taller than the window.
+    viewport: Option<Viewport>,
 }
 
 impl Component for Body {
@@ -141,29 +184,27 @@ impl Component for Body {
     type Properties = BodyProps;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let viewport = if is_mobile_safari() {
+            Some(Viewport::subscribe(move |m| {
+                link.send_message(BodyMsg::ViewportResized(m.height))
+            }))
+        } else {
+            None
+        };
+
         Self {
             props,
             parent: None,
-            link,
             body_ref: NodeRef::default(),
-            last_vh_update: None,
+            viewport,
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            BodyMsg::UpdateViewHeight => {
-                // At least wait 100ms.
-                if let Some(ref m) = self.last_vh_update {
-                    if m.elapsed() < Duration::from_millis(100) {
-                        return false;
-                    }
-                }
-
-                self.last_vh_update = Some(Instant::now());
-                self.update_view_height();
-
-                true
+            BodyMsg::ViewportResized(m) => {
+                self.update_view_height(m);
+                false
             }
         }
     }
@@ -179,9 +220,8 @@ impl Component for Body {
 
     fn rendered(&mut self, first_render: bool) {
         if first_render {
-            if is_mobile_safari() {
-                self.register_events();
-                self.link.send_message(BodyMsg::UpdateViewHeight);
+            if let Some(ref m) = self.viewport {
+                self.update_view_height(m.state().height);
             }
         }
 
@@ -220,12 +260,11 @@ impl Body {
             .unwrap()
     }
 
-    fn update_view_height(&self) {
+    fn update_view_height(&self, height: f64) {
         let body = self.body_element();
         let html_ = self.html_element();
 
-        let view_height = window().inner_height().unwrap().as_f64().unwrap() as i64;
-        let view_height_str = format!("{}px", view_height);
+        let view_height_str = format!("{}px", height as i64);
 
         body.style()
             .set_property("min-height", &view_height_str)
@@ -235,27 +274,4 @@ impl Body {
             .set_property("min-height", &view_height_str)
             .unwrap();
     }
-
-    fn register_events(&self) {
-        let link = self.link.clone();
-        let cb = Closure::wrap(Box::new(move || {
-            let link = link.clone();
-            call_after(
-                move || link.send_message(BodyMsg::UpdateViewHeight),
-                Duration::from_millis(100),
-            )
-        }) as Box<dyn FnMut()>);
-
-        window()
-            .add_event_listener_with_callback("orientationchange", cb.as_ref().unchecked_ref())
-            .unwrap();
-        window()
-            .add_event_listener_with_callback("pageshow", cb.as_ref().unchecked_ref())
-            .unwrap();
-        window()
-            .add_event_listener_with_callback("resize", cb.as_ref().unchecked_ref())
-            .unwrap();
-
-        cb.forget();
-    }
 }
@@ -3,6 +3,7 @@ use std::borrow::Cow;
 use web_sys::MouseEvent;
 use yew::prelude::*;
 
+use crate::css::{self, css, Colour, Length, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -19,26 +20,28 @@ pub enum ButtonColor {
 }
 
 impl ButtonColor {
-    fn normal_color(&self) -> (u8, u8, u8) {
-        match self {
+    fn normal_color(&self) -> Colour {
+        let (r, g, b) = match self {
             Self::Blue => (92, 184, 230),
             Self::Black => (5, 5, 5),
             Self::Red => (238, 82, 26),
             Self::Green => (50, 191, 50),
             Self::Yellow => (255, 193, 7),
             Self::Custom { normal, hover: _ } => *normal,
-        }
+        };
+        Colour::Rgb(r, g, b)
     }
 
-    fn hover_color(&self) -> (u8, u8, u8) {
-        match self {
+    fn hover_color(&self) -> Colour {
+        let (r, g, b) = match self {
             Self::Blue => (125, 198, 235),
             Self::Black => (50, 50, 50),
             Self::Red => (240, 99, 48),
             Self::Green => (91, 204, 91),
             Self::Yellow => (255, 205, 57),
             Self::Custom { normal: _, hover } => *hover,
-        }
+        };
+        Colour::Rgb(r, g, b)
     }
 }
 
@@ -61,6 +64,9 @@ pub struct ButtonProps {
     #[prop_or(None)]
     pub width: Option<String>,
 
+    #[prop_or(false)]
+    pub disabled: bool,
+
     #[prop_or_else(Callback::noop)]
     pub onclick: Callback<MouseEvent>,
 }
@@ -70,53 +76,70 @@ impl YieldStyle for ButtonProps {
         "fl-button".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let normal_color = self.color.normal_color();
-        let hover_color = self.color.hover_color();
-
-        let mut style_string = format!(
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
             r#"
-            display: inline-flex;
-            justify-content: center;
-            align-items: center;
-            flex-direction: row;
-
-            border-radius: 500px;
-            padding-left: 30px;
-            padding-right: 30px;
-            height: {height};
-            box-sizing: border-box;
-
-            text-align: center;
-
-            background-color: rgb({}, {}, {});
-            color: white;
-            transition: background-color 0.20s;
-
-            cursor: default;
-            -moz-user-select: none;
-            -webkit-user-select: none;
-            -ms-user-select: none;
-            user-select: none;
-
-            &:hover {{
-                background-color: rgb({}, {}, {});
-            }}
-            "#,
-            normal_color.0,
-            normal_color.1,
-            normal_color.2,
-            hover_color.0,
-            hover_color.1,
-            hover_color.2,
-            height = &self.height,
+                display: inline-flex;
+                justify-content: center;
+                align-items: center;
+                flex-direction: row;
+
+                border-radius: 500px;
+                padding-left: 30px;
+                padding-right: 30px;
+                height: var(--fl-button-height);
+                width: var(--fl-button-width, auto);
+                box-sizing: border-box;
+
+                text-align: center;
+
+                background-color: var(--fl-button-colour);
+                color: white;
+                transition: background-color 0.20s;
+
+                cursor: default;
+                -moz-user-select: none;
+                -webkit-user-select: none;
+                -ms-user-select: none;
+                user-select: none;
+
+                &:hover {
+                    background-color: var(--fl-button-hover-colour);
+                }
+                "#
         );
 
-        if let Some(ref m) = self.width.as_ref() {
-            style_string.push_str(format!("width: {};\n", m).as_str());
+        if self.disabled {
+            style += css!(
+                r#"
+                background-color: rgb(200, 200, 200);
+                cursor: not-allowed;
+
+                &:hover {
+                    background-color: rgb(200, 200, 200);
+                }
+                "#
+            );
         }
 
-        style_string.into()
+        style
+    }
+
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            (
+                "height",
+                css::checked("height", Length::parse(&self.height)).map(|m| m.to_string()),
+            ),
+            (
+                "width",
+                self.width
+                    .as_ref()
+                    .and_then(|m| css::checked("width", Length::parse(m)).map(|m| m.to_string())),
+            ),
+            ("colour", Some(self.color.normal_color().to_string())),
+            ("hover-colour", Some(self.color.hover_color().to_string())),
+        ]
     }
 }
 
@@ -152,10 +175,20 @@ impl Component for Button {
     }
 
     fn view(&self) -> Html {
+        let onclick = if self.props.disabled {
+            Callback::noop()
+        } else {
+            self.props.onclick.clone()
+        };
+
         html! {
-            <div ref=self.root_ref.clone() class=self.props.yield_style_class() onclick=self.props.onclick.clone()>
+            <div ref=self.root_ref.clone() class=self.props.yield_style_class() onclick=onclick>
                 { self.props.children.clone() }
             </div>
         }
     }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.props.apply_style_vars(&self.root_ref);
+    }
 }
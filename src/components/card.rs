@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use crate::css::{self, css, Keyword, Length, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Properties, Clone, Debug, PartialEq)]
@@ -45,69 +46,78 @@ impl YieldStyle for CardProps {
         "fl-card".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = r#"
-        display: flex;
-        flex-direction: column;
-        justify-content: space-around;
-        align-items: center;
-
-        border-radius: 10px;
-        background-color: white;
-        box-sizing: border-box;
-        padding: 20px;
-
-        @supports (backdrop-filter: blur(100px) saturate(180%)) or (-webkit-backdrop-filter: blur(100px) saturate(180%)) {
-            background-color: rgba(255, 255, 255, 0.7);
-            backdrop-filter: blur(100px) saturate(180%);
-            -webkit-backdrop-filter: blur(100px) saturate(180%)
-        }
-        "#.to_string();
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            display: flex;
+            flex-direction: var(--fl-card-flex-direction, column);
+            justify-content: var(--fl-card-justify-content, space-around);
+            align-items: var(--fl-card-align-items, center);
+
+            max-width: var(--fl-card-max-width, none);
+            width: var(--fl-card-width, auto);
+            max-height: var(--fl-card-max-height, none);
+            height: var(--fl-card-height, auto);
+
+            border-radius: 10px;
+            background-color: white;
+            box-sizing: border-box;
+            padding: 20px;
+
+            @supports (backdrop-filter: blur(100px) saturate(180%)) or (-webkit-backdrop-filter: blur(100px) saturate(180%)) {
+                background-color: rgba(255, 255, 255, 0.7);
+                backdrop-filter: blur(100px) saturate(180%);
+                -webkit-backdrop-filter: blur(100px) saturate(180%)
+            }
+            "#
+        );
 
         if self.with_shadow {
-            style_string.push_str("box-shadow: 0 0 10px 0 rgba(150, 150, 150, 0.8);\n");
+            style += css!("box-shadow: 0 0 10px 0 rgba(150, 150, 150, 0.8);");
         }
 
         if self.with_margin {
-            style_string.push_str("margin: 20px;\n");
+            style += css!("margin: 20px;");
         } else {
-            style_string.push_str("margin: 0;\n");
+            style += css!("margin: 0;");
         }
 
         if self.with_padding {
-            style_string.push_str("padding: 20px;\n");
+            style += css!("padding: 20px;");
         } else {
-            style_string.push_str("padding: 0;\n");
-        }
-
-        if let Some(ref m) = self.max_width {
-            style_string.push_str(format!("max-width: {};\n", m).as_str());
-        }
-
-        if let Some(ref m) = self.width {
-            style_string.push_str(format!("width: {};\n", m).as_str());
-        }
-
-        if let Some(ref m) = self.max_height {
-            style_string.push_str(format!("max-height: {};\n", m).as_str());
+            style += css!("padding: 0;");
         }
 
-        if let Some(ref m) = self.height {
-            style_string.push_str(format!("height: {};\n", m).as_str());
-        }
-
-        if let Some(ref m) = self.flex_direction {
-            style_string.push_str(format!("flex-direction: {};\n", m).as_str());
-        }
-
-        if let Some(ref m) = self.justify_content {
-            style_string.push_str(format!("justify-content: {};\n", m).as_str());
-        }
+        style
+    }
 
-        if let Some(ref m) = self.align_items {
-            style_string.push_str(format!("align-items: {};\n", m).as_str());
-        }
-        style_string.into()
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        let length = |property, value: &Option<String>| {
+            value
+                .as_ref()
+                .and_then(|m| css::checked(property, Length::parse(m)).map(|m| m.to_string()))
+        };
+        let keyword = |property, value: &Option<String>| {
+            value
+                .as_ref()
+                .and_then(|m| css::checked(property, Keyword::parse(m)).map(|m| m.to_string()))
+        };
+
+        vec![
+            ("max-width", length("max-width", &self.max_width)),
+            ("width", length("width", &self.width)),
+            ("max-height", length("max-height", &self.max_height)),
+            ("height", length("height", &self.height)),
+            (
+                "flex-direction",
+                keyword("flex-direction", &self.flex_direction),
+            ),
+            (
+                "justify-content",
+                keyword("justify-content", &self.justify_content),
+            ),
+            ("align-items", keyword("align-items", &self.align_items)),
+        ]
     }
 }
 
@@ -115,13 +125,18 @@ impl YieldStyle for CardProps {
 pub struct Card {
     props: CardProps,
     link: ComponentLink<Self>,
+    root_ref: NodeRef,
 }
 
 impl Component for Card {
     type Message = ();
     type Properties = CardProps;
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        Self { props, link }
+        Self {
+            props,
+            link,
+            root_ref: NodeRef::default(),
+        }
     }
 
     fn update(&mut self, _msg: Self::Message) -> ShouldRender {
@@ -139,9 +154,73 @@ impl Component for Card {
 
     fn view(&self) -> Html {
         html! {
-            <div class=self.props.yield_style_class()>
+            <div ref=self.root_ref.clone() class=self.props.yield_style_class()>
                 { self.props.children.clone() }
             </div>
         }
     }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.props.apply_style_vars(&self.root_ref);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::HtmlElement;
+
+    use super::*;
+    use crate::helpers::document;
+
+    fn props(width: &str) -> CardProps {
+        CardProps {
+            children: Children::default(),
+            with_shadow: true,
+            with_margin: false,
+            with_padding: true,
+            max_width: None,
+            width: Some(width.to_string()),
+            max_height: None,
+            height: None,
+            flex_direction: None,
+            justify_content: None,
+            align_items: Some("flex-start".to_string()),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_sizes_share_style() {
+        // Each of these used to add a <style> element.
+        let widths = ["100%", "400px", "90%", "calc(100vw - 40px)"];
+        let styles = widths
+            .iter()
+            .map(|m| props(m).yield_style_str())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(styles.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_set_style_vars() {
+        let element = document()
+            .create_element("div")
+            .unwrap()
+            .unchecked_into::<HtmlElement>();
+
+        props("400px").set_style_vars(&element);
+
+        let style = element.style();
+        let value = |m| style.get_property_value(m).unwrap();
+        assert_eq!(value("--fl-card-width"), "400px");
+        assert_eq!(value("--fl-card-max-width"), "initial");
+        assert_eq!(value("--fl-card-align-items"), "flex-start");
+
+        props("1px; color: red").set_style_vars(&element);
+        assert_eq!(value("--fl-card-width"), "initial");
+        assert_eq!(value("color"), "");
+    }
 }
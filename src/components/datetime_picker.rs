@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::timezone::{LocalDateTime, TimeZone};
+use crate::traits::YieldStyle;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct DateTimePickerProps {
+    // Unix timestamp in seconds.
+    #[prop_or(None)]
+    pub value: Option<i64>,
+
+    // The IANA timezone the date and time are entered in.
+    pub timezone: String,
+
+    #[prop_or_else(Callback::noop)]
+    pub onchange: Callback<i64>,
+
+    #[prop_or(false)]
+    pub disabled: bool,
+}
+
+impl YieldStyle for DateTimePickerProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-datetime-picker".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: row;
+            flex-wrap: wrap;
+            align-items: center;
+
+            input {
+                height: 40px;
+                border-radius: 500px;
+                border: 1px solid rgb(150, 150, 150);
+                box-sizing: border-box;
+                padding-left: 15px;
+                padding-right: 15px;
+                margin-right: 5px;
+                font-size: 1rem;
+                outline: 0;
+            }
+
+            input:focus {
+                border-color: rgb(92, 184, 230);
+            }
+
+            .timezone {
+                font-size: 0.8rem;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+pub enum DateTimePickerMsg {
+    Date(String),
+    Time(String),
+}
+
+#[derive(Debug)]
+pub struct DateTimePicker {
+    props: DateTimePickerProps,
+    link: ComponentLink<Self>,
+
+    timezone: TimeZone,
+    date: String,
+    time: String,
+}
+
+impl Component for DateTimePicker {
+    type Message = DateTimePickerMsg;
+    type Properties = DateTimePickerProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut m = Self {
+            timezone: Self::timezone(&props.timezone),
+            props,
+            link,
+
+            date: String::new(),
+            time: String::new(),
+        };
+        m.sync_value();
+        m
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            DateTimePickerMsg::Date(m) => self.date = m,
+            DateTimePickerMsg::Time(m) => self.time = m,
+        }
+
+        // Waits until both inputs are filled.
+        if let Some(m) = LocalDateTime::parse(&self.date, &self.time) {
+            self.props.onchange.emit(self.timezone.to_timestamp(m));
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+
+        if props.timezone != self.props.timezone {
+            self.timezone = Self::timezone(&props.timezone);
+        }
+        self.props = props;
+        self.sync_value();
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class=self.props.yield_style_class()>
+                <input type="date" value=self.date.clone() disabled=self.props.disabled
+                    oninput=self.link.callback(|e: InputData| DateTimePickerMsg::Date(e.value)) />
+                <input type="time" value=self.time.clone() disabled=self.props.disabled
+                    oninput=self.link.callback(|e: InputData| DateTimePickerMsg::Time(e.value)) />
+                <div class="timezone">{ self.timezone_hint() }</div>
+            </div>
+        }
+    }
+}
+
+impl DateTimePicker {
+    fn timezone(name: &str) -> TimeZone {
+        TimeZone::new(name).unwrap_or_else(|_| {
+            log::error!(
+                "Unknown timezone: {}, using the timezone of the browser.",
+                name
+            );
+            TimeZone::browser()
+        })
+    }
+
+    // Keeps what is being typed unless the value has been changed from outside.
+    fn sync_value(&mut self) {
+        let current =
+            LocalDateTime::parse(&self.date, &self.time).map(|m| self.timezone.to_timestamp(m));
+
+        if current == self.props.value {
+            return;
+        }
+
+        if let Some(m) = self.props.value {
+            let local = self.timezone.to_local(m);
+            self.date = local.date_str();
+            self.time = local.time_str();
+        }
+    }
+
+    fn timezone_hint(&self) -> String {
+        let browser = TimeZone::browser();
+        let value = match self.props.value {
+            Some(m) if browser != self.timezone => m,
+            _ => return self.timezone.name().to_string(),
+        };
+
+        let local = browser.to_local(value);
+        format!(
+            "{}（本機時間 {} {}）",
+            self.timezone.name(),
+            local.date_str(),
+            local.time_str()
+        )
+    }
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::diff::{diff_lines, DiffKind, DiffLine};
+use crate::traits::YieldStyle;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct DiffViewProps {
+    pub old: String,
+    pub new: String,
+}
+
+impl YieldStyle for DiffViewProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-diff-view".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            width: 100%;
+            overflow-x: auto;
+            font-family: monospace;
+            font-size: 0.9rem;
+            line-height: 1.6;
+
+            .line {
+                display: flex;
+                flex-direction: row;
+                min-height: 1.6em;
+            }
+
+            .line.insert {
+                background-color: rgba(50, 191, 50, 0.1);
+            }
+
+            .line.delete {
+                background-color: rgba(220, 53, 69, 0.1);
+            }
+
+            .sign {
+                flex-shrink: 0;
+                width: 20px;
+                text-align: center;
+                color: rgb(150, 150, 150);
+
+                -moz-user-select: none;
+                -webkit-user-select: none;
+                -ms-user-select: none;
+                user-select: none;
+            }
+
+            .text {
+                white-space: pre-wrap;
+                word-break: break-word;
+            }
+
+            span.insert {
+                background-color: rgba(50, 191, 50, 0.35);
+            }
+
+            span.delete {
+                background-color: rgba(220, 53, 69, 0.35);
+                text-decoration: line-through;
+            }
+
+            .no-change {
+                text-align: center;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+fn kind_class(kind: DiffKind) -> &'static str {
+    match kind {
+        DiffKind::Equal => "equal",
+        DiffKind::Insert => "insert",
+        DiffKind::Delete => "delete",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    props: DiffViewProps,
+    lines: Vec<DiffLine>,
+}
+
+impl Component for DiffView {
+    type Message = ();
+    type Properties = DiffViewProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        let lines = diff_lines(&props.old, &props.new);
+        Self { props, lines }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.lines = diff_lines(&props.old, &props.new);
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        if self.props.old == self.props.new {
+            return html! {
+                <div class=self.props.yield_style_class()>
+                    <div class="no-change">{ "沒有變更" }</div>
+                </div>
+            };
+        }
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                { for self.lines.iter().map(|m| self.render_line(m)) }
+            </div>
+        }
+    }
+}
+
+impl DiffView {
+    fn render_line(&self, line: &DiffLine) -> Html {
+        let sign = match line.kind {
+            DiffKind::Equal => " ",
+            DiffKind::Insert => "+",
+            DiffKind::Delete => "-",
+        };
+
+        html! {
+            <div class=format!("line {}", kind_class(line.kind))>
+                <div class="sign">{ sign }</div>
+                <div class="text">
+                    {
+                        for line.spans.iter().map(|m| match m.kind {
+                            DiffKind::Equal => html! { { m.text.clone() } },
+                            _ => html! { <span class=kind_class(m.kind)>{ m.text.clone() }</span> },
+                        })
+                    }
+                </div>
+            </div>
+        }
+    }
+}
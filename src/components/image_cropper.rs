@@ -0,0 +1,306 @@
+use std::borrow::Cow;
+
+use web_sys::{HtmlElement, HtmlImageElement};
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::traits::YieldStyle;
+
+const MAX_ZOOM: f64 = 4.0;
+
+// A square region of the image in its natural pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropArea {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct ImageCropperProps {
+    pub src: String,
+
+    // The width and height of the viewport in pixels.
+    #[prop_or(280)]
+    pub size: u32,
+
+    // Emitted once the image is loaded and whenever the area is moved or zoomed.
+    #[prop_or_else(Callback::noop)]
+    pub onchange: Callback<CropArea>,
+}
+
+impl YieldStyle for ImageCropperProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-image-cropper".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+
+                .viewport {
+                    position: relative;
+                    width: var(--fl-image-cropper-size);
+                    height: var(--fl-image-cropper-size);
+                    overflow: hidden;
+                    background-color: rgb(50, 50, 50);
+                    cursor: move;
+                    touch-action: none;
+
+                    -moz-user-select: none;
+                    -webkit-user-select: none;
+                    -ms-user-select: none;
+                    user-select: none;
+                }
+
+                .viewport img {
+                    position: absolute;
+                    max-width: none;
+                    pointer-events: none;
+                }
+
+                /* Loaded out of sight to learn its size. */
+                .viewport img.loading {
+                    visibility: hidden;
+                }
+
+                .viewport .mask {
+                    position: absolute;
+                    top: 0;
+                    left: 0;
+                    width: 100%;
+                    height: 100%;
+                    border-radius: 100%;
+                    box-shadow: 0 0 0 var(--fl-image-cropper-size) rgba(0, 0, 0, 0.4);
+                    pointer-events: none;
+                }
+
+                .zoom {
+                    display: flex;
+                    flex-direction: row;
+                    align-items: center;
+                    width: var(--fl-image-cropper-size);
+                    margin-top: 10px;
+                    color: rgb(150, 150, 150);
+                }
+
+                .zoom input {
+                    flex-grow: 1;
+                    margin-left: 10px;
+                    margin-right: 10px;
+                }
+                "#
+        )
+    }
+
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![("size", Some(format!("{}px", self.size)))]
+    }
+}
+
+pub enum ImageCropperMsg {
+    Loaded,
+    DragStart(i32, i32),
+    DragMove(i32, i32),
+    DragEnd,
+    Zoom(String),
+}
+
+#[derive(Debug)]
+pub struct ImageCropper {
+    props: ImageCropperProps,
+    link: ComponentLink<Self>,
+    root_ref: NodeRef,
+    img_ref: NodeRef,
+
+    // Natural width and height of the image, None until it is loaded.
+    natural: Option<(f64, f64)>,
+    // 1 fits the shorter side of the image to the viewport.
+    zoom: f64,
+    // Centre of the area in natural pixels.
+    centre: (f64, f64),
+    // The last pointer position while dragging.
+    dragging: Option<(i32, i32)>,
+}
+
+impl Component for ImageCropper {
+    type Message = ImageCropperMsg;
+    type Properties = ImageCropperProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            props,
+            link,
+            root_ref: NodeRef::default(),
+            img_ref: NodeRef::default(),
+
+            natural: None,
+            zoom: 1.0,
+            centre: (0.0, 0.0),
+            dragging: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ImageCropperMsg::Loaded => {
+                let img = match self.img_ref.cast::<HtmlImageElement>() {
+                    Some(m) => m,
+                    None => return false,
+                };
+                let (width, height) = (img.natural_width() as f64, img.natural_height() as f64);
+                if width == 0.0 || height == 0.0 {
+                    return false;
+                }
+
+                self.natural = Some((width, height));
+                self.zoom = 1.0;
+                self.centre = (width / 2.0, height / 2.0);
+                self.emit();
+                true
+            }
+            ImageCropperMsg::DragStart(x, y) => {
+                self.dragging = Some((x, y));
+                false
+            }
+            ImageCropperMsg::DragMove(x, y) => {
+                let (last_x, last_y) = match self.dragging {
+                    Some(m) => m,
+                    None => return false,
+                };
+                self.dragging = Some((x, y));
+
+                // Dragging moves the image, so the area moves the other way.
+                let scale = self.scale();
+                self.centre.0 -= (x - last_x) as f64 / scale;
+                self.centre.1 -= (y - last_y) as f64 / scale;
+                self.clamp();
+                self.emit();
+                true
+            }
+            ImageCropperMsg::DragEnd => {
+                self.dragging = None;
+                false
+            }
+            ImageCropperMsg::Zoom(m) => {
+                let zoom = match m.parse::<f64>() {
+                    Ok(m) => m.clamp(1.0, MAX_ZOOM),
+                    Err(_) => return false,
+                };
+                self.zoom = zoom;
+                self.clamp();
+                self.emit();
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            // Waits for the new image to load.
+            if props.src != self.props.src {
+                self.natural = None;
+            }
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div ref=self.root_ref.clone() class=self.props.yield_style_class()>
+                <div class="viewport"
+                    onmousedown=self.link.callback(|e: MouseEvent| {
+                        e.prevent_default();
+                        ImageCropperMsg::DragStart(e.client_x(), e.client_y())
+                    })
+                    onmousemove=self.link.callback(|e: MouseEvent| ImageCropperMsg::DragMove(e.client_x(), e.client_y()))
+                    onmouseup=self.link.callback(|_| ImageCropperMsg::DragEnd)
+                    onmouseleave=self.link.callback(|_| ImageCropperMsg::DragEnd)>
+                    <img ref=self.img_ref.clone() src=self.props.src.clone()
+                        class=if self.natural.is_some() { "" } else { "loading" }
+                        onload=self.link.callback(|_| ImageCropperMsg::Loaded) />
+                    <div class="mask"></div>
+                </div>
+                <div class="zoom">
+                    <i class="fas fa-search-minus"></i>
+                    <input type="range" min="1" max=MAX_ZOOM.to_string() step="0.01" value=self.zoom.to_string()
+                        oninput=self.link.callback(|e: InputData| ImageCropperMsg::Zoom(e.value)) />
+                    <i class="fas fa-search-plus"></i>
+                </div>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.props.apply_style_vars(&self.root_ref);
+
+        let (width, height) = match self.natural {
+            Some(m) => m,
+            None => return,
+        };
+        let img = match self.img_ref.cast::<HtmlElement>() {
+            Some(m) => m,
+            None => return,
+        };
+
+        // Set through the CSSOM as it changes while dragging, which would add a class each time.
+        let scale = self.scale();
+        let half = self.props.size as f64 / 2.0;
+        let style = img.style();
+        for (property, value) in &[
+            ("width", width * scale),
+            ("height", height * scale),
+            ("left", half - self.centre.0 * scale),
+            ("top", half - self.centre.1 * scale),
+        ] {
+            style
+                .set_property(property, &format!("{}px", value))
+                .unwrap();
+        }
+    }
+}
+
+impl ImageCropper {
+    // Screen pixels per natural pixel.
+    fn scale(&self) -> f64 {
+        match self.natural {
+            Some((width, height)) => self.props.size as f64 / width.min(height) * self.zoom,
+            None => 1.0,
+        }
+    }
+
+    // The side of the area in natural pixels.
+    fn area_size(&self) -> f64 {
+        self.props.size as f64 / self.scale()
+    }
+
+    // Keeps the area within the image.
+    fn clamp(&mut self) {
+        let (width, height) = match self.natural {
+            Some(m) => m,
+            None => return,
+        };
+        let half = self.area_size() / 2.0;
+
+        self.centre.0 = self.centre.0.max(half).min(width - half);
+        self.centre.1 = self.centre.1.max(half).min(height - half);
+    }
+
+    fn emit(&self) {
+        if self.natural.is_none() {
+            return;
+        }
+        let size = self.area_size();
+
+        self.props.onchange.emit(CropArea {
+            x: (self.centre.0 - size / 2.0).max(0.0).round() as u32,
+            y: (self.centre.1 - size / 2.0).max(0.0).round() as u32,
+            size: size.round() as u32,
+        });
+    }
+}
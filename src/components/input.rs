@@ -5,6 +5,7 @@ use std::string::ToString;
 use web_sys::{HtmlInputElement, KeyboardEvent};
 use yew::prelude::*;
 
+use crate::css::{self, css, Length, Stylesheet};
 use crate::helpers::WeakComponentLink;
 use crate::traits::YieldStyle;
 
@@ -215,6 +216,8 @@ impl Component for Input {
     }
 
     fn rendered(&mut self, _first_render: bool) {
+        self.style().apply_style_vars(&self.props.input_ref);
+
         let input = self.props.input_ref.cast::<HtmlInputElement>().unwrap();
 
         if let Some(ref m) = self.props.value.as_ref() {
@@ -280,46 +283,62 @@ impl Component for Input {
 
     fn view(&self) -> Html {
         html! {
-            <input class=self.yield_style_class() ref=self.props.input_ref.clone() onkeyup=self.props.onkeyup.clone() onfocus=self.link.callback(|_| InputMsg::Focus) oninput=self.link.callback(|e: InputData| InputMsg::Input(e.value)) type={ self.props.type_.type_str() } disabled={ self.props.disabled } autofocus={ self.props.autofocus } required={ self.props.required } readonly={ self.props.readonly } />
+            <input class=self.style().yield_style_class() ref=self.props.input_ref.clone() onkeyup=self.props.onkeyup.clone() onfocus=self.link.callback(|_| InputMsg::Focus) oninput=self.link.callback(|e: InputData| InputMsg::Input(e.value)) type={ self.props.type_.type_str() } disabled={ self.props.disabled } autofocus={ self.props.autofocus } required={ self.props.required } readonly={ self.props.readonly } />
+        }
+    }
+}
+
+impl Input {
+    fn style(&self) -> InputStyle {
+        InputStyle {
+            invalid: self.invalid,
+            height: self.props.height.clone(),
+            width: self.props.width.clone(),
         }
     }
 }
 
-impl YieldStyle for Input {
+// The style of Input, which depends on whether the value is invalid as well as the props.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InputStyle {
+    pub invalid: bool,
+    pub height: String,
+    pub width: String,
+}
+
+impl YieldStyle for InputStyle {
     fn element_name(&self) -> Cow<'static, str> {
         "fl-input".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        let mut style_string = format!(
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
             r#"
-            height: {height};
-            width: {width};
-            border-radius: 500px;
-            border: 1px solid rgb(150, 150, 150);
-            box-sizing: border-box;
-            padding-left: 20px;
-            padding-right: 20px;
-            outline: 0;
-            transition: box-shadow 0.20s, border-color 0.20s, background-color 0.20s;
-            font-size: 1rem;
-
-            -webkit-appearance: none;
-        "#,
-            height = &self.props.height,
-            width = &self.props.width,
+                height: var(--fl-input-height);
+                width: var(--fl-input-width);
+                border-radius: 500px;
+                border: 1px solid rgb(150, 150, 150);
+                box-sizing: border-box;
+                padding-left: 20px;
+                padding-right: 20px;
+                outline: 0;
+                transition: box-shadow 0.20s, border-color 0.20s, background-color 0.20s;
+                font-size: 1rem;
+
+                -webkit-appearance: none;
+            "#
         );
 
         if self.invalid {
-            style_string.push_str(
+            style += css!(
                 r#"
                 border-color: rgb(238, 82, 26);
                 box-shadow: 0 0 0 2px rgba(238, 82, 26, 1);
                 background-color: rgb(254, 237, 234);
-                "#,
+                "#
             );
         } else {
-            style_string.push_str(
+            style += css!(
                 r#"
                 box-shadow: 0 0 0 2px rgba(125, 198, 235, 0);
                 background-color: rgb(255, 255, 255);
@@ -332,10 +351,23 @@ impl YieldStyle for Input {
                     border-color: rgb(125, 198, 235);
                     box-shadow: 0 0 0 2px rgba(125, 198, 235, 1);
                 }
-                "#,
+                "#
             );
         }
 
-        style_string.into()
+        style
+    }
+
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            (
+                "height",
+                css::checked("height", Length::parse(&self.height)).map(|m| m.to_string()),
+            ),
+            (
+                "width",
+                css::checked("width", Length::parse(&self.width)).map(|m| m.to_string()),
+            ),
+        ]
     }
 }
@@ -2,12 +2,26 @@ mod avatar;
 mod body;
 mod button;
 mod card;
+mod datetime_picker;
+mod diff_view;
+mod image_cropper;
 mod input;
+mod pagination;
+mod progressive_image;
 mod spinner;
+mod table;
 
 pub use avatar::Avatar;
-pub use body::Body;
-pub use button::{Button, ButtonColor};
-pub use card::Card;
+pub(crate) use body::HtmlStyle;
+pub use body::{Body, BodyProps};
+pub use button::{Button, ButtonColor, ButtonProps};
+pub use card::{Card, CardProps};
+pub use datetime_picker::DateTimePicker;
+pub use diff_view::DiffView;
+pub use image_cropper::{CropArea, ImageCropper};
+pub(crate) use input::InputStyle;
 pub use input::{Input, InputMsg, InputType, InputValue};
+pub use pagination::Pagination;
+pub use progressive_image::{ProgressiveImage, ProgressiveImageProps};
 pub use spinner::Spinner;
+pub use table::{SortOrder, Table, TableColumn};
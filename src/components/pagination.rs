@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{css, Stylesheet};
+use crate::traits::YieldStyle;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct PaginationProps {
+    // Starts from 1.
+    pub page: u64,
+    pub total_pages: u64,
+
+    #[prop_or_else(Callback::noop)]
+    pub onchange: Callback<u64>,
+}
+
+impl YieldStyle for PaginationProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-pagination".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            display: flex;
+            flex-direction: row;
+            justify-content: center;
+            align-items: center;
+
+            cursor: default;
+            -moz-user-select: none;
+            -webkit-user-select: none;
+            -ms-user-select: none;
+            user-select: none;
+
+            & > * {
+                min-width: 32px;
+                height: 32px;
+                line-height: 32px;
+                margin-left: 2px;
+                margin-right: 2px;
+                text-align: center;
+                border-radius: 500px;
+                font-size: 0.9rem;
+                color: rgb(100, 100, 100);
+            }
+
+            .page {
+                transition: background-color 0.20s;
+            }
+
+            .page:hover {
+                background-color: rgba(150, 150, 150, 0.2);
+            }
+
+            .current, .current:hover {
+                color: white;
+                background-color: rgb(92, 184, 230);
+            }
+
+            .disabled, .disabled:hover {
+                color: rgb(200, 200, 200);
+                background-color: transparent;
+            }
+            "#
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PageItem {
+    Page(u64),
+    Ellipsis,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pagination {
+    props: PaginationProps,
+}
+
+impl Component for Pagination {
+    type Message = ();
+    type Properties = PaginationProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        if self.props.total_pages <= 1 {
+            return html! {};
+        }
+
+        let page = self.props.page;
+        let last = self.props.total_pages;
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                { self.render_link(html! { <i class="fas fa-chevron-left"></i> }, page.saturating_sub(1), page > 1) }
+                {
+                    for self.page_items().into_iter().map(|m| match m {
+                        PageItem::Page(m) if m == page => html! { <div class="page current">{ m }</div> },
+                        PageItem::Page(m) => self.render_link(html! { { m } }, m, true),
+                        PageItem::Ellipsis => html! { <div>{ "…" }</div> },
+                    })
+                }
+                { self.render_link(html! { <i class="fas fa-chevron-right"></i> }, page + 1, page < last) }
+            </div>
+        }
+    }
+}
+
+impl Pagination {
+    // The first, the last and 2 pages around the current one.
+    fn page_items(&self) -> Vec<PageItem> {
+        let page = self.props.page;
+        let last = self.props.total_pages;
+
+        let start = page.saturating_sub(2).max(1);
+        let end = (page + 2).min(last);
+
+        let mut items = Vec::new();
+
+        if start > 1 {
+            items.push(PageItem::Page(1));
+        }
+        if start > 2 {
+            items.push(PageItem::Ellipsis);
+        }
+
+        for m in start..=end {
+            items.push(PageItem::Page(m));
+        }
+
+        if end + 1 < last {
+            items.push(PageItem::Ellipsis);
+        }
+        if end < last {
+            items.push(PageItem::Page(last));
+        }
+
+        items
+    }
+
+    fn render_link(&self, content: Html, page: u64, enabled: bool) -> Html {
+        if !enabled {
+            return html! { <div class="disabled">{ content }</div> };
+        }
+
+        let onchange = self.props.onchange.clone();
+        html! {
+            <div class="page" onclick=Callback::from(move |_| onchange.emit(page))>{ content }</div>
+        }
+    }
+}
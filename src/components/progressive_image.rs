@@ -0,0 +1,205 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::blurhash;
+use crate::css::{self, css, Keyword, Stylesheet};
+use crate::traits::YieldStyle;
+
+// Placeholders are scaled up and blurry anyway.
+const PLACEHOLDER_SIZE: u32 = 32;
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct ProgressiveImageProps {
+    pub src: String,
+
+    // Other versions of the image for high density screens, such as (2.0, "logo@2x.png").
+    #[prop_or_default]
+    pub densities: Vec<(f64, String)>,
+
+    // Shown until the image is loaded.
+    #[prop_or_default]
+    pub blurhash: Option<String>,
+
+    #[prop_or_default]
+    pub alt: String,
+
+    // The object-fit of the image, the component fills its parent.
+    #[prop_or("cover")]
+    pub fit: &'static str,
+
+    // Shown if the image fails to load, the placeholder stays if not set.
+    #[prop_or_default]
+    pub fallback: Option<Html>,
+}
+
+impl YieldStyle for ProgressiveImageProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-progressive-image".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        let mut style = css!(
+            r#"
+            position: relative;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            width: 100%;
+            height: 100%;
+            overflow: hidden;
+
+            img {
+                position: absolute;
+                top: 0;
+                left: 0;
+                width: 100%;
+                height: 100%;
+            }
+
+            img.image {
+                opacity: 0;
+                transition: opacity 0.3s;
+            }
+
+            img.image.loaded {
+                opacity: 1;
+            }
+            "#
+        );
+
+        if let Some(m) = css::checked("object-fit", Keyword::parse(self.fit)) {
+            style += css!("img { object-fit: ${fit}; }", fit = m);
+        }
+
+        style
+    }
+}
+
+pub enum ProgressiveImageMsg {
+    Loaded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LoadStatus {
+    Loading,
+    Loaded,
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct ProgressiveImage {
+    props: ProgressiveImageProps,
+    link: ComponentLink<Self>,
+
+    status: LoadStatus,
+    // Data URL of the decoded BlurHash.
+    placeholder: Option<String>,
+}
+
+impl Component for ProgressiveImage {
+    type Message = ProgressiveImageMsg;
+    type Properties = ProgressiveImageProps;
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let placeholder = Self::decode_placeholder(&props);
+
+        Self {
+            props,
+            link,
+
+            status: LoadStatus::Loading,
+            placeholder,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let status = match msg {
+            ProgressiveImageMsg::Loaded => LoadStatus::Loaded,
+            ProgressiveImageMsg::Failed => LoadStatus::Failed,
+        };
+
+        if self.status != status {
+            self.status = status;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            if props.src != self.props.src || props.densities != self.props.densities {
+                self.status = LoadStatus::Loading;
+            }
+            if props.blurhash != self.props.blurhash {
+                self.placeholder = Self::decode_placeholder(&props);
+            }
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        if self.status == LoadStatus::Failed {
+            if let Some(ref m) = self.props.fallback {
+                return html! {
+                    <div class=self.props.yield_style_class()>{ m.clone() }</div>
+                };
+            }
+        }
+
+        // Stays under the image, which fades in over it.
+        let placeholder = match self.placeholder {
+            Some(ref m) => html! {
+                <img class="placeholder" src=m.clone() alt="" aria-hidden="true" />
+            },
+            _ => html! {},
+        };
+
+        let image = if self.status == LoadStatus::Failed {
+            html! {}
+        } else {
+            html! {
+                <img class=if self.status == LoadStatus::Loaded { "image loaded" } else { "image" }
+                    src=self.props.src.clone() srcset=self.srcset() alt=self.props.alt.clone()
+                    onload=self.link.callback(|_| ProgressiveImageMsg::Loaded)
+                    onerror=self.link.callback(|_| ProgressiveImageMsg::Failed) />
+            }
+        };
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                { placeholder }
+                { image }
+            </div>
+        }
+    }
+}
+
+impl ProgressiveImage {
+    fn decode_placeholder(props: &ProgressiveImageProps) -> Option<String> {
+        let hash = props.blurhash.as_ref()?;
+        let placeholder = blurhash::to_data_url(hash, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE);
+
+        if placeholder.is_none() {
+            log::warn!("Ignoring invalid BlurHash: {}", hash);
+        }
+        placeholder
+    }
+
+    // The browser picks the version for the density of the screen.
+    fn srcset(&self) -> String {
+        let mut srcset = vec![format!("{} 1x", self.props.src)];
+        srcset.extend(
+            self.props
+                .densities
+                .iter()
+                .map(|(density, src)| format!("{} {}x", src, density)),
+        );
+
+        srcset.join(", ")
+    }
+}
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use yew::prelude::*;
 
+use crate::css::{css, Stylesheet};
 use crate::traits::YieldStyle;
 
 #[derive(Debug, Clone)]
@@ -38,58 +39,59 @@ impl YieldStyle for Spinner {
         "fl-spinner".into()
     }
 
-    fn yield_style_str(&self) -> Cow<'static, str> {
-        r#"
-            display: inline-flex;
-            align-items: center;
-            flex-direction: row;
-            justify-content: space-evenly;
-
-            padding-left: 20px;
-            padding-right: 20px;
-            height: 40px;
-            width: 120px;
-            box-sizing: border-box;
-
-            text-align: center;
-
-            .bounce {
-                width: 0.7rem;
-                height: 0.7rem;
-                background-color: rgb(100, 100, 100);
-                transition: background-color 0.20s;
-
-                border-radius: 100%;
-                display: inline-block;
-                -webkit-animation: sk-bouncedelay 1.4s infinite ease-in-out both;
-                animation: sk-bouncedelay 1.4s infinite ease-in-out both;
-            }
-
-            .bounce1 {
-                -webkit-animation-delay: -0.32s;
-                animation-delay: -0.32s;
-            }
-
-            .bounce2 {
-                -webkit-animation-delay: -0.16s;
-                animation-delay: -0.16s;
-            }
-
-            @-webkit-keyframes sk-bouncedelay {
-                0%, 80%, 100% { -webkit-transform: scale(0) }
-                40% { -webkit-transform: scale(1.0) }
-            }
-
-            @keyframes sk-bouncedelay {
-                0%, 80%, 100% {
-                    -webkit-transform: scale(0);
-                    transform: scale(0);
-                } 40% {
-                    -webkit-transform: scale(1.0);
-                    transform: scale(1.0);
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+                display: inline-flex;
+                align-items: center;
+                flex-direction: row;
+                justify-content: space-evenly;
+
+                padding-left: 20px;
+                padding-right: 20px;
+                height: 40px;
+                width: 120px;
+                box-sizing: border-box;
+
+                text-align: center;
+
+                .bounce {
+                    width: 0.7rem;
+                    height: 0.7rem;
+                    background-color: rgb(100, 100, 100);
+                    transition: background-color 0.20s;
+
+                    border-radius: 100%;
+                    display: inline-block;
+                    -webkit-animation: sk-bouncedelay 1.4s infinite ease-in-out both;
+                    animation: sk-bouncedelay 1.4s infinite ease-in-out both;
                 }
-            }
-            "#
-        .into()
+
+                .bounce1 {
+                    -webkit-animation-delay: -0.32s;
+                    animation-delay: -0.32s;
+                }
+
+                .bounce2 {
+                    -webkit-animation-delay: -0.16s;
+                    animation-delay: -0.16s;
+                }
+
+                @-webkit-keyframes sk-bouncedelay {
+                    0%, 80%, 100% { -webkit-transform: scale(0) }
+                    40% { -webkit-transform: scale(1.0) }
+                }
+
+                @keyframes sk-bouncedelay {
+                    0%, 80%, 100% {
+                        -webkit-transform: scale(0);
+                        transform: scale(0);
+                    } 40% {
+                        -webkit-transform: scale(1.0);
+                        transform: scale(1.0);
+                    }
+                }
+                "#
+        )
     }
 }
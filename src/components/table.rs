@@ -0,0 +1,234 @@
+use std::borrow::Cow;
+
+use yew::prelude::*;
+
+use crate::css::{self, css, Length, Stylesheet};
+use crate::traits::YieldStyle;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Ascending,
+        }
+    }
+
+    fn icon_class(self) -> &'static str {
+        match self {
+            Self::Ascending => "fas fa-sort-up",
+            Self::Descending => "fas fa-sort-down",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub sortable: bool,
+    pub width: Option<&'static str>,
+}
+
+impl TableColumn {
+    pub fn new(key: &'static str, label: &'static str) -> Self {
+        Self {
+            key,
+            label,
+            sortable: false,
+            width: None,
+        }
+    }
+
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+
+    pub fn width(mut self, width: &'static str) -> Self {
+        self.width = Some(width);
+        self
+    }
+}
+
+#[derive(Properties, Clone, Debug, PartialEq)]
+pub struct TableProps {
+    pub columns: Vec<TableColumn>,
+
+    // Each row must have one cell per column.
+    #[prop_or_default]
+    pub rows: Vec<Vec<Html>>,
+
+    #[prop_or(None)]
+    pub sort: Option<(&'static str, SortOrder)>,
+
+    // Emits the key of the column that has been clicked.
+    #[prop_or_else(Callback::noop)]
+    pub onsort: Callback<&'static str>,
+
+    #[prop_or("沒有項目".to_string())]
+    pub empty_text: String,
+}
+
+impl YieldStyle for TableProps {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-table".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            width: 100%;
+            overflow-x: auto;
+
+            table {
+                width: 100%;
+                border-collapse: collapse;
+            }
+
+            th {
+                height: 40px;
+                padding-left: 10px;
+                padding-right: 10px;
+                text-align: left;
+                font-weight: normal;
+                font-size: 0.9rem;
+                color: rgb(100, 100, 100);
+                border-bottom: 1px solid rgba(150, 150, 150, 0.5);
+                white-space: nowrap;
+
+                cursor: default;
+                -moz-user-select: none;
+                -webkit-user-select: none;
+                -ms-user-select: none;
+                user-select: none;
+            }
+
+            th.sortable:hover {
+                color: black;
+            }
+
+            th i {
+                margin-left: 5px;
+            }
+
+            td {
+                height: 50px;
+                padding-left: 10px;
+                padding-right: 10px;
+                border-bottom: 1px solid rgba(150, 150, 150, 0.2);
+            }
+
+            tr:last-child td {
+                border-bottom: 0;
+            }
+
+            .empty {
+                text-align: center;
+                color: rgb(150, 150, 150);
+            }
+            "#
+        )
+    }
+}
+
+// Widths are classes of their own rather than inline styles, which a strict Content-Security-Policy
+// blocks. Columns of the same width share one.
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnWidth(Length);
+
+impl YieldStyle for ColumnWidth {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-table-column".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!("width: ${width};", width = self.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    props: TableProps,
+}
+
+impl Component for Table {
+    type Message = ();
+    type Properties = TableProps;
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props != self.props {
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let rows = if self.props.rows.is_empty() {
+            html! {
+                <tr>
+                    <td class="empty" colspan=self.props.columns.len().to_string()>{ self.props.empty_text.clone() }</td>
+                </tr>
+            }
+        } else {
+            html! {
+                { for self.props.rows.iter().map(|row| html! {
+                    <tr>{ for row.iter().map(|cell| html! { <td>{ cell.clone() }</td> }) }</tr>
+                }) }
+            }
+        };
+
+        html! {
+            <div class=self.props.yield_style_class()>
+                <table>
+                    <thead>
+                        <tr>{ for self.props.columns.iter().map(|m| self.render_header(m)) }</tr>
+                    </thead>
+                    <tbody>{ rows }</tbody>
+                </table>
+            </div>
+        }
+    }
+}
+
+impl Table {
+    fn render_header(&self, column: &TableColumn) -> Html {
+        let width = column
+            .width
+            .and_then(|m| css::checked("width", Length::parse(m)))
+            .map(|m| ColumnWidth(m).yield_style_class())
+            .unwrap_or_default();
+
+        if !column.sortable {
+            return html! { <th class=width>{ column.label }</th> };
+        }
+
+        let icon = match self.props.sort {
+            Some((key, order)) if key == column.key => order.icon_class(),
+            _ => "fas fa-sort",
+        };
+
+        let key = column.key;
+        let onsort = self.props.onsort.clone();
+
+        html! {
+            <th class=format!("sortable {}", width) onclick=Callback::from(move |_| onsort.emit(key))>
+                { column.label }<i class=icon></i>
+            </th>
+        }
+    }
+}
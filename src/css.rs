@@ -0,0 +1,347 @@
+use std::fmt;
+use std::ops::AddAssign;
+
+use sha2::{Digest, Sha256};
+
+pub use furtherland_office_macros::css;
+
+// Values interpolated into the styles of YieldStyle implementations.
+//
+// Styles are added to a stylesheet shared by the whole page, so a prop such as
+// `0; } body { display: none` must never reach it as is. Values are either built from numbers or
+// checked against what the property accepts, unsafe ones are left out with a warning.
+
+const MAX_LENGTH: usize = 200;
+
+// Functions allowed in lengths, anything else such as url() is rejected.
+const LENGTH_FUNCTIONS: &[&str] = &["calc", "min", "max", "clamp", "var", "env"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsafeValue(String);
+
+impl fmt::Display for UnsafeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsafe CSS value {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnsafeValue {}
+
+fn unsafe_value(value: &str) -> UnsafeValue {
+    UnsafeValue(value.chars().take(MAX_LENGTH).collect())
+}
+
+// A length or percentage, such as `100%`, `40px` or `calc(100vw - 40px)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Length(String);
+
+impl Length {
+    pub fn parse(value: &str) -> Result<Self, UnsafeValue> {
+        let value = value.trim();
+        if value.is_empty() || value.len() > MAX_LENGTH || value.contains("/*") {
+            return Err(unsafe_value(value));
+        }
+
+        let mut depth = 0usize;
+        let mut ident = String::new();
+
+        for c in value.chars() {
+            match c {
+                'a'..='z' | 'A'..='Z' => {
+                    ident.push(c);
+                    continue;
+                }
+                '(' => {
+                    if !LENGTH_FUNCTIONS.contains(&ident.to_lowercase().as_str()) {
+                        return Err(unsafe_value(value));
+                    }
+                    depth += 1;
+                }
+                ')' => {
+                    depth = depth.checked_sub(1).ok_or_else(|| unsafe_value(value))?;
+                }
+                '0'..='9' | ' ' | '.' | '%' | '+' | '-' | '*' | '/' | ',' => {}
+                _ => return Err(unsafe_value(value)),
+            }
+            ident.clear();
+        }
+
+        if depth != 0 {
+            return Err(unsafe_value(value));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// A keyword value, such as `flex-start` or `contain`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword(String);
+
+impl Keyword {
+    pub fn parse(value: &str) -> Result<Self, UnsafeValue> {
+        let value = value.trim();
+        let valid = value.len() <= MAX_LENGTH
+            && value.starts_with(|c: char| c.is_ascii_alphabetic())
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+        if valid {
+            Ok(Self(value.to_string()))
+        } else {
+            Err(unsafe_value(value))
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Colours are built from numbers, so there is nothing to check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Colour {
+    Rgb(u8, u8, u8),
+    // Hue in degrees, saturation and lightness in percent.
+    Hsl(u16, u8, u8),
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Rgb(r, g, b) => write!(f, "rgb({}, {}, {})", r, g, b),
+            Self::Hsl(h, s, l) => write!(f, "hsl({}, {}%, {}%)", h % 360, s.min(100), l.min(100)),
+        }
+    }
+}
+
+// The value, or None with a warning if it is unsafe.
+pub fn checked<T>(property: &str, value: Result<T, UnsafeValue>) -> Option<T> {
+    match value {
+        Ok(m) => Some(m),
+        Err(e) => {
+            log::warn!("Ignoring {}: {}", property, e);
+            None
+        }
+    }
+}
+
+// Values that can be interpolated by css!, strings cannot as nothing checks them.
+pub trait CssValue {
+    fn to_css_value(&self) -> String;
+}
+
+impl CssValue for Length {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CssValue for Keyword {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CssValue for Colour {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CssValue for u32 {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CssValue for i32 {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl CssValue for f64 {
+    fn to_css_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// Used by the code generated by css!.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StylesheetPart {
+    Text(&'static str),
+    Scope,
+    Value(String),
+}
+
+// A stylesheet compiled by css!, which is scoped to an element once it has a class name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Stylesheet {
+    text: String,
+    // Where the selector of the element goes in the text.
+    scopes: Vec<usize>,
+}
+
+impl Stylesheet {
+    #[doc(hidden)]
+    pub fn from_parts(parts: Vec<StylesheetPart>) -> Self {
+        let mut stylesheet = Self::default();
+
+        for part in parts {
+            match part {
+                StylesheetPart::Text(m) => stylesheet.text.push_str(m),
+                StylesheetPart::Scope => stylesheet.scopes.push(stylesheet.text.len()),
+                StylesheetPart::Value(m) => stylesheet.text.push_str(&m),
+            }
+        }
+
+        stylesheet
+    }
+
+    // Rules of the other stylesheet come later, so they win over the same ones in this one.
+    pub fn push(&mut self, other: &Stylesheet) {
+        let offset = self.text.len();
+
+        self.text.push_str(&other.text);
+        self.scopes.extend(other.scopes.iter().map(|m| m + offset));
+    }
+
+    pub fn to_css(&self, class_name: &str) -> String {
+        let selector = format!(".{}", class_name);
+        let mut css = String::with_capacity(self.text.len() + self.scopes.len() * selector.len());
+        let mut last = 0;
+
+        for m in self.scopes.iter() {
+            css.push_str(&self.text[last..*m]);
+            css.push_str(&selector);
+            last = *m;
+        }
+        css.push_str(&self.text[last..]);
+
+        css
+    }
+
+    // The same on every target, unlike Hash, so classes prerendered natively match the ones of the
+    // browser.
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.text.as_bytes());
+        for m in self.scopes.iter() {
+            hasher.update((*m as u32).to_le_bytes());
+        }
+
+        hasher.finalize()[..6]
+            .iter()
+            .map(|m| format!("{:02x}", m))
+            .collect()
+    }
+}
+
+impl AddAssign for Stylesheet {
+    fn add_assign(&mut self, other: Stylesheet) {
+        self.push(&other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        for m in &[
+            "100%",
+            "40px",
+            "0",
+            "-1.5rem",
+            "calc(100vw - 40px)",
+            "min(90%, calc(1200px / 2))",
+            "var(--width)",
+        ] {
+            assert_eq!(Length::parse(m).unwrap().to_string(), *m);
+        }
+    }
+
+    #[test]
+    fn test_length_hostile() {
+        for m in &[
+            "",
+            "100px; } body { display: none",
+            "100px;color:red",
+            "1px) } * { color: red",
+            "calc(1px",
+            "1px)",
+            "url(https://example.com/track)",
+            "expression(alert(1))",
+            "1px /* ",
+            "100px\"",
+            "100px\\3b",
+            "100px\n}",
+            "100px<style>",
+        ] {
+            assert!(Length::parse(m).is_err(), "{:?} should be rejected", m);
+        }
+
+        assert!(Length::parse(&"1".repeat(MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_keyword() {
+        assert_eq!(
+            Keyword::parse("flex-start").unwrap().to_string(),
+            "flex-start"
+        );
+        assert!(Keyword::parse("center; } body { display: none").is_err());
+        assert!(Keyword::parse("-moz-center").is_err());
+        assert!(Keyword::parse("").is_err());
+    }
+
+    #[test]
+    fn test_colour() {
+        assert_eq!(Colour::Rgb(92, 184, 230).to_string(), "rgb(92, 184, 230)");
+        assert_eq!(Colour::Hsl(400, 45, 255).to_string(), "hsl(40, 45%, 100%)");
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(
+            checked("width", Length::parse("100%")),
+            Some(Length("100%".to_string()))
+        );
+        assert_eq!(checked("width", Length::parse("1px; color: red")), None);
+    }
+
+    #[test]
+    fn test_stylesheet() {
+        let mut stylesheet = css!(
+            "color: ${colour}; img { width: 100%; }",
+            colour = Colour::Rgb(0, 0, 0)
+        );
+        stylesheet += css!("&:hover { opacity: ${opacity}; }", opacity = 0.5);
+
+        assert_eq!(
+            stylesheet.to_css("fl-x-1"),
+            ".fl-x-1 { color: rgb(0, 0, 0); }\n\
+             .fl-x-1 img { width: 100%; }\n\
+             .fl-x-1:hover { opacity: 0.5; }\n"
+        );
+    }
+
+    #[test]
+    fn test_digest() {
+        let digest = css!("color: red;").digest();
+
+        assert_eq!(digest.len(), 12);
+        assert_eq!(digest, css!("color: red;").digest());
+        assert_ne!(digest, css!("color: blue;").digest());
+    }
+}
@@ -0,0 +1,253 @@
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DiffSpan {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DiffLine {
+    pub kind: DiffKind,
+    pub spans: Vec<DiffSpan>,
+}
+
+impl DiffLine {
+    fn whole(kind: DiffKind, text: &str) -> Self {
+        Self {
+            kind,
+            spans: vec![DiffSpan {
+                kind,
+                text: text.to_string(),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+    Word,
+    Space,
+    // Characters that are not separated by spaces, each one is a word on its own.
+    Ideograph,
+    Other,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        match c {
+            '\u{3000}'..='\u{303f}'
+            | '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{f900}'..='\u{faff}'
+            // Halfwidth katakana, fullwidth letters and digits are words.
+            | '\u{ff66}'..='\u{ff9f}'
+            | '\u{20000}'..='\u{2fa1f}' => Self::Ideograph,
+            m if m.is_whitespace() => Self::Space,
+            m if m.is_alphanumeric() || m == '_' => Self::Word,
+            _ => Self::Other,
+        }
+    }
+}
+
+// Splits text into words, Chinese and Japanese are split into characters.
+pub(crate) fn segment_words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut last_class = None;
+
+    for (i, c) in s.char_indices() {
+        let class = CharClass::of(c);
+
+        let joins = matches!(
+            (last_class, class),
+            (Some(CharClass::Word), CharClass::Word) | (Some(CharClass::Space), CharClass::Space)
+        );
+
+        if !joins && i > start {
+            words.push(&s[start..i]);
+            start = i;
+        }
+        last_class = Some(class);
+    }
+
+    if start < s.len() {
+        words.push(&s[start..]);
+    }
+
+    words
+}
+
+fn diff_words(old: &str, new: &str) -> (DiffLine, DiffLine) {
+    let old_words = segment_words(old);
+    let new_words = segment_words(new);
+
+    let mut old_spans: Vec<DiffSpan> = Vec::new();
+    let mut new_spans: Vec<DiffSpan> = Vec::new();
+
+    // Merges adjacent words of the same kind.
+    fn push(spans: &mut Vec<DiffSpan>, kind: DiffKind, words: &[&str]) {
+        if words.is_empty() {
+            return;
+        }
+
+        match spans.last_mut() {
+            Some(m) if m.kind == kind => m.text.push_str(&words.concat()),
+            _ => spans.push(DiffSpan {
+                kind,
+                text: words.concat(),
+            }),
+        }
+    }
+
+    for op in capture_diff_slices(Algorithm::Myers, &old_words, &new_words) {
+        let (_, old_range, new_range) = op.as_tag_tuple();
+        let old_part = &old_words[old_range];
+        let new_part = &new_words[new_range];
+
+        match op {
+            DiffOp::Equal { .. } => {
+                push(&mut old_spans, DiffKind::Equal, old_part);
+                push(&mut new_spans, DiffKind::Equal, new_part);
+            }
+            _ => {
+                push(&mut old_spans, DiffKind::Delete, old_part);
+                push(&mut new_spans, DiffKind::Insert, new_part);
+            }
+        }
+    }
+
+    (
+        DiffLine {
+            kind: DiffKind::Delete,
+            spans: old_spans,
+        },
+        DiffLine {
+            kind: DiffKind::Insert,
+            spans: new_spans,
+        },
+    )
+}
+
+// Compares line by line, lines that have been modified are also compared word by word.
+pub(crate) fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+
+    let mut lines = Vec::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines) {
+        match op {
+            DiffOp::Equal { old_index, len, .. } => {
+                for m in &old_lines[old_index..old_index + len] {
+                    lines.push(DiffLine::whole(DiffKind::Equal, m));
+                }
+            }
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => {
+                for m in &old_lines[old_index..old_index + old_len] {
+                    lines.push(DiffLine::whole(DiffKind::Delete, m));
+                }
+            }
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => {
+                for m in &new_lines[new_index..new_index + new_len] {
+                    lines.push(DiffLine::whole(DiffKind::Insert, m));
+                }
+            }
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                let old_part = &old_lines[old_index..old_index + old_len];
+                let new_part = &new_lines[new_index..new_index + new_len];
+                let paired = old_len.min(new_len);
+
+                let mut deleted = Vec::new();
+                let mut inserted = Vec::new();
+
+                for (old_line, new_line) in old_part.iter().zip(new_part.iter()) {
+                    let (old_line, new_line) = diff_words(old_line, new_line);
+                    deleted.push(old_line);
+                    inserted.push(new_line);
+                }
+
+                for m in &old_part[paired..] {
+                    deleted.push(DiffLine::whole(DiffKind::Delete, m));
+                }
+                for m in &new_part[paired..] {
+                    inserted.push(DiffLine::whole(DiffKind::Insert, m));
+                }
+
+                lines.extend(deleted);
+                lines.extend(inserted);
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_words() {
+        assert_eq!(
+            segment_words("hello  world_2"),
+            vec!["hello", "  ", "world_2"]
+        );
+        assert_eq!(segment_words(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_segment_ideographs() {
+        assert_eq!(segment_words("未來領域"), vec!["未", "來", "領", "域"]);
+        assert_eq!(
+            segment_words("ひらがなカタカナ"),
+            vec!["ひ", "ら", "が", "な", "カ", "タ", "カ", "ナ"]
+        );
+        assert_eq!(segment_words("ｶﾀｶﾅ"), vec!["ｶ", "ﾀ", "ｶ", "ﾅ"]);
+    }
+
+    #[test]
+    fn test_segment_mixed() {
+        assert_eq!(
+            segment_words("使用Rust編寫 office"),
+            vec!["使", "用", "Rust", "編", "寫", " ", "office"]
+        );
+        assert_eq!(segment_words("第2020年"), vec!["第", "2020", "年"]);
+    }
+
+    #[test]
+    fn test_segment_punctuation() {
+        assert_eq!(
+            segment_words("Hello, world!"),
+            vec!["Hello", ",", " ", "world", "!"]
+        );
+        assert_eq!(
+            segment_words("你好，世界。"),
+            vec!["你", "好", "，", "世", "界", "。"]
+        );
+        assert_eq!(segment_words("a--b"), vec!["a", "-", "-", "b"]);
+    }
+
+    #[test]
+    fn test_segment_fullwidth_alphanumerics() {
+        assert_eq!(segment_words("ＡＢＣ１２３"), vec!["ＡＢＣ１２３"]);
+        assert_eq!(segment_words("ＡＢ！"), vec!["ＡＢ", "！"]);
+    }
+}
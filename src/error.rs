@@ -2,6 +2,8 @@ use wasm_bindgen::JsValue;
 
 use thiserror::Error as ThisError;
 
+use crate::backend::SigninThrottle;
+
 use std::result::Result as StdResult;
 
 #[derive(Debug, ThisError)]
@@ -15,8 +17,29 @@ pub enum Error {
     #[error("Failed to parse Url.")]
     Url(#[from] url::ParseError),
 
+    #[error("{0:?} is not a valid path segment.")]
+    InvalidSegment(String),
+
     #[error("The manifest is not valid.")]
     InvalidManifest(#[from] toml::de::Error),
+
+    #[error("Too many attempts.")]
+    Throttled(SigninThrottle),
+
+    #[error("You are not signed in.")]
+    Unauthorized,
+
+    #[error("Server responded with status {0}.")]
+    Status(u16),
+
+    #[error("Failed to parse response.")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to process image: {0}")]
+    Image(String),
+
+    #[error("The upload did not advance past byte {0}.")]
+    UploadStalled(u64),
 }
 
 impl From<JsValue> for Error {
@@ -31,7 +54,16 @@ impl From<Error> for JsValue {
             Error::Js(val) => val,
             Error::Networking(e) => e.to_string().into(),
             Error::Url(e) => e.to_string().into(),
+            Error::InvalidSegment(m) => format!("{:?} is not a valid path segment.", m).into(),
             Error::InvalidManifest(e) => e.to_string().into(),
+            Error::Throttled(_) => "Too many attempts.".into(),
+            Error::Unauthorized => "You are not signed in.".into(),
+            Error::Status(m) => format!("Server responded with status {}.", m).into(),
+            Error::Json(e) => e.to_string().into(),
+            Error::Image(m) => format!("Failed to process image: {}", m).into(),
+            Error::UploadStalled(m) => {
+                format!("The upload did not advance past byte {}.", m).into()
+            }
         }
     }
 }
@@ -5,8 +5,8 @@ use std::rc::Rc;
 use std::time::Duration;
 
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::JsCast;
-use web_sys::{Document, Window};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Document, Event, EventTarget, Window};
 use yew::html::{Component, ComponentLink};
 
 use once_cell::sync::Lazy;
@@ -53,6 +53,38 @@ pub(crate) fn is_mobile_safari() -> bool {
     is_ios && is_webkit && !is_crios
 }
 
+// Current unix timestamp in seconds.
+pub(crate) fn now_timestamp() -> i64 {
+    (js_sys::Date::now() / 1000.0) as i64
+}
+
+pub(crate) fn format_date(timestamp: i64) -> String {
+    let date = js_sys::Date::new(&((timestamp * 1000) as f64).into());
+    date.to_locale_date_string("zh-Hant", &JsValue::UNDEFINED)
+        .into()
+}
+
+pub(crate) fn format_date_time(timestamp: i64) -> String {
+    let date = js_sys::Date::new(&((timestamp * 1000) as f64).into());
+    date.to_locale_string("zh-Hant", &JsValue::UNDEFINED).into()
+}
+
+pub(crate) fn format_relative_time(timestamp: i64) -> String {
+    let secs = now_timestamp() - timestamp;
+
+    if secs < 60 {
+        "剛剛".to_string()
+    } else if secs < 60 * 60 {
+        format!("{} 分鐘前", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{} 小時前", secs / (60 * 60))
+    } else if secs < 30 * 24 * 60 * 60 {
+        format!("{} 天前", secs / (24 * 60 * 60))
+    } else {
+        format_date(timestamp)
+    }
+}
+
 pub(crate) async fn sleep(dur: Duration) {
     let (s, r) = async_channel::unbounded::<()>();
 
@@ -80,6 +112,43 @@ where
         .unwrap();
 }
 
+// An event listener that is removed when dropped.
+#[derive(Debug)]
+pub(crate) struct EventListener {
+    target: EventTarget,
+    event_name: &'static str,
+    callback: Closure<dyn FnMut(Event)>,
+}
+
+impl EventListener {
+    pub fn new<F>(target: &EventTarget, event_name: &'static str, f: F) -> Self
+    where
+        F: 'static + FnMut(Event),
+    {
+        let callback = Closure::wrap(Box::new(f) as Box<dyn FnMut(Event)>);
+        target
+            .add_event_listener_with_callback(event_name, callback.as_ref().unchecked_ref())
+            .unwrap();
+
+        Self {
+            target: target.clone(),
+            event_name,
+            callback,
+        }
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        self.target
+            .remove_event_listener_with_callback(
+                self.event_name,
+                self.callback.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
+}
+
 #[derive(Debug)]
 pub struct WeakComponentLink<COMP: Component>(Rc<RefCell<Option<ComponentLink<COMP>>>>);
 
@@ -0,0 +1,336 @@
+use std::io::Cursor;
+
+use exif::{In, Reader as ExifReader, Tag};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageOutputFormat, ImageResult};
+use serde::{Deserialize, Serialize};
+
+// How images are processed before uploading, set in the [image] table of the manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ImageOptions {
+    // Larger images are scaled down to fit, the aspect ratio is kept.
+    pub max_width: u32,
+    pub max_height: u32,
+    // JPEG quality between 1 and 100.
+    pub quality: u8,
+    // The longer side of thumbnails.
+    pub thumbnail_size: u32,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            max_width: 2560,
+            max_height: 2560,
+            quality: 85,
+            thumbnail_size: 400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OutputFormat {
+    Jpeg,
+    Png,
+}
+
+impl OutputFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Png => "image/png",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ProcessedImage {
+    pub format: OutputFormat,
+    pub data: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+// GIFs are left untouched as they may be animated.
+pub(crate) fn is_processable(content_type: &str) -> bool {
+    matches!(content_type, "image/jpeg" | "image/png" | "image/webp")
+}
+
+// EXIF orientation of the image, 1 if not present.
+fn orientation(data: &[u8]) -> u32 {
+    ExifReader::new()
+        .read_from_container(&mut Cursor::new(data))
+        .ok()
+        .and_then(|m| {
+            m.get_field(Tag::Orientation, In::PRIMARY)
+                .and_then(|m| m.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+// The orientation is lost with the rest of the metadata, so it is applied to the pixels.
+fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn fit_within(image: DynamicImage, max_width: u32, max_height: u32) -> DynamicImage {
+    if image.width() <= max_width && image.height() <= max_height {
+        return image;
+    }
+
+    image.resize(max_width.max(1), max_height.max(1), FilterType::CatmullRom)
+}
+
+// Nothing but pixels are written, which strips EXIF (and GPS) metadata.
+fn encode(image: &DynamicImage, format: OutputFormat, quality: u8) -> ImageResult<Vec<u8>> {
+    let mut buf = Cursor::new(Vec::new());
+
+    match format {
+        OutputFormat::Png => image.write_to(&mut buf, ImageOutputFormat::Png)?,
+        // JPEG has no alpha channel.
+        OutputFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_to(&mut buf, ImageOutputFormat::Jpeg(quality.clamp(1, 100)))?,
+    }
+
+    Ok(buf.into_inner())
+}
+
+// Decodes a JPEG, PNG or WebP image, scales it down and encodes it again with a thumbnail.
+pub(crate) fn process_image(data: &[u8], options: &ImageOptions) -> ImageResult<ProcessedImage> {
+    let input_format = image::guess_format(data)?;
+    let image = image::load_from_memory_with_format(data, input_format)?;
+    let image = apply_orientation(image, orientation(data));
+    let image = fit_within(image, options.max_width, options.max_height);
+
+    // PNGs are usually screenshots or drawings, which do not compress well as JPEG.
+    let format = if input_format == ImageFormat::Png || image.color().has_alpha() {
+        OutputFormat::Png
+    } else {
+        OutputFormat::Jpeg
+    };
+
+    let thumbnail = fit_within(
+        image.clone(),
+        options.thumbnail_size,
+        options.thumbnail_size,
+    );
+
+    Ok(ProcessedImage {
+        format,
+        data: encode(&image, format, options.quality)?,
+        thumbnail: encode(&thumbnail, format, options.quality)?,
+    })
+}
+
+// Crops the square at (x, y) of the oriented image and scales it to output_size.
+//
+// The square is moved and shrunk to fit if it goes beyond the image.
+pub(crate) fn crop_square(
+    data: &[u8],
+    x: u32,
+    y: u32,
+    size: u32,
+    output_size: u32,
+    quality: u8,
+) -> ImageResult<(OutputFormat, Vec<u8>)> {
+    let input_format = image::guess_format(data)?;
+    let image = image::load_from_memory_with_format(data, input_format)?;
+    let image = apply_orientation(image, orientation(data));
+
+    let size = size.min(image.width()).min(image.height()).max(1);
+    let x = x.min(image.width() - size);
+    let y = y.min(image.height() - size);
+
+    let image = image.crop_imm(x, y, size, size).resize_exact(
+        output_size,
+        output_size,
+        FilterType::CatmullRom,
+    );
+
+    let format = if image.color().has_alpha() {
+        OutputFormat::Png
+    } else {
+        OutputFormat::Jpeg
+    };
+
+    Ok((format, encode(&image, format, quality)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    use super::*;
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 0, 0])));
+        encode(&image, OutputFormat::Jpeg, 90).unwrap()
+    }
+
+    fn png(image: DynamicImage) -> Vec<u8> {
+        encode(&image, OutputFormat::Png, 90).unwrap()
+    }
+
+    // Inserts an APP1 segment with the orientation and a GPS latitude after SOI.
+    fn with_exif(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+        let mut tiff = Vec::new();
+        // Big endian, IFD0 at 8.
+        tiff.extend_from_slice(b"MM\x00\x2a\x00\x00\x00\x08");
+        // IFD0 with Orientation and a pointer to the GPS IFD at 38.
+        tiff.extend_from_slice(&[0x00, 0x02]);
+        tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01]);
+        tiff.extend_from_slice(&orientation.to_be_bytes());
+        tiff.extend_from_slice(&[0x00, 0x00]);
+        tiff.extend_from_slice(&[0x88, 0x25, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01]);
+        tiff.extend_from_slice(&38u32.to_be_bytes());
+        tiff.extend_from_slice(&[0x00; 4]);
+        // GPS IFD with GPSLatitudeRef.
+        tiff.extend_from_slice(&[0x00, 0x01]);
+        tiff.extend_from_slice(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02]);
+        tiff.extend_from_slice(b"N\x00\x00\x00");
+        tiff.extend_from_slice(&[0x00; 4]);
+
+        let mut app1 = vec![0xff, 0xe1];
+        app1.extend_from_slice(&((2 + 6 + tiff.len()) as u16).to_be_bytes());
+        app1.extend_from_slice(b"Exif\x00\x00");
+        app1.extend_from_slice(&tiff);
+
+        let mut data = jpeg[..2].to_vec();
+        data.extend_from_slice(&app1);
+        data.extend_from_slice(&jpeg[2..]);
+        data
+    }
+
+    // Markers of the segments before the image data.
+    fn jpeg_markers(data: &[u8]) -> Vec<u8> {
+        let mut markers = Vec::new();
+        let mut i = 2;
+        while i + 4 <= data.len() && data[i] == 0xff {
+            let marker = data[i + 1];
+            markers.push(marker);
+            // Start of scan.
+            if marker == 0xda {
+                break;
+            }
+            i += 2 + u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        }
+        markers
+    }
+
+    fn decode(data: &[u8]) -> DynamicImage {
+        image::load_from_memory(data).unwrap()
+    }
+
+    #[test]
+    fn test_strips_exif() {
+        let data = with_exif(&jpeg(16, 8), 1);
+        assert!(jpeg_markers(&data).contains(&0xe1));
+
+        let processed = process_image(&data, &ImageOptions::default()).unwrap();
+
+        assert_eq!(processed.format, OutputFormat::Jpeg);
+        for m in &[&processed.data, &processed.thumbnail] {
+            assert!(!jpeg_markers(m).contains(&0xe1));
+            assert!(!m.windows(6).any(|m| m == b"Exif\x00\x00"));
+        }
+    }
+
+    #[test]
+    fn test_applies_orientation() {
+        let data = with_exif(&jpeg(16, 8), 6);
+        assert_eq!(orientation(&data), 6);
+
+        let processed = process_image(&data, &ImageOptions::default()).unwrap();
+        let image = decode(&processed.data);
+
+        assert_eq!((image.width(), image.height()), (8, 16));
+    }
+
+    #[test]
+    fn test_fits_within_max_size() {
+        let options = ImageOptions {
+            max_width: 100,
+            max_height: 100,
+            thumbnail_size: 20,
+            ..ImageOptions::default()
+        };
+
+        let processed = process_image(&jpeg(400, 200), &options).unwrap();
+
+        let image = decode(&processed.data);
+        assert_eq!((image.width(), image.height()), (100, 50));
+        let thumbnail = decode(&processed.thumbnail);
+        assert_eq!((thumbnail.width(), thumbnail.height()), (20, 10));
+    }
+
+    #[test]
+    fn test_keeps_small_images() {
+        let processed = process_image(&jpeg(40, 30), &ImageOptions::default()).unwrap();
+        let image = decode(&processed.data);
+
+        assert_eq!((image.width(), image.height()), (40, 30));
+    }
+
+    #[test]
+    fn test_output_format() {
+        let rgb = DynamicImage::ImageRgb8(RgbImage::new(8, 8));
+        let rgba = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 128])));
+
+        for (data, format) in &[
+            (jpeg(8, 8), OutputFormat::Jpeg),
+            (png(rgb), OutputFormat::Png),
+            (png(rgba), OutputFormat::Png),
+        ] {
+            let processed = process_image(data, &ImageOptions::default()).unwrap();
+            assert_eq!(processed.format, *format);
+            assert_eq!(
+                image::guess_format(&processed.data).unwrap(),
+                match format {
+                    OutputFormat::Jpeg => ImageFormat::Jpeg,
+                    OutputFormat::Png => ImageFormat::Png,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_crop_square_clamps() {
+        // Red on the left 40 columns, blue on the rest.
+        let image = RgbImage::from_fn(100, 60, |x, _| {
+            if x < 40 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        });
+        let data = png(DynamicImage::ImageRgb8(image));
+
+        // Beyond the right and bottom edges, and larger than the image.
+        let (format, cropped) = crop_square(&data, 80, 50, 200, 32, 90).unwrap();
+
+        assert_eq!(format, OutputFormat::Jpeg);
+        let cropped = decode(&cropped).to_rgb8();
+        assert_eq!(cropped.dimensions(), (32, 32));
+        // Moved to the rightmost 60 columns, which are all blue.
+        for (x, y) in &[(0, 0), (31, 0), (0, 31), (31, 31), (16, 16)] {
+            let Rgb([r, _, b]) = *cropped.get_pixel(*x, *y);
+            assert!(r < 64 && b > 192, "{:?} is not blue.", (r, b));
+        }
+    }
+}
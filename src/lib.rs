@@ -10,14 +10,25 @@ use log::Level;
 mod app_components;
 mod apps;
 pub mod backend;
+mod blurhash;
 pub mod components;
+pub mod css;
+mod diff;
 mod error;
 mod helpers;
+mod imaging;
 mod manifest;
+mod markdown;
+mod permission;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod prerender;
+mod services;
+mod timezone;
 pub mod traits;
+mod user_agent;
 mod wasm_instant;
 
-use apps::{SigninApp, SigninAppProps, SigninSection};
+use apps::{OfficeApp, SigninApp, SigninAppProps, SigninSection};
 pub use error::{Error, Result};
 use manifest::Manifest;
 
@@ -33,9 +44,25 @@ pub fn prepare_office() -> StdResult<(), JsValue> {
 
 #[wasm_bindgen(js_name = startOffice)]
 pub async fn start_office() -> StdResult<(), JsValue> {
+    let manifest: Manifest = Manifest::fetch().await?;
+    App::<OfficeApp>::new().mount_as_body_with_props(manifest.into());
     Ok(())
 }
 
+// Called by static/image_worker.js inside a web worker.
+#[wasm_bindgen(js_name = startImageWorker)]
+pub fn start_image_worker() {
+    services::serve_image_worker();
+}
+
+// Call styleMetrics() in the console to see how many styles are in the document.
+#[cfg(debug_assertions)]
+#[wasm_bindgen(js_name = styleMetrics)]
+pub fn style_metrics() -> StdResult<JsValue, JsValue> {
+    let metrics = serde_json::to_string(&traits::style_metrics()).map_err(|e| e.to_string())?;
+    js_sys::JSON::parse(&metrics)
+}
+
 #[wasm_bindgen(js_name = startSignin)]
 pub async fn start_signin() -> StdResult<(), JsValue> {
     let manifest: Manifest = Manifest::fetch().await?;
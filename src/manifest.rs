@@ -1,9 +1,13 @@
 use std::borrow::Cow;
+use std::time::Duration;
 
 use reqwest::Url;
 use serde::Deserialize;
 
+use crate::backend::Client;
 use crate::helpers::window;
+use crate::imaging::ImageOptions;
+use crate::timezone::TimeZone;
 use crate::Result;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -11,6 +15,19 @@ pub(crate) struct Manifest {
     pub site_title: String,
     pub endpoint: String,
     pub asset_base_url: String,
+
+    // Seconds of inactivity before the office is locked.
+    #[serde(default = "Manifest::default_idle_timeout")]
+    pub idle_timeout: u64,
+
+    // An IANA timezone such as "Asia/Taipei", used to schedule posts.
+    // The timezone of the browser is used if not set.
+    #[serde(default)]
+    pub timezone: Option<String>,
+
+    // Images are scaled down and stripped of metadata before uploading.
+    #[serde(default)]
+    pub image: ImageOptions,
 }
 
 impl Manifest {
@@ -25,6 +42,31 @@ impl Manifest {
         Ok(toml::from_str(manifest_str.as_str())?)
     }
 
+    fn default_idle_timeout() -> u64 {
+        15 * 60
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout)
+    }
+
+    pub fn timezone(&self) -> TimeZone {
+        match self.timezone {
+            Some(ref m) => TimeZone::new(m).unwrap_or_else(|_| {
+                log::error!(
+                    "Unknown timezone: {}, using the timezone of the browser.",
+                    m
+                );
+                TimeZone::browser()
+            }),
+            None => TimeZone::browser(),
+        }
+    }
+
+    pub fn client(&self) -> Result<Client> {
+        Client::new(&self.endpoint)
+    }
+
     pub fn get_asset_url<S: AsRef<str>>(&self, partial: S) -> Result<String> {
         let url_base: Cow<'_, str> = if !self.asset_base_url.ends_with('/') {
             format!("{}/", self.asset_base_url).into()
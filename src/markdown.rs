@@ -0,0 +1,118 @@
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+fn options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start().to_lowercase();
+    !(url.starts_with("javascript:") || url.starts_with("vbscript:") || url.starts_with("data:"))
+}
+
+// Posts can be written by other residents, so the preview must not run anything they embed.
+// Raw HTML is shown as text and script urls are dropped.
+fn sanitize(event: Event) -> Event {
+    match event {
+        Event::Html(m) => Event::Text(m),
+        Event::Start(Tag::Link(ty, url, title)) if !is_safe_url(&url) => {
+            Event::Start(Tag::Link(ty, CowStr::Borrowed(""), title))
+        }
+        Event::Start(Tag::Image(ty, url, title)) if !is_safe_url(&url) => {
+            Event::Start(Tag::Image(ty, CowStr::Borrowed(""), title))
+        }
+        m => m,
+    }
+}
+
+// Renders CommonMark with GitHub flavoured tables, footnotes, strikethrough and task lists.
+pub(crate) fn render_markdown(s: &str) -> String {
+    let parser = Parser::new_ext(s, options()).map(sanitize);
+
+    let mut rendered = String::with_capacity(s.len() * 3 / 2);
+    html::push_html(&mut rendered, parser);
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_html_is_text() {
+        let rendered = render_markdown(
+            "<script>alert(1)</script>\n\nHello <img src=x onerror=\"alert(1)\"> world",
+        );
+
+        assert!(!rendered.contains("<script"));
+        assert!(!rendered.contains("<img"));
+        assert!(rendered.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(rendered.contains("&lt;img src=x onerror=&quot;alert(1)&quot;&gt;"));
+    }
+
+    #[test]
+    fn test_unsafe_urls_are_dropped() {
+        for url in &[
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "vbscript:msgbox(1)",
+            "data:text/html;base64,PHNjcmlwdD4=",
+        ] {
+            let rendered = render_markdown(&format!("[link]({}) ![image]({})", url, url));
+            assert_eq!(
+                rendered, "<p><a href=\"\">link</a> <img src=\"\" alt=\"image\" /></p>\n",
+                "{}",
+                url
+            );
+        }
+
+        // Leading whitespace is ignored by browsers.
+        let rendered = render_markdown("[link](< data:text/html,x>)");
+        assert_eq!(rendered, "<p><a href=\"\">link</a></p>\n");
+    }
+
+    #[test]
+    fn test_safe_urls_are_kept() {
+        let rendered = render_markdown("[link](https://example.com/a?b=c) ![image](/a.png)");
+
+        assert_eq!(
+            rendered,
+            "<p><a href=\"https://example.com/a?b=c\">link</a> <img src=\"/a.png\" alt=\"image\" /></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_tables() {
+        let rendered = render_markdown("| a | b |\n|---|--:|\n| 1 | 2 |");
+
+        assert!(rendered.contains("<table>"));
+        assert!(rendered.contains("<th>a</th>"));
+        assert!(rendered.contains("<td align=\"right\">2</td>"));
+    }
+
+    #[test]
+    fn test_footnotes() {
+        let rendered = render_markdown("Text[^1]\n\n[^1]: Note");
+
+        assert!(rendered.contains("<sup class=\"footnote-reference\"><a href=\"#1\">1</a></sup>"));
+        assert!(rendered.contains("<div class=\"footnote-definition\" id=\"1\">"));
+    }
+
+    #[test]
+    fn test_task_lists() {
+        let rendered = render_markdown("- [x] done\n- [ ] todo");
+
+        assert!(rendered.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\"/>"));
+        assert!(rendered.contains("<input disabled=\"\" type=\"checkbox\"/>"));
+    }
+
+    #[test]
+    fn test_strikethrough() {
+        assert_eq!(render_markdown("~~gone~~"), "<p><del>gone</del></p>\n");
+    }
+}
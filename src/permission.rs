@@ -0,0 +1,63 @@
+use crate::backend::Role;
+
+// Actions that not every resident may perform.
+//
+// The server enforces the same rules, the office only uses them to hide or disable what would be
+// refused anyway.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Permission {
+    // Includes scheduling.
+    PublishPosts,
+    EditOthersPosts,
+    UploadMedia,
+    ManageTaxonomy,
+    ModerateComments,
+    ManageResidents,
+}
+
+impl Role {
+    pub(crate) fn can(self, permission: Permission) -> bool {
+        match permission {
+            Permission::PublishPosts | Permission::UploadMedia => {
+                matches!(self, Role::Owner | Role::Editor | Role::Author)
+            }
+            Permission::EditOthersPosts
+            | Permission::ManageTaxonomy
+            | Permission::ModerateComments => matches!(self, Role::Owner | Role::Editor),
+            Permission::ManageResidents => self == Role::Owner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_can_do_everything() {
+        for permission in [
+            Permission::PublishPosts,
+            Permission::EditOthersPosts,
+            Permission::UploadMedia,
+            Permission::ManageTaxonomy,
+            Permission::ModerateComments,
+            Permission::ManageResidents,
+        ]
+        .iter()
+        {
+            assert!(Role::Owner.can(*permission));
+        }
+    }
+
+    #[test]
+    fn test_roles_are_ordered() {
+        assert!(Role::Editor.can(Permission::ModerateComments));
+        assert!(!Role::Editor.can(Permission::ManageResidents));
+
+        assert!(Role::Author.can(Permission::PublishPosts));
+        assert!(!Role::Author.can(Permission::EditOthersPosts));
+
+        assert!(!Role::Contributor.can(Permission::PublishPosts));
+        assert!(!Role::Contributor.can(Permission::UploadMedia));
+    }
+}
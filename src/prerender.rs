@@ -0,0 +1,373 @@
+// Critical styles and a loading shell for the static pages, which the prerender binary writes into
+// them so they are styled before the wasm is loaded.
+//
+// Only styles can be evaluated outside of a browser, so the shell is written by hand with the
+// classes and the text of the components it imitates. Styles of the rest of the first screen are
+// included as well, the style registry uses the prerendered classes instead of adding them again.
+
+use yew::prelude::*;
+
+use crate::app_components::{
+    BottomLinkItemPosition, BottomLinkItemProps, BottomLinksProps, FlexGrow, SigninLogoProps,
+    SigninSubTitleProps, SigninTitleProps,
+};
+use crate::apps::{SigninAppProps, SigninSection, SIGNIN_FOOTER};
+use crate::components::{
+    BodyProps, ButtonProps, CardProps, HtmlStyle, InputStyle, ProgressiveImageProps, Spinner,
+};
+use crate::css::Stylesheet;
+use crate::manifest::Manifest;
+use crate::traits::{class_name_of, YieldStyle};
+use crate::Result;
+
+const STYLES_START: &str = "<!-- prerender:styles -->";
+const STYLES_END: &str = "<!-- /prerender:styles -->";
+const SHELL_START: &str = "<!-- prerender:shell -->";
+const SHELL_END: &str = "<!-- /prerender:shell -->";
+
+// Replaced by the server with the nonce of each response, as in the rest of the page.
+const STYLE_NONCE: &str = "{{style-nonce}}";
+
+// Custom properties of the shell are set under this class, which the app does not use.
+const SHELL_CLASS: &str = "fl-prerendered";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Prerendered {
+    pub html_class: String,
+    pub body_class: String,
+    // Classes styled by css.
+    pub classes: Vec<String>,
+    pub css: String,
+    pub shell: String,
+    pub nonce: Option<String>,
+}
+
+impl Prerendered {
+    // The class of the style, which is added to css the first time.
+    fn class(&mut self, style: &impl YieldStyle) -> String {
+        self.class_of(&style.element_name(), style.yield_style_str())
+    }
+
+    // As class, for styles which differ in the browser and are prerendered without its helpers.
+    fn class_of(&mut self, element_name: &str, stylesheet: Stylesheet) -> String {
+        let class_name = class_name_of(element_name, &stylesheet);
+
+        if !self.classes.contains(&class_name) {
+            self.css.push_str(&stylesheet.to_css(&class_name));
+            self.classes.push(class_name.clone());
+        }
+
+        class_name
+    }
+
+    // The class, with the custom properties of this instance set for elements of the shell.
+    fn shell_class(&mut self, style: &impl YieldStyle) -> String {
+        let class_name = self.class(style);
+        let element_name = style.element_name();
+
+        let vars = style
+            .yield_style_vars()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = value.as_deref().unwrap_or("initial");
+                format!("--{}-{}: {};", element_name, name, value)
+            })
+            .collect::<Vec<_>>();
+
+        if !vars.is_empty() {
+            self.css.push_str(&format!(
+                ".{} .{} {{ {} }}\n",
+                SHELL_CLASS,
+                class_name,
+                vars.join(" ")
+            ));
+        }
+
+        class_name
+    }
+
+    // Writes the styles and the shell into the page between their markers, None if the page has
+    // no markers.
+    pub fn write_into(&self, page: &str) -> Option<String> {
+        let nonce = match self.nonce {
+            Some(ref m) => format!(" nonce=\"{}\"", escape(m)),
+            None => "".to_string(),
+        };
+        let styles = format!(
+            "<style data-prerendered=\"{}\"{}>\n{}</style>",
+            self.classes.join(" "),
+            nonce,
+            self.css
+        );
+
+        let page = replace_between(page, STYLES_START, STYLES_END, &styles)?;
+        let page = replace_between(&page, SHELL_START, SHELL_END, &self.shell)?;
+        let page = set_class(&page, "html", &self.html_class)?;
+
+        set_class(&page, "body", &self.body_class)
+    }
+}
+
+// signin.html, from the content of manifest.toml.
+pub fn signin(manifest: &str) -> Result<Prerendered> {
+    signin_app(manifest, SigninSection::Name)
+}
+
+// signup.html, from the content of manifest.toml.
+pub fn signup(manifest: &str) -> Result<Prerendered> {
+    signin_app(manifest, SigninSection::Signup)
+}
+
+// The shell imitates the signin app while it is busy.
+fn signin_app(manifest: &str, first_section: SigninSection) -> Result<Prerendered> {
+    let manifest: Manifest = toml::from_str(manifest)?;
+    let mut props: SigninAppProps = manifest.into();
+    props.set_first_section(first_section);
+
+    let mut prerendered = Prerendered {
+        nonce: Some(STYLE_NONCE.to_string()),
+        ..Prerendered::default()
+    };
+
+    let html = HtmlStyle {
+        grey_background: true,
+    };
+    prerendered.html_class = prerendered.class_of(&html.element_name(), html.prerender_style());
+    let body = BodyProps::builder()
+        .grey_background(true)
+        .title(props.to_title())
+        .children(Children::default())
+        .build();
+    prerendered.body_class = prerendered.class_of(&body.element_name(), body.prerender_style());
+
+    let app = prerendered.class(&props);
+    let card = prerendered.shell_class(
+        &CardProps::builder()
+            .with_margin(false)
+            .max_width(Some("400px".to_string()))
+            .width(Some("calc(100vw - 40px)".to_string()))
+            .height(Some(props.card_height().to_string()))
+            .build(),
+    );
+    let title = prerendered.class(&SigninTitleProps::builder().build());
+    // The url of the logo depends on where the pages are served, so the shell only keeps its place
+    // and the app loads it.
+    let logo = prerendered.shell_class(&SigninLogoProps::builder().src(String::new()).build());
+    let sub_title = prerendered.class(&SigninSubTitleProps::builder().build());
+    let spinner = prerendered.class(&Spinner);
+
+    // The rest of the first screen.
+    prerendered.class(
+        &ProgressiveImageProps::builder()
+            .fit("contain")
+            .src(String::new())
+            .build(),
+    );
+    prerendered.class(&FlexGrow);
+    prerendered.class(&InputStyle {
+        invalid: false,
+        height: "40px".to_string(),
+        width: "100%".to_string(),
+    });
+    prerendered.class(
+        &ButtonProps::builder()
+            .width(Some("100%".to_string()))
+            .build(),
+    );
+    prerendered.class(
+        &SigninSubTitleProps::builder()
+            .text_align("right".to_string())
+            .build(),
+    );
+    prerendered.class(
+        &BottomLinksProps::builder()
+            .children(Children::default())
+            .build(),
+    );
+    for position in &[BottomLinkItemPosition::First, BottomLinkItemPosition::Last] {
+        prerendered.class(
+            &BottomLinkItemProps::builder()
+                .position(position.clone())
+                .build(),
+        );
+    }
+
+    prerendered.shell = format!(
+        r#"<div class="{app} {shell}">
+  <div class="{card}">
+    <div class="{title}">{title_text}</div>
+    <div class="{logo}"></div>
+    <div class="busy">
+      <div class="{sub_title}">請稍候...</div>
+      <div class="{spinner}"><div class="bounce1 bounce"></div><div class="bounce2 bounce"></div><div class="bounce3 bounce"></div></div>
+    </div>
+  </div>
+  <footer>{footer}</footer>
+</div>"#,
+        app = app,
+        shell = SHELL_CLASS,
+        card = card,
+        title = title,
+        title_text = escape(props.first_section.title()),
+        logo = logo,
+        sub_title = sub_title,
+        spinner = spinner,
+        footer = escape(SIGNIN_FOOTER),
+    );
+
+    Ok(prerendered)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Replaces what is between the markers, which stay so the page can be prerendered again.
+fn replace_between(page: &str, start: &str, end: &str, content: &str) -> Option<String> {
+    let content_start = page.find(start)? + start.len();
+    let content_end = content_start + page[content_start..].find(end)?;
+
+    Some(format!(
+        "{}\n{}\n{}",
+        &page[..content_start],
+        content,
+        &page[content_end..]
+    ))
+}
+
+// Sets the class attribute of the first element with the tag, None if there is none.
+fn set_class(page: &str, tag: &str, class_name: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let tag_start = page
+        .match_indices(&open)
+        .map(|(i, _)| i)
+        .find(|i| matches!(page[i + open.len()..].chars().next(), Some(' ') | Some('>')))?;
+    let tag_end = tag_start + page[tag_start..].find('>')?;
+    let tag_str = &page[tag_start..tag_end];
+
+    let new_tag = match tag_str.find(" class=\"") {
+        Some(m) => {
+            let value_start = m + " class=\"".len();
+            let value_end = value_start + tag_str[value_start..].find('"')?;
+            format!(
+                "{}{}{}",
+                &tag_str[..value_start],
+                class_name,
+                &tag_str[value_end..]
+            )
+        }
+        None => format!("{} class=\"{}\"", tag_str, class_name),
+    };
+
+    Some(format!(
+        "{}{}{}",
+        &page[..tag_start],
+        new_tag,
+        &page[tag_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html lang="zh-hant">
+  <head>
+    <!-- prerender:styles --><!-- /prerender:styles -->
+  </head>
+  <body>
+    <!-- prerender:shell --><!-- /prerender:shell -->
+  </body>
+</html>"#;
+
+    fn prerendered() -> Prerendered {
+        Prerendered {
+            html_class: "fl-html-1".to_string(),
+            body_class: "fl-body-1".to_string(),
+            classes: vec!["fl-card-1".to_string(), "fl-spinner-1".to_string()],
+            css: ".fl-card-1 { width: 100%; }\n".to_string(),
+            shell: "<div class=\"fl-card-1\"></div>".to_string(),
+            nonce: None,
+        }
+    }
+
+    #[test]
+    fn test_write_into() {
+        let page = prerendered().write_into(PAGE).unwrap();
+
+        assert!(page.contains(r#"<html lang="zh-hant" class="fl-html-1">"#));
+        assert!(page.contains(r#"<body class="fl-body-1">"#));
+        assert!(page.contains(
+            "<!-- prerender:styles -->\n\
+             <style data-prerendered=\"fl-card-1 fl-spinner-1\">\n\
+             .fl-card-1 { width: 100%; }\n\
+             </style>\n\
+             <!-- /prerender:styles -->"
+        ));
+        assert!(page.contains(
+            "<!-- prerender:shell -->\n<div class=\"fl-card-1\"></div>\n<!-- /prerender:shell -->"
+        ));
+    }
+
+    #[test]
+    fn test_write_into_again() {
+        let page = prerendered().write_into(PAGE).unwrap();
+
+        let mut other = prerendered();
+        other.body_class = "fl-body-2".to_string();
+        other.nonce = Some("abc\"".to_string());
+        let page = other.write_into(&page).unwrap();
+
+        assert!(page.contains(r#"<body class="fl-body-2">"#));
+        assert!(
+            page.contains(r#"<style data-prerendered="fl-card-1 fl-spinner-1" nonce="abc&quot;">"#)
+        );
+        assert_eq!(page.matches("<style").count(), 1);
+        assert_eq!(page.matches("fl-card-1\"></div>").count(), 1);
+    }
+
+    #[test]
+    fn test_write_into_without_markers() {
+        assert_eq!(prerendered().write_into("<html><body></body></html>"), None);
+    }
+
+    #[test]
+    fn test_signin() {
+        let manifest = include_str!("../static/manifest.toml");
+        let prerendered = signin(manifest).unwrap();
+
+        assert!(prerendered.body_class.starts_with("fl-body-"));
+        assert!(prerendered.classes.contains(&prerendered.body_class));
+        assert!(prerendered.css.contains(".fl-prerendered .fl-card-"));
+        assert!(prerendered.shell.contains("歡迎來到未來領域管理局"));
+    }
+
+    // Fails when the styles of the first screen change without running the prerender binary.
+    #[test]
+    fn test_pages_are_current() {
+        let manifest = include_str!("../static/manifest.toml");
+
+        for (name, page, prerendered) in &[
+            (
+                "signin.html",
+                include_str!("../static/signin.html"),
+                signin(manifest),
+            ),
+            (
+                "signup.html",
+                include_str!("../static/signup.html"),
+                signup(manifest),
+            ),
+        ] {
+            let prerendered = prerendered.as_ref().unwrap();
+            assert!(
+                prerendered.write_into(page).as_deref() == Some(*page),
+                "static/{} is stale, run `cargo run --bin prerender -- static`.",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest};
+
+use crate::helpers::{window, EventListener};
+use crate::Result;
+
+const DATABASE_NAME: &str = "fl-office";
+const DATABASE_VERSION: u32 = 2;
+// Object stores are created when missing, so adding one only needs a new version.
+const STORE_NAMES: &[&str] = &["drafts", "uploads"];
+
+// Resolves when the request succeeds or fails.
+pub(crate) async fn wait(request: &IdbRequest) -> Result<JsValue> {
+    let (s, r) = async_channel::bounded::<bool>(1);
+    let s_error = s.clone();
+
+    let _success = EventListener::new(request, "success", move |_| {
+        let _ = s.try_send(true);
+    });
+    let _error = EventListener::new(request, "error", move |_| {
+        let _ = s_error.try_send(false);
+    });
+
+    if r.recv().await.unwrap_or(false) {
+        Ok(request.result()?)
+    } else {
+        let e = request
+            .error()?
+            .map(JsValue::from)
+            .unwrap_or(JsValue::UNDEFINED);
+        Err(e.into())
+    }
+}
+
+// The IndexedDB database of the office.
+pub(crate) async fn open_database() -> Result<IdbDatabase> {
+    let factory = window()
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB is not available."))?;
+
+    let request: IdbOpenDbRequest = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+
+    let upgrade_request = request.clone();
+    let _upgrade = EventListener::new(&request, "upgradeneeded", move |_| {
+        let db = match upgrade_request.result() {
+            Ok(m) => m.unchecked_into::<IdbDatabase>(),
+            Err(_) => return,
+        };
+
+        for name in STORE_NAMES {
+            if db.object_store_names().contains(name) {
+                continue;
+            }
+
+            if let Err(e) = db.create_object_store(name) {
+                log::error!("Failed to create object store {}: {:?}", name, e);
+            }
+        }
+    });
+
+    Ok(wait(&request).await?.unchecked_into::<IdbDatabase>())
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{IdbDatabase, IdbObjectStore, IdbTransactionMode};
+
+use super::database::{open_database, wait};
+use crate::backend::PostDraft;
+use crate::Result;
+
+const STORE_NAME: &str = "drafts";
+
+// A copy of the editor state kept in the browser, so it survives a crashed tab.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LocalDraft {
+    pub key: String,
+    pub draft: PostDraft,
+    // Unix timestamp in seconds.
+    pub saved_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DraftStore {
+    db: IdbDatabase,
+}
+
+impl DraftStore {
+    pub async fn open() -> Result<Self> {
+        Ok(Self {
+            db: open_database().await?,
+        })
+    }
+
+    fn object_store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore> {
+        Ok(self
+            .db
+            .transaction_with_str_and_mode(STORE_NAME, mode)?
+            .object_store(STORE_NAME)?)
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<LocalDraft>> {
+        let request = self
+            .object_store(IdbTransactionMode::Readonly)?
+            .get(&key.into())?;
+
+        // Copies that cannot be read anymore are treated as missing.
+        Ok(wait(&request)
+            .await?
+            .as_string()
+            .and_then(|m| serde_json::from_str(&m).ok()))
+    }
+
+    pub async fn put(&self, draft: &LocalDraft) -> Result<()> {
+        let value = serde_json::to_string(draft).unwrap();
+        let request = self
+            .object_store(IdbTransactionMode::Readwrite)?
+            .put_with_key(&value.into(), &draft.key.as_str().into())?;
+
+        wait(&request).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        let request = self
+            .object_store(IdbTransactionMode::Readwrite)?
+            .delete(&key.into())?;
+
+        wait(&request).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::cell::Cell;
+use std::convert::TryInto;
+use std::rc::Rc;
+use std::time::Duration;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::helpers::{document, window, EventListener, Instant};
+
+const ACTIVITY_EVENTS: &[&str] = &["keydown", "mousedown", "mousemove", "touchstart", "wheel"];
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct IdleState {
+    timeout: Duration,
+    last_active: Cell<Instant>,
+    idle: Cell<bool>,
+}
+
+impl IdleState {
+    fn touch(&self) {
+        if !self.idle.get() {
+            self.last_active.set(Instant::now());
+        }
+    }
+
+    // Returns true if the tracker has just become idle.
+    fn check(&self) -> bool {
+        if self.idle.get() || self.last_active.get().elapsed() < self.timeout {
+            return false;
+        }
+
+        self.idle.set(true);
+        true
+    }
+}
+
+// Calls `on_idle` once no keyboard or mouse activity has been seen for `timeout`.
+// The tracker stays idle until it is reset.
+#[derive(Debug)]
+pub(crate) struct IdleTracker {
+    state: Rc<IdleState>,
+    _listeners: Vec<EventListener>,
+    _check_closure: Closure<dyn FnMut()>,
+    interval_handle: i32,
+}
+
+impl IdleTracker {
+    pub fn new<F>(timeout: Duration, on_idle: F) -> Self
+    where
+        F: 'static + Fn(),
+    {
+        let state = Rc::new(IdleState {
+            timeout,
+            last_active: Cell::new(Instant::now()),
+            idle: Cell::new(false),
+        });
+        let on_idle = Rc::new(on_idle);
+
+        let mut listeners = Vec::with_capacity(ACTIVITY_EVENTS.len() + 1);
+
+        for event_name in ACTIVITY_EVENTS.iter() {
+            let state = state.clone();
+            listeners.push(EventListener::new(&window(), event_name, move |_| {
+                state.touch()
+            }));
+        }
+
+        // Timers are throttled in background tabs,
+        // so check as soon as the page becomes visible again.
+        {
+            let state = state.clone();
+            let on_idle = on_idle.clone();
+            listeners.push(EventListener::new(
+                &document(),
+                "visibilitychange",
+                move |_| {
+                    if !document().hidden() && state.check() {
+                        on_idle();
+                    }
+                },
+            ));
+        }
+
+        let check_closure = {
+            let state = state.clone();
+            Closure::wrap(Box::new(move || {
+                if state.check() {
+                    on_idle();
+                }
+            }) as Box<dyn FnMut()>)
+        };
+
+        let interval_handle = window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                check_closure.as_ref().unchecked_ref(),
+                CHECK_INTERVAL.as_millis().try_into().unwrap(),
+            )
+            .unwrap();
+
+        Self {
+            state,
+            _listeners: listeners,
+            _check_closure: check_closure,
+            interval_handle,
+        }
+    }
+
+    pub fn reset(&self) {
+        self.state.idle.set(false);
+        self.state.last_active.set(Instant::now());
+    }
+}
+
+impl Drop for IdleTracker {
+    fn drop(&mut self) {
+        window().clear_interval_with_handle(self.interval_handle);
+    }
+}
@@ -0,0 +1,310 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use async_channel::Sender;
+use js_sys::{Array, Uint8Array};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobPropertyBag, DedicatedWorkerGlobalScope, File, MessageEvent, Worker, WorkerOptions,
+    WorkerType,
+};
+
+use crate::backend::MediaUpload;
+use crate::components::CropArea;
+use crate::helpers::EventListener;
+use crate::imaging::{crop_square, process_image, ImageOptions};
+use crate::{Error, Result};
+
+// Loads the same wasm module as the office, see static/image_worker.js.
+const WORKER_URL: &str = "image_worker.js";
+// Sent by the worker once it is listening.
+const READY: &str = "ready";
+
+#[derive(Debug, Serialize, Deserialize)]
+enum ImageTask {
+    // Scaled down with a thumbnail, see process_image.
+    Process(ImageOptions),
+    // See crop_square.
+    CropSquare {
+        x: u32,
+        y: u32,
+        size: u32,
+        output_size: u32,
+        quality: u8,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageJob {
+    id: u64,
+    task: ImageTask,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageInfo {
+    content_type: String,
+    extension: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageJobResult {
+    id: u64,
+    result: std::result::Result<ImageInfo, String>,
+}
+
+// Runs inside the worker.
+pub(crate) fn serve_image_worker() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+
+    let reply_scope = scope.clone();
+    let listener = EventListener::new(&scope, "message", move |e| {
+        let message = match e.dyn_ref::<MessageEvent>() {
+            Some(m) => Array::from(&m.data()),
+            None => return,
+        };
+
+        let job = match message
+            .get(0)
+            .as_string()
+            .and_then(|m| serde_json::from_str::<ImageJob>(&m).ok())
+        {
+            Some(m) => m,
+            None => {
+                log::warn!("Ignoring malformed image job.");
+                return;
+            }
+        };
+        let input = Uint8Array::new(&message.get(1)).to_vec();
+
+        let processed = match job.task {
+            ImageTask::Process(ref options) => {
+                process_image(&input, options).map(|m| (m.format, m.data, m.thumbnail))
+            }
+            ImageTask::CropSquare {
+                x,
+                y,
+                size,
+                output_size,
+                quality,
+            } => crop_square(&input, x, y, size, output_size, quality)
+                .map(|(format, data)| (format, data, Vec::new())),
+        };
+
+        let (result, data, thumbnail) = match processed {
+            Ok((format, data, thumbnail)) => (
+                Ok(ImageInfo {
+                    content_type: format.content_type().to_string(),
+                    extension: format.extension().to_string(),
+                }),
+                data,
+                thumbnail,
+            ),
+            Err(e) => (Err(e.to_string()), Vec::new(), Vec::new()),
+        };
+
+        let header = serde_json::to_string(&ImageJobResult { id: job.id, result }).unwrap();
+        let data = Uint8Array::from(&data[..]);
+        let thumbnail = Uint8Array::from(&thumbnail[..]);
+
+        let sent = reply_scope.post_message_with_transfer(
+            &Array::of3(&header.into(), &data, &thumbnail),
+            &Array::of2(&data.buffer(), &thumbnail.buffer()),
+        );
+        if let Err(e) = sent {
+            log::error!("Failed to reply to image job: {:?}", e);
+        }
+    });
+
+    // The worker listens until it is terminated.
+    std::mem::forget(listener);
+
+    scope.post_message(&READY.into()).unwrap();
+}
+
+#[derive(Debug, Default)]
+struct WorkerState {
+    ready: bool,
+    failed: bool,
+    // Jobs sent before the worker is ready.
+    queued: Vec<(Array, Array)>,
+    pending: HashMap<u64, Sender<Array>>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct ImageWorkerInner {
+    worker: Worker,
+    options: ImageOptions,
+    state: Rc<RefCell<WorkerState>>,
+    _listeners: [EventListener; 2],
+}
+
+impl Drop for ImageWorkerInner {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+// Resizes and strips metadata from images off the main thread.
+#[derive(Debug, Clone)]
+pub(crate) struct ImageWorker {
+    inner: Rc<ImageWorkerInner>,
+}
+
+impl ImageWorker {
+    pub fn new(options: ImageOptions) -> Result<Self> {
+        let worker_options = WorkerOptions::new();
+        worker_options.set_type(WorkerType::Module);
+        let worker = Worker::new_with_options(WORKER_URL, &worker_options)?;
+
+        let state: Rc<RefCell<WorkerState>> = Rc::default();
+
+        let message_state = state.clone();
+        let message_worker = worker.clone();
+        let message_listener = EventListener::new(&worker, "message", move |e| {
+            let data = match e.dyn_ref::<MessageEvent>() {
+                Some(m) => m.data(),
+                None => return,
+            };
+            let mut state = message_state.borrow_mut();
+
+            if data.as_string().as_deref() == Some(READY) {
+                state.ready = true;
+                for (message, transfer) in state.queued.drain(..) {
+                    if let Err(e) = message_worker.post_message_with_transfer(&message, &transfer) {
+                        log::error!("Failed to send image job: {:?}", e);
+                    }
+                }
+                return;
+            }
+
+            let message = Array::from(&data);
+            let id = message
+                .get(0)
+                .as_string()
+                .and_then(|m| serde_json::from_str::<ImageJobResult>(&m).ok())
+                .map(|m| m.id);
+
+            if let Some(s) = id.and_then(|m| state.pending.remove(&m)) {
+                let _ = s.try_send(message);
+            }
+        });
+
+        // Pending jobs are failed by dropping their senders.
+        let error_state = state.clone();
+        let error_listener = EventListener::new(&worker, "error", move |_| {
+            log::error!("The image worker has failed.");
+            let mut state = error_state.borrow_mut();
+            state.failed = true;
+            state.queued.clear();
+            state.pending.clear();
+        });
+
+        Ok(Self {
+            inner: Rc::new(ImageWorkerInner {
+                worker,
+                options,
+                state,
+                _listeners: [message_listener, error_listener],
+            }),
+        })
+    }
+
+    fn send(&self, message: Array, transfer: Array) -> Result<()> {
+        let mut state = self.inner.state.borrow_mut();
+
+        if state.ready {
+            self.inner
+                .worker
+                .post_message_with_transfer(&message, &transfer)?;
+        } else {
+            state.queued.push((message, transfer));
+        }
+        Ok(())
+    }
+
+    // Sends the data to the worker, which replies with the info, the data and the thumbnail.
+    async fn run(&self, data: Uint8Array, task: ImageTask) -> Result<(ImageInfo, Array)> {
+        let (s, r) = async_channel::bounded(1);
+        let id = {
+            let mut state = self.inner.state.borrow_mut();
+            if state.failed {
+                return Err(Error::Image("The image worker has failed.".to_string()));
+            }
+
+            let id = state.next_id;
+            state.next_id += 1;
+            state.pending.insert(id, s);
+            id
+        };
+
+        let header = serde_json::to_string(&ImageJob { id, task })?;
+        self.send(
+            Array::of2(&header.into(), &data),
+            Array::of1(&data.buffer()),
+        )?;
+
+        let message = r
+            .recv()
+            .await
+            .map_err(|_| Error::Image("The image worker has failed.".to_string()))?;
+        let result: ImageJobResult =
+            serde_json::from_str(&message.get(0).as_string().unwrap_or_default())?;
+        let info = result.result.map_err(Error::Image)?;
+
+        Ok((info, message))
+    }
+
+    pub async fn process(&self, file: &File) -> Result<MediaUpload> {
+        let data = Uint8Array::new(&JsFuture::from(file.array_buffer()).await?);
+        let (info, message) = self
+            .run(data, ImageTask::Process(self.inner.options.clone()))
+            .await?;
+
+        // The extension follows the new format, such as photo.webp becoming photo.jpg.
+        let filename = file.name();
+        let stem = match filename.rfind('.') {
+            Some(i) if i > 0 => &filename[..i],
+            _ => filename.as_str(),
+        };
+
+        Ok(MediaUpload {
+            file: to_blob(&message.get(1), &info.content_type)?,
+            filename: format!("{}.{}", stem, info.extension),
+            thumbnail: Some(to_blob(&message.get(2), &info.content_type)?),
+        })
+    }
+
+    // Crops the square of the oriented image and scales it to output_size, see crop_square.
+    pub async fn crop_square(
+        &self,
+        data: &[u8],
+        crop: CropArea,
+        output_size: u32,
+        quality: u8,
+    ) -> Result<Blob> {
+        let task = ImageTask::CropSquare {
+            x: crop.x,
+            y: crop.y,
+            size: crop.size,
+            output_size,
+            quality,
+        };
+        let (info, message) = self.run(Uint8Array::from(data), task).await?;
+
+        to_blob(&message.get(1), &info.content_type)
+    }
+}
+
+fn to_blob(data: &JsValue, content_type: &str) -> Result<Blob> {
+    let options = BlobPropertyBag::new();
+    options.set_type(content_type);
+
+    Ok(Blob::new_with_u8_array_sequence_and_options(
+        &Array::of1(data),
+        &options,
+    )?)
+}
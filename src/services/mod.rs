@@ -0,0 +1,14 @@
+mod database;
+mod draft_store;
+mod idle;
+mod image_worker;
+mod tab_sync;
+mod upload_store;
+mod viewport;
+
+pub(crate) use draft_store::{DraftStore, LocalDraft};
+pub(crate) use idle::IdleTracker;
+pub(crate) use image_worker::{serve_image_worker, ImageWorker};
+pub(crate) use tab_sync::{TabEvent, TabSync};
+pub(crate) use upload_store::{PendingUpload, UploadStore};
+pub(crate) use viewport::{Viewport, ViewportState};
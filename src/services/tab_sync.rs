@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{BroadcastChannel, MessageEvent, StorageEvent};
+
+use crate::helpers::{document, window, EventListener};
+
+const CHANNEL_NAME: &str = "fl-office";
+// Used when BroadcastChannel is not available.
+const STORAGE_KEY: &str = "fl-office-tab-event";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub(crate) enum TabEvent {
+    SignedIn,
+    SignedOut,
+    TokenRefreshed,
+    // The office has no theme or locale setting of its own, these are sent by other pages of the
+    // origin which change them, such as the site.
+    ThemeChanged { theme: String },
+    LocaleChanged { locale: String },
+}
+
+impl TabEvent {
+    // Sets the theme or the locale on <html>, as data-theme and lang, which styles select on.
+    pub fn apply_to_document(&self) {
+        let (name, value) = match self {
+            TabEvent::ThemeChanged { theme } => ("data-theme", theme),
+            TabEvent::LocaleChanged { locale } => ("lang", locale),
+            _ => return,
+        };
+
+        if let Some(m) = document().document_element() {
+            if let Err(e) = m.set_attribute(name, value) {
+                log::error!("Failed to set {}: {:?}", name, e);
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TabEnvelope {
+    event: TabEvent,
+    // Makes every write unique, storage events do not fire if the value stays the same.
+    sent_at: f64,
+}
+
+impl TabEnvelope {
+    fn encode(event: &TabEvent) -> String {
+        serde_json::to_string(&TabEnvelope {
+            event: event.clone(),
+            sent_at: js_sys::Date::now(),
+        })
+        .unwrap()
+    }
+
+    fn decode(s: &str) -> Option<TabEvent> {
+        match serde_json::from_str::<TabEnvelope>(s) {
+            Ok(m) => Some(m.event),
+            Err(e) => {
+                log::warn!("Ignoring malformed tab event: {}", e);
+                None
+            }
+        }
+    }
+}
+
+// Propagates events to other tabs of the office on the same origin.
+// Events are never delivered to the tab that sent them.
+#[derive(Debug)]
+pub(crate) struct TabSync {
+    channel: Option<BroadcastChannel>,
+    // On the channel, or on the window for storage events.
+    listener: Option<EventListener>,
+}
+
+impl TabSync {
+    pub fn new<F>(on_event: F) -> Self
+    where
+        F: 'static + Fn(TabEvent),
+    {
+        match BroadcastChannel::new(CHANNEL_NAME) {
+            Ok(channel) => {
+                let listener = EventListener::new(&channel, "message", move |e| {
+                    let event = e
+                        .dyn_ref::<MessageEvent>()
+                        .and_then(|m| m.data().as_string())
+                        .and_then(|m| TabEnvelope::decode(&m));
+
+                    if let Some(m) = event {
+                        on_event(m);
+                    }
+                });
+
+                Self {
+                    channel: Some(channel),
+                    listener: Some(listener),
+                }
+            }
+            Err(_) => {
+                log::debug!("BroadcastChannel is not available, falling back to storage events.");
+
+                let listener = EventListener::new(&window(), "storage", move |e| {
+                    let e = match e.dyn_ref::<StorageEvent>() {
+                        Some(m) => m,
+                        None => return,
+                    };
+
+                    if e.key().as_deref() != Some(STORAGE_KEY) {
+                        return;
+                    }
+
+                    if let Some(m) = e.new_value().and_then(|m| TabEnvelope::decode(&m)) {
+                        on_event(m);
+                    }
+                });
+
+                Self {
+                    channel: None,
+                    listener: Some(listener),
+                }
+            }
+        }
+    }
+
+    pub fn broadcast(&self, event: &TabEvent) {
+        let encoded = TabEnvelope::encode(event);
+
+        match self.channel {
+            Some(ref m) => {
+                if let Err(e) = m.post_message(&encoded.into()) {
+                    log::error!("Failed to broadcast tab event: {:?}", e);
+                }
+            }
+            None => {
+                if let Some(m) = window().local_storage().ok().flatten() {
+                    if let Err(e) = m.set_item(STORAGE_KEY, &encoded) {
+                        log::error!("Failed to broadcast tab event: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for TabSync {
+    fn drop(&mut self) {
+        // Removed before the channel is closed.
+        self.listener.take();
+
+        if let Some(ref m) = self.channel {
+            m.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        for (s, event) in &[
+            (
+                r#"{"event":{"type":"signed-out"},"sent_at":1.0}"#,
+                TabEvent::SignedOut,
+            ),
+            (
+                r#"{"event":{"type":"theme-changed","theme":"dark"},"sent_at":1.0}"#,
+                TabEvent::ThemeChanged {
+                    theme: "dark".to_string(),
+                },
+            ),
+            (
+                r#"{"event":{"type":"locale-changed","locale":"en"},"sent_at":1.0}"#,
+                TabEvent::LocaleChanged {
+                    locale: "en".to_string(),
+                },
+            ),
+        ] {
+            assert_eq!(TabEnvelope::decode(s).as_ref(), Some(event));
+        }
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        assert_eq!(TabEnvelope::decode(""), None);
+        assert_eq!(TabEnvelope::decode(r#"{"event":{"type":"unknown"}}"#), None);
+        assert_eq!(
+            TabEnvelope::decode(r#"{"event":{"type":"theme-changed"},"sent_at":1.0}"#),
+            None
+        );
+    }
+}
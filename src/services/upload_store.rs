@@ -0,0 +1,150 @@
+use js_sys::{Array, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, IdbDatabase, IdbObjectStore, IdbTransactionMode};
+
+use super::database::{open_database, wait};
+use crate::backend::{MediaUpload, UploadMetadata};
+use crate::helpers::now_timestamp;
+use crate::Result;
+
+const STORE_NAME: &str = "uploads";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PendingUploadInfo {
+    key: String,
+    filename: String,
+    // Set once the server has accepted the upload.
+    location: Option<String>,
+    // Unix timestamp in seconds.
+    created_at: i64,
+}
+
+// An unfinished resumable upload, kept with its file so it can be resumed after a reload.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PendingUpload {
+    pub key: String,
+    pub upload: MediaUpload,
+    pub location: Option<String>,
+    pub created_at: i64,
+}
+
+impl PendingUpload {
+    pub fn new(upload: MediaUpload) -> Self {
+        let created_at = now_timestamp();
+
+        Self {
+            key: format!(
+                "{}-{:08x}",
+                created_at,
+                (js_sys::Math::random() * f64::from(u32::MAX)) as u32
+            ),
+            upload,
+            location: None,
+            created_at,
+        }
+    }
+
+    pub fn metadata(&self) -> UploadMetadata {
+        UploadMetadata {
+            filename: self.upload.filename.clone(),
+            content_type: self.upload.file.type_(),
+        }
+    }
+
+    // Files are stored as they are, which IndexedDB supports but JSON does not.
+    fn to_value(&self) -> Result<JsValue> {
+        let info = serde_json::to_string(&PendingUploadInfo {
+            key: self.key.clone(),
+            filename: self.upload.filename.clone(),
+            location: self.location.clone(),
+            created_at: self.created_at,
+        })?;
+
+        let value = Object::new();
+        Reflect::set(&value, &"info".into(), &info.into())?;
+        Reflect::set(&value, &"file".into(), &self.upload.file)?;
+        if let Some(ref m) = self.upload.thumbnail {
+            Reflect::set(&value, &"thumbnail".into(), m)?;
+        }
+
+        Ok(value.into())
+    }
+
+    fn from_value(value: &JsValue) -> Option<Self> {
+        let info: PendingUploadInfo = Reflect::get(value, &"info".into())
+            .ok()?
+            .as_string()
+            .and_then(|m| serde_json::from_str(&m).ok())?;
+        let file = Reflect::get(value, &"file".into())
+            .ok()?
+            .dyn_into::<Blob>()
+            .ok()?;
+        let thumbnail = Reflect::get(value, &"thumbnail".into())
+            .ok()
+            .and_then(|m| m.dyn_into::<Blob>().ok());
+
+        Some(Self {
+            key: info.key,
+            upload: MediaUpload {
+                file,
+                filename: info.filename,
+                thumbnail,
+            },
+            location: info.location,
+            created_at: info.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct UploadStore {
+    db: IdbDatabase,
+}
+
+impl UploadStore {
+    pub async fn open() -> Result<Self> {
+        Ok(Self {
+            db: open_database().await?,
+        })
+    }
+
+    fn object_store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore> {
+        Ok(self
+            .db
+            .transaction_with_str_and_mode(STORE_NAME, mode)?
+            .object_store(STORE_NAME)?)
+    }
+
+    // Oldest first.
+    pub async fn all(&self) -> Result<Vec<PendingUpload>> {
+        let request = self.object_store(IdbTransactionMode::Readonly)?.get_all()?;
+
+        // Uploads that cannot be read anymore are skipped.
+        let mut uploads = Array::from(&wait(&request).await?)
+            .iter()
+            .filter_map(|m| PendingUpload::from_value(&m))
+            .collect::<Vec<_>>();
+        uploads.sort_by_key(|m| m.created_at);
+
+        Ok(uploads)
+    }
+
+    pub async fn put(&self, upload: &PendingUpload) -> Result<()> {
+        let request = self
+            .object_store(IdbTransactionMode::Readwrite)?
+            .put_with_key(&upload.to_value()?, &upload.key.as_str().into())?;
+
+        wait(&request).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        let request = self
+            .object_store(IdbTransactionMode::Readwrite)?
+            .delete(&key.into())?;
+
+        wait(&request).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::css::{css, Stylesheet};
+use crate::helpers::{call_after, document, window, EventListener};
+use crate::traits::YieldStyle;
+
+// The same widths as the media queries of the styles.
+const SMALL_MAX_WIDTH: f64 = 340.0;
+const MEDIUM_MAX_WIDTH: f64 = 800.0;
+
+// The visual viewport is shorter than this when the virtual keyboard is shown, less is taken by
+// the toolbars of mobile browsers.
+const KEYBOARD_MIN_HEIGHT: f64 = 150.0;
+
+// Sizes are wrong for a moment after orientationchange on mobile Safari, and events often come
+// in bursts, so they are measured once after this.
+const MEASURE_DELAY: Duration = Duration::from_millis(100);
+
+const WINDOW_EVENTS: &[&str] = &["resize", "orientationchange", "pageshow"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Breakpoint {
+    // Up to 340px.
+    Small,
+    // Up to 800px.
+    Medium,
+    Large,
+}
+
+impl Breakpoint {
+    fn from_width(width: f64) -> Self {
+        if width <= SMALL_MAX_WIDTH {
+            Self::Small
+        } else if width <= MEDIUM_MAX_WIDTH {
+            Self::Medium
+        } else {
+            Self::Large
+        }
+    }
+}
+
+// In pixels, the parts of the viewport covered by notches and rounded corners.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct SafeAreaInsets {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ViewportState {
+    pub width: f64,
+    // The height of the window, which 100vh exceeds on mobile Safari.
+    pub height: f64,
+    // The part of it not covered by the virtual keyboard.
+    pub visible_height: f64,
+    pub keyboard_visible: bool,
+    pub safe_area: SafeAreaInsets,
+    pub breakpoint: Breakpoint,
+}
+
+impl ViewportState {
+    fn measure(probe: &HtmlElement) -> Self {
+        let window = window();
+        let width = window.inner_width().unwrap().as_f64().unwrap();
+        let height = window.inner_height().unwrap().as_f64().unwrap();
+
+        // Not available in older browsers, which do not tell if the keyboard is shown.
+        let visible_height = window
+            .visual_viewport()
+            .map(|m| m.height())
+            .unwrap_or(height);
+
+        let style = window.get_computed_style(probe).ok().flatten();
+        let inset = |property| {
+            style
+                .as_ref()
+                .and_then(|m| m.get_property_value(property).ok())
+                .and_then(|m| m.trim_end_matches("px").parse().ok())
+                .unwrap_or(0.0)
+        };
+
+        Self {
+            width,
+            height,
+            visible_height,
+            keyboard_visible: height - visible_height > KEYBOARD_MIN_HEIGHT,
+            safe_area: SafeAreaInsets {
+                top: inset("padding-top"),
+                right: inset("padding-right"),
+                bottom: inset("padding-bottom"),
+                left: inset("padding-left"),
+            },
+            breakpoint: Breakpoint::from_width(width),
+        }
+    }
+}
+
+// An element padded by the safe area insets, which are only available to CSS.
+struct SafeAreaProbe;
+
+impl YieldStyle for SafeAreaProbe {
+    fn element_name(&self) -> Cow<'static, str> {
+        "fl-safe-area-probe".into()
+    }
+
+    fn yield_style_str(&self) -> Stylesheet {
+        css!(
+            r#"
+            position: fixed;
+            top: 0;
+            left: 0;
+            width: 0;
+            height: 0;
+            visibility: hidden;
+            pointer-events: none;
+
+            padding-top: env(safe-area-inset-top, 0px);
+            padding-right: env(safe-area-inset-right, 0px);
+            padding-bottom: env(safe-area-inset-bottom, 0px);
+            padding-left: env(safe-area-inset-left, 0px);
+            "#
+        )
+    }
+}
+
+type Subscriber = Rc<dyn Fn(ViewportState)>;
+
+// Shared by all subscriptions, exists while there is at least one.
+struct ViewportShared {
+    state: ViewportState,
+    subscribers: Vec<(u64, Subscriber)>,
+    next_id: u64,
+    measure_pending: bool,
+    probe: HtmlElement,
+    _listeners: Vec<EventListener>,
+}
+
+impl ViewportShared {
+    fn new() -> Self {
+        let document = document();
+
+        // Outside of the body, which the app replaces when mounted.
+        let probe: HtmlElement = document.create_element("div").unwrap().unchecked_into();
+        probe.set_class_name(&SafeAreaProbe.yield_style_class());
+        probe.set_attribute("aria-hidden", "true").unwrap();
+        document
+            .document_element()
+            .expect("Document element is not available.")
+            .append_child(&probe)
+            .unwrap();
+
+        let window = window();
+        let mut listeners: Vec<EventListener> = WINDOW_EVENTS
+            .iter()
+            .map(|m| EventListener::new(&window, m, |_| schedule_measure()))
+            .collect();
+
+        // Resized when the virtual keyboard is shown or hidden, the window is not.
+        if let Some(m) = window.visual_viewport() {
+            listeners.push(EventListener::new(&m, "resize", |_| schedule_measure()));
+        }
+
+        Self {
+            state: ViewportState::measure(&probe),
+            subscribers: Vec::new(),
+            next_id: 0,
+            measure_pending: false,
+            probe,
+            _listeners: listeners,
+        }
+    }
+}
+
+impl Drop for ViewportShared {
+    fn drop(&mut self) {
+        self.probe.remove();
+    }
+}
+
+thread_local! {
+    static SHARED: RefCell<Option<ViewportShared>> = const { RefCell::new(None) };
+}
+
+fn schedule_measure() {
+    let scheduled = SHARED.with(|m| match *m.borrow_mut() {
+        Some(ref mut shared) if !shared.measure_pending => {
+            shared.measure_pending = true;
+            true
+        }
+        _ => false,
+    });
+
+    if scheduled {
+        call_after(measure, MEASURE_DELAY);
+    }
+}
+
+fn measure() {
+    let changed = SHARED.with(|m| {
+        // Every subscription may have been dropped since.
+        let mut shared = m.borrow_mut();
+        let shared = shared.as_mut()?;
+        shared.measure_pending = false;
+
+        let state = ViewportState::measure(&shared.probe);
+        if state == shared.state {
+            return None;
+        }
+        shared.state = state;
+
+        let subscribers = shared
+            .subscribers
+            .iter()
+            .map(|(_, m)| m.clone())
+            .collect::<Vec<_>>();
+        Some((state, subscribers))
+    });
+
+    // Called once the state is released, as subscribers may read it or unsubscribe.
+    if let Some((state, subscribers)) = changed {
+        for m in subscribers {
+            m(state);
+        }
+    }
+}
+
+// Publishes the size of the viewport, whether the virtual keyboard is shown, the safe area
+// insets and the breakpoint to the subscriber whenever they change.
+//
+// Listeners are added with the first subscription and removed when the last one is dropped, so
+// components keep it for as long as they are mounted.
+#[derive(Debug)]
+pub(crate) struct Viewport {
+    id: u64,
+}
+
+impl Viewport {
+    pub fn subscribe<F>(on_change: F) -> Self
+    where
+        F: 'static + Fn(ViewportState),
+    {
+        let id = SHARED.with(|m| {
+            let mut shared = m.borrow_mut();
+            let shared = shared.get_or_insert_with(ViewportShared::new);
+
+            let id = shared.next_id;
+            shared.next_id += 1;
+            shared.subscribers.push((id, Rc::new(on_change)));
+            id
+        });
+
+        Self { id }
+    }
+
+    pub fn state(&self) -> ViewportState {
+        SHARED.with(|m| {
+            m.borrow()
+                .as_ref()
+                .expect("Viewport is not available.")
+                .state
+        })
+    }
+}
+
+impl Drop for Viewport {
+    fn drop(&mut self) {
+        // Taken out of the cell first, so listeners are not removed while it is borrowed.
+        let last = SHARED.with(|m| {
+            let mut shared = m.borrow_mut();
+            let is_empty = match *shared {
+                Some(ref mut m) => {
+                    m.subscribers.retain(|(id, _)| *id != self.id);
+                    m.subscribers.is_empty()
+                }
+                None => false,
+            };
+
+            if is_empty {
+                shared.take()
+            } else {
+                None
+            }
+        });
+
+        drop(last);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    fn is_listening() -> bool {
+        SHARED.with(|m| m.borrow().is_some())
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        assert_eq!(Breakpoint::from_width(320.0), Breakpoint::Small);
+        assert_eq!(Breakpoint::from_width(340.0), Breakpoint::Small);
+        assert_eq!(Breakpoint::from_width(768.0), Breakpoint::Medium);
+        assert_eq!(Breakpoint::from_width(1280.0), Breakpoint::Large);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_unsubscribe() {
+        let first = Viewport::subscribe(|_| {});
+        let second = Viewport::subscribe(|_| {});
+        assert!(is_listening());
+        assert_eq!(first.state(), second.state());
+
+        drop(first);
+        assert!(is_listening());
+
+        drop(second);
+        assert!(!is_listening());
+        assert_eq!(
+            document()
+                .get_elements_by_class_name(&SafeAreaProbe.yield_style_class())
+                .length(),
+            0
+        );
+    }
+}
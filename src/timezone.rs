@@ -0,0 +1,285 @@
+use js_sys::{Array, Function, Intl, Object, Reflect};
+use wasm_bindgen::JsCast;
+
+use crate::Result;
+
+// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(if month <= 2 { year - 1 } else { year });
+    let month = i64::from(month);
+    let day = i64::from(day);
+
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// A date and time on the wall clock of a timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct LocalDateTime {
+    pub year: i32,
+    // Starts from 1.
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl LocalDateTime {
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    // 0 for Sunday.
+    pub fn weekday(year: i32, month: u32, day: u32) -> u32 {
+        // 1970-01-01 is a Thursday.
+        (days_from_civil(year, month, day) + 4).rem_euclid(7) as u32
+    }
+
+    // Seconds since epoch if this was in UTC.
+    fn utc_timestamp(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+    }
+
+    // Parses the values of <input type="date"> and <input type="time">.
+    pub fn parse(date: &str, time: &str) -> Option<Self> {
+        let mut date = date.splitn(3, '-').map(|m| m.parse::<u32>().ok());
+        let mut time = time.splitn(3, ':').map(|m| m.parse::<u32>().ok());
+
+        let m = Self {
+            year: date.next()?? as i32,
+            month: date.next()??,
+            day: date.next()??,
+            hour: time.next()??,
+            minute: time.next()??,
+        };
+
+        let valid = (1..=12).contains(&m.month)
+            && (1..=Self::days_in_month(m.year, m.month)).contains(&m.day)
+            && m.hour < 24
+            && m.minute < 60;
+
+        if valid {
+            Some(m)
+        } else {
+            None
+        }
+    }
+
+    pub fn date_str(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    pub fn time_str(&self) -> String {
+        format!("{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+// An IANA timezone, conversions are done by the Intl api of the browser.
+#[derive(Debug, Clone)]
+pub(crate) struct TimeZone {
+    name: String,
+    format: Intl::DateTimeFormat,
+}
+
+impl PartialEq for TimeZone {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl TimeZone {
+    pub fn new<S: AsRef<str>>(name: S) -> Result<Self> {
+        let options = Object::new();
+        for (key, value) in [
+            ("timeZone", name.as_ref()),
+            ("hourCycle", "h23"),
+            ("year", "numeric"),
+            ("month", "numeric"),
+            ("day", "numeric"),
+            ("hour", "numeric"),
+            ("minute", "numeric"),
+        ]
+        .iter()
+        {
+            Reflect::set(&options, &(*key).into(), &(*value).into())?;
+        }
+
+        // Intl.DateTimeFormat throws a RangeError for unknown timezones.
+        let constructor: Function = Reflect::get(&js_sys::global(), &"Intl".into())
+            .and_then(|m| Reflect::get(&m, &"DateTimeFormat".into()))?
+            .unchecked_into();
+        let args = Array::of2(&"en-US".into(), &options);
+        let format = Reflect::construct(&constructor, &args)?.unchecked_into();
+
+        Ok(Self {
+            name: name.as_ref().to_string(),
+            format,
+        })
+    }
+
+    // The timezone the browser is in.
+    pub fn browser() -> Self {
+        let name = Reflect::get(
+            &Intl::DateTimeFormat::new(&Array::new(), &Object::new()).resolved_options(),
+            &"timeZone".into(),
+        )
+        .ok()
+        .and_then(|m| m.as_string())
+        .unwrap_or_else(|| "UTC".to_string());
+
+        Self::new(&name)
+            .or_else(|_| Self::new("UTC"))
+            .expect("UTC is not supported.")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn to_local(&self, timestamp: i64) -> LocalDateTime {
+        let date = js_sys::Date::new(&((timestamp * 1000) as f64).into());
+
+        let mut m = LocalDateTime {
+            year: 1970,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+        };
+
+        for part in self.format.format_to_parts(&date).iter() {
+            let get = |key: &str| {
+                Reflect::get(&part, &key.into())
+                    .ok()
+                    .and_then(|m| m.as_string())
+                    .unwrap_or_default()
+            };
+            let value = get("value").parse::<u32>().unwrap_or(0);
+
+            match get("type").as_str() {
+                "year" => m.year = value as i32,
+                "month" => m.month = value,
+                "day" => m.day = value,
+                // Some browsers use 24 for midnight.
+                "hour" => m.hour = value % 24,
+                "minute" => m.minute = value,
+                _ => {}
+            }
+        }
+
+        m
+    }
+
+    // Seconds to add to UTC to get the wall clock time at the timestamp.
+    fn offset(&self, timestamp: i64) -> i64 {
+        let timestamp = timestamp - timestamp.rem_euclid(60);
+        self.to_local(timestamp).utc_timestamp() - timestamp
+    }
+
+    pub fn to_timestamp(&self, local: LocalDateTime) -> i64 {
+        let guess = local.utc_timestamp();
+        let offset = self.offset(guess);
+        let timestamp = guess - offset;
+
+        // The offset may be different on the other side of a daylight saving transition.
+        let corrected = self.offset(timestamp);
+        if corrected != offset {
+            guess - corrected
+        } else {
+            timestamp
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1970, 1, 2), 1);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(1900, 1, 1), -25_567);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2020, 12, 31), 18_627);
+    }
+
+    #[test]
+    fn test_leap_years() {
+        assert_eq!(LocalDateTime::days_in_month(2020, 2), 29);
+        assert_eq!(LocalDateTime::days_in_month(2021, 2), 28);
+        assert_eq!(LocalDateTime::days_in_month(1900, 2), 28);
+        assert_eq!(LocalDateTime::days_in_month(2000, 2), 29);
+
+        assert_eq!(
+            days_from_civil(2020, 3, 1) - days_from_civil(2020, 2, 28),
+            2
+        );
+        assert_eq!(
+            days_from_civil(1900, 3, 1) - days_from_civil(1900, 2, 28),
+            1
+        );
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(LocalDateTime::weekday(1970, 1, 1), 4);
+        assert_eq!(LocalDateTime::weekday(1969, 12, 28), 0);
+        assert_eq!(LocalDateTime::weekday(2020, 2, 29), 6);
+    }
+
+    #[test]
+    fn test_parse() {
+        let m = LocalDateTime::parse("2020-02-29", "23:59").unwrap();
+        assert_eq!(
+            m,
+            LocalDateTime {
+                year: 2020,
+                month: 2,
+                day: 29,
+                hour: 23,
+                minute: 59,
+            }
+        );
+        assert_eq!(m.date_str(), "2020-02-29");
+        assert_eq!(m.time_str(), "23:59");
+        assert_eq!(m.utc_timestamp(), 1_583_020_740);
+
+        let m = LocalDateTime::parse("1969-12-31", "00:00").unwrap();
+        assert_eq!(m.utc_timestamp(), -86_400);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let cases = &[
+            ("", "12:00"),
+            ("2020-01-01", ""),
+            ("2020-01", "12:00"),
+            ("2020-1a-01", "12:00"),
+            ("2021-02-29", "12:00"),
+            ("2020-13-01", "12:00"),
+            ("2020-00-01", "12:00"),
+            ("2020-01-00", "12:00"),
+            ("2020-01-01", "24:00"),
+            ("2020-01-01", "12:60"),
+            ("2020-01-01", "12"),
+            ("-2020-01-01", "12:00"),
+        ];
+
+        for (date, time) in cases.iter() {
+            assert_eq!(LocalDateTime::parse(date, time), None, "{} {}", date, time);
+        }
+    }
+}
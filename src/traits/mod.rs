@@ -1,3 +1,6 @@
+mod style_registry;
 mod yield_style;
 
+pub(crate) use style_registry::class_name_of;
+pub use style_registry::{style_metrics, StyleMetrics};
 pub use yield_style::YieldStyle;
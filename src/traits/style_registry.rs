@@ -0,0 +1,360 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlStyleElement;
+
+use crate::css::Stylesheet;
+use crate::helpers::{call_after, document};
+
+// Styles are kept while an element in the document has their class, which counts the mounted
+// components using them. Views only return class names, so the count is taken by a sweep that runs
+// after the new styles are rendered.
+const SWEEP_DELAY: Duration = Duration::from_secs(1);
+
+// Styles kept at most, the least recently used ones without elements are evicted beyond it. It is
+// only exceeded while more styles than this are in use at once.
+const MAX_STYLES: usize = 256;
+
+// The page can allow the styles under a Content-Security-Policy of `style-src 'nonce-…'` with
+// <meta name="style-nonce" content="…">, which the server fills with the nonce of each response.
+// See static/README.md.
+const NONCE_META_SELECTOR: &str = "meta[name=\"style-nonce\"]";
+
+// Critical styles written into the page by the prerender tool, with the classes they contain in
+// data-prerendered.
+const PRERENDERED_SELECTOR: &str = "style[data-prerendered]";
+
+type StyleKey = (Cow<'static, str>, Stylesheet);
+
+#[derive(Debug, Clone, PartialEq)]
+struct StyleEntry {
+    class_name: String,
+    // The tick of the registry when the class was last asked for.
+    last_used: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct StyleMetrics {
+    // Styles in the document.
+    pub styles: usize,
+    // Elements using them at the last sweep.
+    pub references: usize,
+    pub created: u64,
+    // By sweeps and evictions.
+    pub removed: u64,
+    pub evictions: u64,
+    pub sweeps: u64,
+}
+
+#[derive(Debug)]
+struct StyleRegistry {
+    styles: HashMap<StyleKey, StyleEntry>,
+    // Incremented on every lookup, to find the least recently used styles.
+    tick: u64,
+    // Classes already styled by the page, which are used as they are.
+    prerendered: HashSet<String>,
+    sweep_scheduled: bool,
+    metrics: StyleMetrics,
+    // Set on every <style> added to the document.
+    nonce: Option<String>,
+}
+
+static REGISTRY: Lazy<Mutex<StyleRegistry>> = Lazy::new(|| {
+    Mutex::new(StyleRegistry {
+        styles: HashMap::new(),
+        tick: 0,
+        prerendered: prerendered_classes(),
+        sweep_scheduled: false,
+        metrics: StyleMetrics::default(),
+        nonce: nonce_from_meta(),
+    })
+});
+
+fn prerendered_classes() -> HashSet<String> {
+    document()
+        .query_selector(PRERENDERED_SELECTOR)
+        .ok()
+        .flatten()
+        .and_then(|m| m.dyn_into::<HtmlStyleElement>().ok())
+        // A style blocked by the Content-Security-Policy has no sheet, its classes are added again.
+        .filter(|m| m.sheet().is_some())
+        .and_then(|m| m.get_attribute("data-prerendered"))
+        .map(|m| m.split_whitespace().map(|m| m.to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn nonce_from_meta() -> Option<String> {
+    document()
+        .query_selector(NONCE_META_SELECTOR)
+        .ok()
+        .flatten()
+        .and_then(|m| m.get_attribute("content"))
+        .filter(|m| !m.is_empty())
+}
+
+// Class names are the element name and the digest of the stylesheet, so the prerender tool can
+// name them without a document.
+pub(crate) fn class_name_of(element_name: &str, stylesheet: &Stylesheet) -> String {
+    format!("{}-{}", element_name, stylesheet.digest())
+}
+
+pub(crate) fn class_name(key: StyleKey) -> String {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.tick += 1;
+    let tick = registry.tick;
+
+    if let Some(m) = registry.styles.get_mut(&key) {
+        m.last_used = tick;
+        return m.class_name.clone();
+    }
+
+    let class_name = class_name_of(&key.0, &key.1);
+    let entry = StyleEntry {
+        class_name: class_name.clone(),
+        last_used: tick,
+    };
+
+    if registry.prerendered.contains(&class_name) {
+        registry.styles.insert(key, entry);
+        registry.metrics.styles = registry.styles.len();
+        return class_name;
+    }
+
+    let document = document();
+    let element = document.create_element("style").unwrap();
+    element.set_attribute("data-style", &class_name).unwrap();
+    if let Some(ref m) = registry.nonce {
+        element.set_attribute("nonce", m).unwrap();
+    }
+    element.set_text_content(Some(&key.1.to_css(&class_name)));
+    document
+        .head()
+        .expect("Head is not available.")
+        .append_child(&element)
+        .unwrap();
+
+    registry.styles.insert(key, entry);
+    registry.metrics.created += 1;
+
+    if registry.styles.len() > MAX_STYLES {
+        registry.evict(&class_name);
+    }
+    registry.metrics.styles = registry.styles.len();
+
+    if !registry.sweep_scheduled {
+        registry.sweep_scheduled = true;
+        call_after(sweep, SWEEP_DELAY);
+    }
+
+    class_name
+}
+
+impl StyleRegistry {
+    // Brings the styles down to MAX_STYLES, except for the one just asked for, which is about to be
+    // rendered.
+    fn evict(&mut self, keep: &str) {
+        let document = document();
+        let prerendered = &self.prerendered;
+        let evictions = least_recently_used(&self.styles, MAX_STYLES, |m| {
+            m == keep
+                || prerendered.contains(m)
+                || document.get_elements_by_class_name(m).length() > 0
+        });
+
+        for key in evictions {
+            if let Some(m) = self.styles.remove(&key) {
+                remove_style_element(&m.class_name);
+                self.metrics.removed += 1;
+                self.metrics.evictions += 1;
+            }
+        }
+
+        if self.styles.len() > MAX_STYLES {
+            log::warn!(
+                "{} styles are in use, which is more than {}.",
+                self.styles.len(),
+                MAX_STYLES
+            );
+        }
+    }
+}
+
+// The least recently used styles which are not in use, as many as there are beyond max.
+fn least_recently_used(
+    styles: &HashMap<StyleKey, StyleEntry>,
+    max: usize,
+    in_use: impl Fn(&str) -> bool,
+) -> Vec<StyleKey> {
+    let excess = styles.len().saturating_sub(max);
+    if excess == 0 {
+        return Vec::new();
+    }
+
+    let mut entries = styles.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(_, m)| m.last_used);
+
+    entries
+        .into_iter()
+        .filter(|(_, m)| !in_use(&m.class_name))
+        .take(excess)
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+fn remove_style_element(class_name: &str) {
+    let selector = format!("style[data-style=\"{}\"]", class_name);
+    if let Ok(Some(m)) = document().query_selector(&selector) {
+        m.remove();
+    }
+}
+
+fn sweep() {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.sweep_scheduled = false;
+
+    let document = document();
+    let mut references = 0;
+    let mut removed = 0;
+
+    let StyleRegistry {
+        styles,
+        prerendered,
+        ..
+    } = &mut *registry;
+
+    styles.retain(|_, m| {
+        let count = document.get_elements_by_class_name(&m.class_name).length() as usize;
+        references += count;
+
+        // Prerendered styles are part of the page and stay.
+        if count > 0 || prerendered.contains(m.class_name.as_str()) {
+            return true;
+        }
+
+        remove_style_element(&m.class_name);
+        removed += 1;
+        false
+    });
+
+    registry.metrics.styles = registry.styles.len();
+    registry.metrics.references = references;
+    registry.metrics.removed += removed;
+    registry.metrics.sweeps += 1;
+
+    if cfg!(debug_assertions) {
+        log::debug!("Swept styles: {:?}", registry.metrics);
+    }
+}
+
+pub fn style_metrics() -> StyleMetrics {
+    REGISTRY.lock().unwrap().metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::css::css;
+
+    fn style_elements() -> usize {
+        let styles = document().get_elements_by_tag_name("style");
+        (0..styles.length())
+            .filter_map(|i| styles.item(i))
+            .filter(|m| m.has_attribute("data-style"))
+            .count()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_sweep() {
+        let class_name = class_name(("fl-test-sweep".into(), css!("color: red;")));
+        let selector = format!("style[data-style=\"{}\"]", class_name);
+        assert!(document().query_selector(&selector).unwrap().is_some());
+
+        // Nothing has the class, so the element is removed with the entry.
+        sweep();
+        assert!(document().query_selector(&selector).unwrap().is_none());
+
+        // Every <style> added is owned by the registry and counted.
+        let registry = REGISTRY.lock().unwrap();
+        let injected = registry
+            .styles
+            .values()
+            .filter(|m| !registry.prerendered.contains(m.class_name.as_str()))
+            .count();
+        assert_eq!(style_elements(), injected);
+        assert_eq!(registry.metrics.styles, registry.styles.len());
+        assert_eq!(
+            registry.metrics.created - registry.metrics.removed,
+            injected as u64
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_evicts_beyond_max() {
+        for i in 0..MAX_STYLES + 10 {
+            class_name((format!("fl-test-evict-{}", i).into(), css!("color: red;")));
+        }
+
+        let registry = REGISTRY.lock().unwrap();
+        assert!(registry.styles.len() <= MAX_STYLES);
+        assert!(registry.metrics.evictions >= 10);
+        // The style asked for last is kept.
+        let last = format!("fl-test-evict-{}-", MAX_STYLES + 9);
+        assert!(registry
+            .styles
+            .values()
+            .any(|m| m.class_name.starts_with(&last)));
+    }
+
+    fn styles(last_used: &[u64]) -> HashMap<StyleKey, StyleEntry> {
+        last_used
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let key = (format!("fl-test-{}", i).into(), css!("color: red;"));
+                let entry = StyleEntry {
+                    class_name: format!("fl-test-{}", i),
+                    last_used: *m,
+                };
+                (key, entry)
+            })
+            .collect()
+    }
+
+    fn evicted(styles: &HashMap<StyleKey, StyleEntry>, keys: Vec<StyleKey>) -> Vec<String> {
+        let mut evicted = keys
+            .iter()
+            .map(|m| styles[m].class_name.clone())
+            .collect::<Vec<_>>();
+        evicted.sort();
+        evicted
+    }
+
+    #[test]
+    fn test_least_recently_used() {
+        let styles = styles(&[5, 1, 4, 2, 3]);
+
+        let keys = least_recently_used(&styles, 3, |_| false);
+        assert_eq!(evicted(&styles, keys), vec!["fl-test-1", "fl-test-3"]);
+
+        assert!(least_recently_used(&styles, 5, |_| false).is_empty());
+        assert!(least_recently_used(&styles, 8, |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_skips_styles_in_use() {
+        let styles = styles(&[5, 1, 4, 2, 3]);
+
+        let keys = least_recently_used(&styles, 3, |m| m == "fl-test-1");
+        assert_eq!(evicted(&styles, keys), vec!["fl-test-3", "fl-test-4"]);
+
+        // Nothing can be evicted when every style is in use.
+        assert!(least_recently_used(&styles, 3, |_| true).is_empty());
+    }
+}
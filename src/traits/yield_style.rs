@@ -1,32 +1,51 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::sync::Mutex;
 
-use css_in_rust::Style;
+use web_sys::HtmlElement;
+use yew::NodeRef;
 
-use once_cell::sync::Lazy;
+use super::style_registry;
+use crate::css::Stylesheet;
 
 pub trait YieldStyle {
     fn element_name(&self) -> Cow<'static, str> {
         "unknown-element".into()
     }
 
-    // Update to yield style string
-    fn yield_style_str(&self) -> Cow<'static, str>;
+    // The stylesheet of the element, written with css! so it is checked at compile time.
+    fn yield_style_str(&self) -> Stylesheet;
 
+    // Values that differ between instances, such as sizes from props.
+    //
+    // They are set on the element as custom properties named `--{element_name}-{name}`, so all
+    // instances share the class of yield_style_str, which reads them with var(). None is the
+    // fallback of var(), the value of an outer element of the same kind is not inherited.
+    fn yield_style_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        Vec::new()
+    }
+
+    // The style is removed from the document once no element has the class.
     fn yield_style_class(&self) -> String {
-        static STYLES: Lazy<Mutex<HashMap<(Cow<'static, str>, Cow<'static, str>), String>>> =
-            Lazy::new(|| Mutex::new(HashMap::with_capacity(1)));
-
-        let k = (self.element_name(), self.yield_style_str());
-        let mut styles = STYLES.lock().unwrap();
-
-        if let Some(v) = styles.get(&k) {
-            v.clone()
-        } else {
-            let v = Style::create(&*k.0, &*k.1).unwrap().get_class_name();
-            styles.insert(k, v.clone());
-            v
+        style_registry::class_name((self.element_name(), self.yield_style_str()))
+    }
+
+    // Sets yield_style_vars on the element, components call it from rendered() with the NodeRef of
+    // their root. Inline style attributes are blocked under a style-src without 'unsafe-inline',
+    // properties set through the CSSOM are not.
+    fn apply_style_vars(&self, node: &NodeRef) {
+        if let Some(m) = node.cast::<HtmlElement>() {
+            self.set_style_vars(&m);
+        }
+    }
+
+    fn set_style_vars(&self, element: &HtmlElement) {
+        let element_name = self.element_name();
+        let style = element.style();
+
+        for (name, value) in self.yield_style_vars() {
+            let property = format!("--{}-{}", element_name, name);
+            // initial makes var() use its fallback.
+            let value = value.as_deref().unwrap_or("initial");
+            style.set_property(&property, value).unwrap();
         }
     }
 }
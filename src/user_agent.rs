@@ -0,0 +1,193 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DeviceKind {
+    Desktop,
+    Tablet,
+    Mobile,
+}
+
+impl DeviceKind {
+    pub fn icon_class(&self) -> &'static str {
+        match self {
+            Self::Desktop => "fas fa-desktop",
+            Self::Tablet => "fas fa-tablet-alt",
+            Self::Mobile => "fas fa-mobile-alt",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UserAgent {
+    pub browser: String,
+    pub os: String,
+    pub device: DeviceKind,
+}
+
+impl UserAgent {
+    pub fn parse(user_agent: &str) -> Self {
+        Self {
+            browser: Self::parse_browser(user_agent),
+            os: Self::parse_os(user_agent),
+            device: Self::parse_device(user_agent),
+        }
+    }
+
+    fn parse_browser(user_agent: &str) -> String {
+        // Order matters, most browsers also claim to be Chrome or Safari.
+        static BROWSERS: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+            vec![
+                ("Edge", Regex::new(r"Edg(?:e|A|iOS)?/(\d+)").unwrap()),
+                ("Opera", Regex::new(r"(?:OPR|Opera)/(\d+)").unwrap()),
+                (
+                    "Samsung Internet",
+                    Regex::new(r"SamsungBrowser/(\d+)").unwrap(),
+                ),
+                ("Firefox", Regex::new(r"(?:Firefox|FxiOS)/(\d+)").unwrap()),
+                ("Chrome", Regex::new(r"(?:Chrome|CriOS)/(\d+)").unwrap()),
+                (
+                    "Safari",
+                    Regex::new(r"Version/(\d+)(?:\.\d+)*.*Safari/").unwrap(),
+                ),
+            ]
+        });
+
+        for (name, re) in BROWSERS.iter() {
+            if let Some(m) = re.captures(user_agent) {
+                return format!("{} {}", name, &m[1]);
+            }
+        }
+
+        "未知瀏覽器".to_string()
+    }
+
+    fn parse_os(user_agent: &str) -> String {
+        static IOS_RE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?i)iP(?:ad|od|hone).*? OS (\d+)").unwrap());
+        static ANDROID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Android (\d+)").unwrap());
+        static WINDOWS_RE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"Windows NT (\d+\.\d+)").unwrap());
+        static MACOS_RE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"Mac OS X (\d+)[_.](\d+)").unwrap());
+
+        if let Some(m) = IOS_RE.captures(user_agent) {
+            let name = if user_agent.contains("iPad") {
+                "iPadOS"
+            } else {
+                "iOS"
+            };
+            return format!("{} {}", name, &m[1]);
+        }
+
+        if let Some(m) = ANDROID_RE.captures(user_agent) {
+            return format!("Android {}", &m[1]);
+        }
+
+        if let Some(m) = WINDOWS_RE.captures(user_agent) {
+            let version = match &m[1] {
+                "10.0" => "10",
+                "6.3" => "8.1",
+                "6.2" => "8",
+                "6.1" => "7",
+                m => m,
+            };
+            return format!("Windows {}", version);
+        }
+
+        if let Some(m) = MACOS_RE.captures(user_agent) {
+            return format!("macOS {}.{}", &m[1], &m[2]);
+        }
+
+        if user_agent.contains("CrOS") {
+            return "Chrome OS".to_string();
+        }
+
+        if user_agent.contains("Linux") {
+            return "Linux".to_string();
+        }
+
+        "未知系統".to_string()
+    }
+
+    fn parse_device(user_agent: &str) -> DeviceKind {
+        if user_agent.contains("iPad") || user_agent.contains("Tablet") {
+            DeviceKind::Tablet
+        } else if user_agent.contains("Mobi") || user_agent.contains("iPhone") {
+            DeviceKind::Mobile
+        } else if user_agent.contains("Android") {
+            // Android tablets do not include "Mobile".
+            DeviceKind::Tablet
+        } else {
+            DeviceKind::Desktop
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cases = &[
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_6) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.0 Safari/605.1.15",
+                "Safari 14",
+                "macOS 10.15",
+                DeviceKind::Desktop,
+            ),
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 14_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/14.0 Mobile/15E148 Safari/604.1",
+                "Safari 14",
+                "iOS 14",
+                DeviceKind::Mobile,
+            ),
+            (
+                "Mozilla/5.0 (iPad; CPU OS 13_3 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/87.0.4280.77 Mobile/15E148 Safari/604.1",
+                "Chrome 87",
+                "iPadOS 13",
+                DeviceKind::Tablet,
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36",
+                "Chrome 87",
+                "Windows 10",
+                DeviceKind::Desktop,
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 11; Pixel 5) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.101 Mobile Safari/537.36",
+                "Chrome 87",
+                "Android 11",
+                DeviceKind::Mobile,
+            ),
+            (
+                "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:83.0) Gecko/20100101 Firefox/83.0",
+                "Firefox 83",
+                "Linux",
+                DeviceKind::Desktop,
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 6.1; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36 Edg/87.0.664.66",
+                "Edge 87",
+                "Windows 7",
+                DeviceKind::Desktop,
+            ),
+            ("curl/7.64.1", "未知瀏覽器", "未知系統", DeviceKind::Desktop),
+            ("", "未知瀏覽器", "未知系統", DeviceKind::Desktop),
+        ];
+
+        for (user_agent, browser, os, device) in cases.iter() {
+            assert_eq!(
+                UserAgent::parse(user_agent),
+                UserAgent {
+                    browser: browser.to_string(),
+                    os: os.to_string(),
+                    device: device.clone(),
+                },
+                "{}",
+                user_agent
+            );
+        }
+    }
+}
@@ -0,0 +1,2 @@
+import init, { startImageWorker } from "./office.js"
+init().then(() => startImageWorker());